@@ -0,0 +1,55 @@
+use std::time::{Duration, Instant};
+
+use cgraph::{
+    app::{Window, WindowContext},
+    object::primitives::{Color, Position, Size, create_circle, create_quad},
+};
+use glam::Vec2;
+
+struct Start(Instant);
+
+fn animate(context: &mut WindowContext, time: Instant) {
+    let elapsed = match context.objects.borrow::<Start>() {
+        Some(start) => time.duration_since(start.0).as_secs_f32(),
+        None => return,
+    };
+    if let Some(circle) = context.renderer.objects_mut().get_mut(0) {
+        circle.position.x = 400.0 + elapsed.sin() * 200.0;
+    }
+    context.request_animation_frame(animate);
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::new("Animation", 800, 600, None)?;
+
+    win.add_object(create_circle(
+        Size::new(100.0, 100.0),
+        Color::new(0.2, 0.6, 1.0, 1.0),
+        1.0,
+        Position::new(400.0, 300.0),
+    ));
+    win.add_object(create_quad(
+        Size::new(2.0, 20.0),
+        Color::new(1.0, 1.0, 1.0, 1.0),
+        1.0,
+        Position::new(100.0, 100.0),
+    ));
+
+    win.share_object(Start(Instant::now()));
+    win.request_animation_frame(animate);
+
+    // A blinking cursor only needs a redraw twice a second.
+    let mut visible = true;
+    win.set_interval(Duration::from_millis(500), move |context| {
+        visible = !visible;
+        let scale = if visible { 1.0 } else { 0.0 };
+        if let Some(cursor) = context.renderer.objects_mut().get_mut(1) {
+            cursor.set_scale(Vec2::new(scale, scale));
+        }
+        context.request_redraw();
+    });
+
+    win.launch()?;
+
+    Ok(())
+}
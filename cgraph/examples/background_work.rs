@@ -0,0 +1,36 @@
+use std::{thread, time::Duration};
+
+use cgraph::app::{Propagation, Window};
+
+enum Indexing {
+    Progress(u32),
+    Done,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::new("Background Work", 800, 600, None)?;
+
+    win.on_user_event(|_, context, message: &mut Indexing| {
+        match message {
+            Indexing::Progress(percent) => context.set_title(&format!("Indexing... {percent}%")),
+            Indexing::Done => context.set_title("Indexing done"),
+        }
+        Propagation::Stop
+    })
+    .detach();
+
+    let sender = win.event_sender::<Indexing>();
+    thread::spawn(move || {
+        for percent in (0..100).step_by(10) {
+            thread::sleep(Duration::from_millis(200));
+            if sender.send(Indexing::Progress(percent)).is_err() {
+                return;
+            }
+        }
+        let _ = sender.send(Indexing::Done);
+    });
+
+    win.launch()?;
+
+    Ok(())
+}
@@ -3,8 +3,8 @@ use cgraph::{
     object::primitives::{Color, Position, Size, create_circle},
 };
 
-fn main() {
-    let mut win = Window::new("Circle", 800, 600, None);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::new("Circle", 800, 600, None)?;
 
     win.add_object(create_circle(
         Size::new(1000.0, 1000.0),
@@ -20,5 +20,7 @@ fn main() {
         Position::new(0.0, 0.0),
     ));
 
-    win.launch();
+    win.launch()?;
+
+    Ok(())
 }
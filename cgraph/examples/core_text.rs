@@ -6,20 +6,21 @@ use cgraph::{
 };
 use glam::Vec2;
 
-fn main() {
-    let mut win = Window::new("Window", 800, 600, None);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::new("Window", 800, 600, None)?;
 
-    let font = get_font(&win, "BIZ UDMincho", 18.0).unwrap();
+    let font = get_font(&win, "BIZ UDMincho", 18.0)?;
     let object = make_text(
         font,
         "おはよう!",
         Color::new(0.0, 1.0, 0.0, 1.0),
         3.0,
         Vec2::new(0.0, 0.0),
-    )
-    .unwrap();
+    )?;
 
     win.add_object(object);
 
-    win.launch();
+    win.launch()?;
+
+    Ok(())
 }
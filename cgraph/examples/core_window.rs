@@ -1,14 +1,33 @@
 use cgraph::{
     self,
-    app::{CoreEvent, CoreEventReference, CoreWindowEvent, Window},
+    app::{CoreEvent, CoreEventReference, CoreWindowEvent, MouseEvent, Propagation, Window},
 };
 
-fn main() {
-    let mut win = Window::new("Window", 800, 600, None);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::new("Window", 800, 600, None)?;
     win.on_event(CoreEventReference::WindowEvent, |_, event| {
         if let CoreEvent::WindowEvent(CoreWindowEvent::KeyboardInput(input)) = event {
             println!("Key pressed: {input:?}");
         }
     });
-    win.launch();
+
+    // Consumes Escape so the generic handler above never sees it.
+    let _escape = win.on_key_with_priority(10, |_, _, event| {
+        if event.key == Some(winit::event::VirtualKeyCode::Escape) {
+            println!("Escape consumed");
+            return Propagation::Stop;
+        }
+        Propagation::Continue
+    });
+
+    let _clicks = win.on_mouse(|_, _, event| {
+        if let MouseEvent::Pressed { button, x, y } = event {
+            println!("{button:?} pressed at {x}, {y}");
+        }
+        Propagation::Continue
+    });
+
+    win.launch()?;
+
+    Ok(())
 }
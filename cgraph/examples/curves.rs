@@ -1,13 +1,14 @@
 use cgraph::{
     app::Window,
     object::{
+        curve::Flattening,
         curve_primitives::*,
         primitives::{Color, Position},
     },
 };
 
-fn main() {
-    let mut win = Window::new("Curve Examples", 800, 600, None);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::new("Curve Examples", 800, 600, None)?;
 
     // Create a quadratic Bézier curve
     win.add_object(create_quadratic_bezier(
@@ -17,7 +18,7 @@ fn main() {
         Color::new(1.0, 0.0, 0.0, 1.0), // red
         1.0,                            // z_index
         3.0,                            // line_width
-        50,                             // steps
+        Some(50),                       // steps
     ));
 
     win.add_object(create_cubic_bezier(
@@ -30,7 +31,7 @@ fn main() {
         Color::new(0.0, 1.0, 0.0, 1.0), // green
         1.0,                            // z_index
         3.0,                            // line_width
-        50,                             // steps
+        Some(50),                       // steps
     ));
 
     win.add_object(create_arc(
@@ -43,7 +44,7 @@ fn main() {
         Color::new(0.0, 0.0, 1.0, 1.0), // blue
         1.0,                            // z_index
         3.0,                            // line_width
-        50,                             // steps
+        Some(50),                       // steps
     ));
 
     // Add a full circle using create_circle_arc
@@ -53,7 +54,7 @@ fn main() {
         Color::new(1.0, 0.5, 0.0, 1.0), // orange
         1.0,                            // z_index
         2.0,                            // line_width
-        60,                             // steps
+        None,                           // adaptive steps
     ));
 
     win.add_object(create_heart_shape(
@@ -62,7 +63,7 @@ fn main() {
         Color::new(1.0, 0.0, 1.0, 1.0), // magenta
         1.0,                            // z_index
         2.0,                            // line_width
-        100,                            // steps
+        None,                           // adaptive steps
     ));
 
     win.add_object(create_star_shape(
@@ -75,7 +76,7 @@ fn main() {
         Color::new(1.0, 1.0, 0.0, 1.0), // yellow
         1.0,                            // z_index
         2.0,                            // line_width
-        20,                             // steps
+        Some(20),                       // steps
     ));
 
     let path_points = vec![
@@ -91,7 +92,7 @@ fn main() {
         Color::new(0.5, 0.8, 0.2, 1.0), // lime green
         1.0,                            // z_index
         2.0,                            // line_width
-        30,                             // steps
+        Some(30),                       // steps
     ));
 
     win.add_object(
@@ -117,9 +118,11 @@ fn main() {
                 Color::new(0.8, 0.4, 0.8, 1.0), // purple
                 1.0,                            // z_index
                 2.5,                            // line_width
-                40,                             // steps
+                Flattening::new(0.1),           // tolerance
             ),
     );
 
-    win.launch();
+    win.launch()?;
+
+    Ok(())
 }
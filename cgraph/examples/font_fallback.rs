@@ -0,0 +1,30 @@
+use cfont::font::fallback::set_fallback_families;
+use cgraph::{
+    self,
+    app::Window,
+    object::primitives::Color,
+    text::{get_font, make_text},
+};
+use glam::Vec2;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::new("Font Fallback", 800, 600, None)?;
+
+    // Tried before the system fallbacks for each script
+    set_fallback_families(&["Noto Sans Symbols 2"]);
+
+    let font = get_font(&win, "Menlo", 18.0)?;
+    let object = make_text(
+        font,
+        "let greeting = \"こんにちは 🦀 ✓ → ∑\";",
+        Color::new(1.0, 1.0, 1.0, 1.0),
+        1.0,
+        Vec2::new(20.0, 20.0),
+    )?;
+
+    win.add_object(object);
+
+    win.launch()?;
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use cfont::font::registry::{register_font_file, registered_families};
+use cgraph::app::Window;
+use cgraph::object::primitives::Color;
+use cgraph::text::*;
+use glam::Vec2;
+
+// Run with the path of a `.ttf`, `.otf` or `.ttc` file to draw text with a font that isn't installed.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::args()
+        .nth(1)
+        .ok_or("Pass the path of a font file")?;
+    register_font_file(&path)?;
+
+    let mut window = Window::new("Font File", 800, 600, None)?;
+
+    // Family lookups find the registered fonts before the system ones.
+    for (index, family) in registered_families().iter().enumerate() {
+        let style = TextStyleBuilder::new(family, 24.0).build_font(&window)?;
+        let text_object = make_styled_text(
+            style,
+            family,
+            Color::new(1.0, 1.0, 1.0, 1.0),
+            1.0,
+            Vec2::new(40.0, 40.0 + index as f32 * 40.0),
+        )?;
+        window.add_object(text_object);
+    }
+
+    window.launch()?;
+
+    Ok(())
+}
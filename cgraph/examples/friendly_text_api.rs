@@ -4,7 +4,7 @@ use cgraph::text::*;
 use glam::Vec2;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let mut window = Window::new("Hello", 800, 600, None);
+    let mut window = Window::new("Hello", 800, 600, None)?;
 
     let style = TextStyleBuilder::new("Arial", 24.0)
         .italic()
@@ -22,7 +22,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     window.add_object(text_object);
 
-    window.launch();
+    window.launch()?;
 
     Ok(())
 }
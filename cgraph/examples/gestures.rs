@@ -0,0 +1,51 @@
+use cgraph::{
+    app::{CursorIcon, GestureEvent, Propagation, Window},
+    object::primitives::{Color, Position, Size, create_rounded_quad},
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::new("Gestures", 800, 600, None)?;
+
+    win.add_object(
+        create_rounded_quad(
+            Size::new(100.0, 100.0),
+            Color::new(0.2, 0.5, 1.0, 1.0),
+            1.0,
+            Position::new(150.0, 100.0),
+            12.0,
+        )
+        .with_cursor(CursorIcon::Pointer),
+    );
+    win.add_object(
+        create_rounded_quad(
+            Size::new(100.0, 100.0),
+            Color::new(1.0, 0.5, 0.2, 1.0),
+            1.0,
+            Position::new(450.0, 100.0),
+            12.0,
+        )
+        .with_cursor(CursorIcon::Grab),
+    );
+
+    win.on_gesture(|_, context, event| {
+        let title = match event {
+            GestureEvent::Click { count, .. } => format!("Clicked {count} times"),
+            GestureEvent::DragStart { .. } => {
+                context.set_cursor(CursorIcon::Grabbing);
+                "Dragging".to_string()
+            }
+            GestureEvent::DragMove { dx, dy, .. } => format!("Dragged by {dx:.0}, {dy:.0}"),
+            GestureEvent::DragEnd { .. } => "Dropped".to_string(),
+            GestureEvent::HoverEnter { object, .. } => format!("Over object {object}"),
+            GestureEvent::HoverLeave { .. } => "Gestures".to_string(),
+            GestureEvent::Wheel { lines, .. } => format!("Scrolled {:.1} lines", lines.1),
+        };
+        context.set_title(&title);
+        Propagation::Continue
+    })
+    .detach();
+
+    win.launch()?;
+
+    Ok(())
+}
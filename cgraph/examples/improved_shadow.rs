@@ -7,8 +7,8 @@ use cgraph::{
 };
 use glam::Vec2;
 
-fn main() {
-    let mut win = Window::new("Improved Shadows", 1200, 800, None);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::new("Improved Shadows", 1200, 800, None)?;
 
     let object1 = create_quad_with_shadow(
         Size::new(200.0, 150.0),
@@ -87,5 +87,7 @@ fn main() {
     win.add_object(object4);
     win.add_object(object5);
 
-    win.launch();
+    win.launch()?;
+
+    Ok(())
 }
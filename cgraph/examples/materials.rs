@@ -0,0 +1,41 @@
+use cgraph::{
+    app::{SHADER_DIR, Window},
+    object::{
+        material::Material,
+        primitives::{Color, Position, Size, create_quad},
+    },
+};
+
+// Colors each indent guide by its depth, cycling through the rainbow.
+const RAINBOW: &str = r#"
+fragment float4 rainbow_guide(VertexOut in [[stage_in]], constant float* params [[buffer(4)]]) {
+    float hue = fract(params[0] / params[1]);
+    float3 rgb = clamp(abs(fmod(hue * 6.0 + float3(0.0, 4.0, 2.0), 6.0) - 3.0) - 1.0, 0.0, 1.0);
+    return float4(rgb, params[2]);
+}
+"#;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::new("Materials", 800, 600, None)?;
+
+    for depth in 0..6 {
+        let material =
+            Material::new("rainbow_guide", RAINBOW).with_uniforms(&[depth as f32, 6.0, 0.6]);
+        win.add_object(
+            create_quad(
+                Size::new(2.0, 400.0),
+                Color::new(1.0, 1.0, 1.0, 1.0),
+                1.0,
+                Position::new(100.0 + depth as f32 * 40.0, 100.0),
+            )
+            .with_material(material),
+        );
+    }
+
+    // Edit the files in `cgraph/metal` while the example runs to see the changes live.
+    win.watch_shaders(SHADER_DIR);
+
+    win.launch()?;
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use cgraph::{
+    app::{Application, Propagation},
+    object::primitives::Color,
+};
+use winit::event::VirtualKeyCode;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = Application::new()?;
+
+    let mut editor = app.create_window("Editor", 800, 600, None)?;
+    editor
+        .on_key(|_, context, event| {
+            if event.is_pressed() && event.key == Some(VirtualKeyCode::N) {
+                context.open_window("Panel", 300, 400, None, (), |panel| {
+                    panel.set_background_color(Color::new(0.15, 0.15, 0.2, 1.0));
+                    panel
+                        .on_key(|_, context, event| {
+                            if event.is_pressed() && event.key == Some(VirtualKeyCode::Escape) {
+                                context.close();
+                                return Propagation::Stop;
+                            }
+                            Propagation::Continue
+                        })
+                        .detach();
+                });
+                return Propagation::Stop;
+            }
+            Propagation::Continue
+        })
+        .detach();
+    app.add_window(editor);
+
+    let settings = app.create_window("Settings", 400, 300, None)?;
+    app.add_window(settings);
+
+    app.run()
+}
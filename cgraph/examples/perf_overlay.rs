@@ -0,0 +1,53 @@
+use std::time::{Duration, Instant};
+
+use cgraph::{
+    app::{Window, WindowContext},
+    object::primitives::{Color, Position, Size, create_circle},
+};
+
+struct Start(Instant);
+
+fn animate(context: &mut WindowContext, time: Instant) {
+    let elapsed = match context.objects.borrow::<Start>() {
+        Some(start) => time.duration_since(start.0).as_secs_f32(),
+        None => return,
+    };
+    for (index, circle) in context.renderer.objects_mut().iter_mut().enumerate() {
+        circle.position.y = 300.0 + (elapsed * 2.0 + index as f32 * 0.3).sin() * 150.0;
+    }
+    context.request_animation_frame(animate);
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::new("Performance Overlay", 800, 600, None)?;
+
+    for index in 0..20 {
+        win.add_object(create_circle(
+            Size::new(20.0, 20.0),
+            Color::new(0.2, 0.6, 1.0, 1.0),
+            1.0,
+            Position::new(40.0 + index as f32 * 36.0, 300.0),
+        ));
+    }
+
+    win.show_performance_overlay(true);
+    win.share_object(Start(Instant::now()));
+    win.request_animation_frame(animate);
+
+    win.set_interval(Duration::from_secs(1), |context| {
+        let stats = context.renderer.frame_stats();
+        println!(
+            "cpu {:?}, gpu {:?}, {} draw calls, {} objects, {} vertices, {} buffers",
+            stats.cpu_time,
+            stats.gpu_time,
+            stats.draw_calls,
+            stats.objects,
+            stats.vertices,
+            stats.buffer_allocations
+        );
+    });
+
+    win.launch()?;
+
+    Ok(())
+}
@@ -3,8 +3,8 @@ use cgraph::{
     object::primitives::{Color, Position, Size, create_polygon},
 };
 
-fn main() {
-    let mut win = Window::new("Polygons", 800, 600, None);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::new("Polygons", 800, 600, None)?;
 
     win.add_object(create_polygon(
         Size::new(1000.0, 1000.0),
@@ -22,5 +22,7 @@ fn main() {
         8,
     ));
 
-    win.launch();
+    win.launch()?;
+
+    Ok(())
 }
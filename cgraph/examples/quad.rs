@@ -3,8 +3,8 @@ use cgraph::{
     object::primitives::{Color, Position, Size, create_quad, create_rounded_quad},
 };
 
-fn main() {
-    let mut win = Window::new("Quad", 800, 600, None);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::new("Quad", 800, 600, None)?;
 
     win.add_object(create_quad(
         Size::new(800.0, 600.0),
@@ -21,5 +21,7 @@ fn main() {
         20.0,
     ));
 
-    win.launch();
+    win.launch()?;
+
+    Ok(())
 }
@@ -0,0 +1,45 @@
+use cgraph::app::{MouseEvent, Propagation, Recording, Window};
+
+#[derive(Default)]
+struct Counter {
+    clicks: u32,
+}
+
+fn setup(window: &mut Window<Counter>) {
+    window
+        .on_mouse(|counter, context, event| {
+            if let MouseEvent::Pressed { .. } = event {
+                counter.clicks += 1;
+                context.set_title(&format!("Clicked {} times", counter.clicks));
+            }
+            Propagation::Continue
+        })
+        .detach();
+}
+
+// Run with `record <file>` to record a session, then `replay <file>` to replay it without a window.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+    match (args.get(1).map(String::as_str), args.get(2)) {
+        (Some("replay"), Some(path)) => {
+            let recording = Recording::load(path)?;
+            let mut window = Window::headless("Replay", 800, 600, Counter::default());
+            setup(&mut window);
+            let end = window.replay(&recording);
+            println!(
+                "Replayed {} events ({end:?}), {} clicks",
+                recording.events.len(),
+                window.state().clicks
+            );
+        }
+        (Some("record"), Some(path)) => {
+            let mut window = Window::with_state("Recording", 800, 600, None, Counter::default())?;
+            setup(&mut window);
+            window.record_events(path)?;
+            window.launch()?;
+        }
+        _ => eprintln!("Usage: record_replay record|replay <file>"),
+    }
+
+    Ok(())
+}
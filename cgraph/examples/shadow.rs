@@ -3,8 +3,8 @@ use cgraph::{
     object::primitives::{Color, Position, Size, create_quad, create_rounded_quad},
 };
 
-fn main() {
-    let mut win = Window::new("Quad", 800, 600, None);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::new("Quad", 800, 600, None)?;
 
     let mut object = create_quad(
         Size::new(1000.0, 1000.0),
@@ -31,5 +31,7 @@ fn main() {
     win.add_object(object);
     win.add_object(object2);
 
-    win.launch();
+    win.launch()?;
+
+    Ok(())
 }
@@ -4,8 +4,8 @@ use cgraph::{
 };
 use glam::Vec2;
 
-fn main() {
-    let mut win = Window::new("Dynamic Shadow Control", 800, 600, None);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::new("Dynamic Shadow Control", 800, 600, None)?;
 
     let mut object = create_quad(
         Size::new(200.0, 200.0),
@@ -18,5 +18,7 @@ fn main() {
 
     win.add_object(object);
 
-    win.launch();
+    win.launch()?;
+
+    Ok(())
 }
@@ -0,0 +1,35 @@
+use cgraph::{
+    app::{MouseEvent, Propagation, Window},
+    object::primitives::{Color, Position, Size, create_circle},
+};
+
+struct Counter {
+    clicks: u32,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::with_state("State", 800, 600, None, Counter { clicks: 0 })?;
+
+    let _clicks = win.on_mouse(|counter, context, event| {
+        if let MouseEvent::Pressed { x, y, .. } = event {
+            counter.clicks += 1;
+            context.renderer.add_object(create_circle(
+                Size::new(20.0, 20.0),
+                Color::new(1.0, 0.5, 0.0, 1.0),
+                1.0,
+                Position::new(*x as f32, *y as f32),
+            ));
+            context.request_redraw();
+            return Propagation::Stop;
+        }
+        Propagation::Continue
+    });
+
+    win.each_frame(|counter, context| {
+        context.set_title(&format!("Clicked {} times", counter.clicks));
+    });
+
+    win.launch()?;
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use cgraph::app::{KeyChord, Propagation, TextInputEvent, Window};
+
+struct Editor {
+    text: String,
+    preedit: String,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::with_state(
+        "Text Input",
+        800,
+        600,
+        None,
+        Editor {
+            text: String::new(),
+            preedit: String::new(),
+        },
+    )?;
+    win.set_ime_allowed(true);
+
+    let clear: KeyChord = "CmdOrCtrl+Shift+K".parse().unwrap();
+    win.on_text_input(move |editor, context, event| {
+        match event {
+            TextInputEvent::Key(key) if clear.matches(key) => editor.text.clear(),
+            TextInputEvent::Commit(text) => {
+                editor.text.push_str(text);
+                editor.preedit.clear();
+            }
+            TextInputEvent::Preedit { text, .. } => editor.preedit = text.clone(),
+            _ => return Propagation::Continue,
+        }
+        context.set_title(&format!("{}[{}]", editor.text, editor.preedit));
+        Propagation::Stop
+    })
+    .detach();
+
+    win.launch()?;
+
+    Ok(())
+}
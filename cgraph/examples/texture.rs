@@ -4,8 +4,8 @@ use cgraph::{
     object::primitives::{Color, Position, Size, create_rounded_quad},
 };
 
-fn main() {
-    let mut win = Window::new("Texture", 800, 600, None);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut win = Window::new("Texture", 800, 600, None)?;
 
     let mut object = create_rounded_quad(
         Size::new(1000.0, 1000.0),
@@ -15,11 +15,12 @@ fn main() {
         50.0,
     );
 
-    object = object.with_texture(
-        Image::new(&format!("{}/assets/wall.jpg", env!("CARGO_MANIFEST_DIR"))).unwrap(),
-    );
+    let image = Image::new(&format!("{}/assets/wall.jpg", env!("CARGO_MANIFEST_DIR")))?;
+    object = object.with_texture(image);
 
     win.add_object(object);
 
-    win.launch();
+    win.launch()?;
+
+    Ok(())
 }
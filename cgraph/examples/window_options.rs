@@ -0,0 +1,17 @@
+use cgraph::app::{Window, WindowOptions};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let options = WindowOptions::new()
+        .with_min_size(400, 300)
+        .with_max_size(1600, 1200)
+        .with_position(100, 100)
+        .with_always_on_top(true);
+    let mut win = Window::new("Window Options", 800, 600, Some(options))?;
+
+    // Reopens where the window was last closed.
+    win.remember_geometry("window_options.geometry");
+
+    win.launch()?;
+
+    Ok(())
+}
@@ -22,8 +22,23 @@ struct Uniforms {
     float4x4 model_matrix;
     float4x4 projection_matrix;
     bool use_texture;
+    float shadow_radius;
+    float4 shadow_color;
+    float4 corner_radii;
+    uint shape;
+    uint point_count;
+    float border_width;
+    float border_outset;
+    float4 border_color;
+    float2 border_dash;
+    float2 vertex_outset;
+    float2 uv_outset;
 };
 
+constant uint SHAPE_RECT = 0;
+constant uint SHAPE_ELLIPSE = 1;
+constant uint SHAPE_POLYGON = 2;
+
 struct ShadowUniforms {
     float offset_x;
     float offset_y;
@@ -35,9 +50,12 @@ struct ShadowUniforms {
 vertex VertexOut vertex_main(VertexIn in [[stage_in]], constant Uniforms& uniforms [[buffer(1)]]) {
     VertexOut out;
     float depth = (0 + in.zIndex) / 50;
-    out.position = uniforms.projection_matrix * uniforms.model_matrix * float4(in.position, depth, 1.0);
+    // Grow quads outwards so borders drawn outside the edge have room; UVs grow upwards.
+    float2 direction = sign(in.uv - 0.5);
+    float2 position = in.position + float2(direction.x, -direction.y) * uniforms.vertex_outset;
+    out.position = uniforms.projection_matrix * uniforms.model_matrix * float4(position, depth, 1.0);
     out.color = in.color;
-    out.uv = in.uv;
+    out.uv = in.uv + direction * uniforms.uv_outset;
     return out;
 }
 
@@ -46,15 +64,101 @@ float rounded_rect_sdf(float2 p, float2 size, float corner_radius) {
     return length(max(d, 0.0)) + min(max(d.x, d.y), 0.0) - corner_radius;
 }
 
-float calculate_shadow_expanded(float2 uv, float2 original_rect_size, float corner_radius, float blur_radius) {
+// Radii are ordered top-left, top-right, bottom-right, bottom-left; y points up.
+float rounded_rect_corners_sdf(float2 p, float2 size, float4 radii) {
+    float r = p.y > 0.0 ? (p.x > 0.0 ? radii.y : radii.x) : (p.x > 0.0 ? radii.z : radii.w);
+    r = min(r, min(size.x, size.y));
+    return rounded_rect_sdf(p, size, r);
+}
+
+float ellipse_sdf(float2 p, float2 size) {
+    float k1 = length(p / size);
+    float k2 = length(p / (size * size));
+    return k2 > 0.0 ? k1 * (k1 - 1.0) / k2 : -min(size.x, size.y);
+}
+
+float polygon_sdf(float2 p, constant float2* points, uint count) {
+    float d = dot(p - points[0], p - points[0]);
+    float s = 1.0;
+    for (uint i = 0, j = count - 1; i < count; j = i, i++) {
+        float2 e = points[j] - points[i];
+        float2 w = p - points[i];
+        float2 b = w - e * clamp(dot(w, e) / dot(e, e), 0.0, 1.0);
+        d = min(d, dot(b, b));
+        bool above = p.y >= points[i].y;
+        bool below = p.y < points[j].y;
+        bool left = e.x * w.y > e.y * w.x;
+        if ((above && below && left) || (!above && !below && !left)) {
+            s = -s;
+        }
+    }
+    return s * sqrt(d);
+}
+
+float shape_sdf(float2 p, float2 size, constant Uniforms& uniforms, constant float2* points) {
+    float2 half_size = size * 0.5;
+    if (uniforms.shape == SHAPE_ELLIPSE) {
+        return ellipse_sdf(p, half_size);
+    }
+    if (uniforms.shape == SHAPE_POLYGON && uniforms.point_count >= 3) {
+        return polygon_sdf(p / size, points, uniforms.point_count) * min(size.x, size.y) - uniforms.corner_radius;
+    }
+    return rounded_rect_corners_sdf(p, half_size, uniforms.corner_radii);
+}
+
+bool has_shape(constant Uniforms& uniforms) {
+    return uniforms.shape != SHAPE_RECT || any(uniforms.corner_radii > 0.0) || uniforms.border_width > 0.0;
+}
+
+// Distance travelled along the outline up to the point closest to p, used to place dashes.
+float outline_position(float2 p, float2 size, constant Uniforms& uniforms, constant float2* points) {
+    float2 h = size * 0.5;
+    if (uniforms.shape == SHAPE_ELLIPSE) {
+        float perimeter = M_PI_F * (3.0 * (h.x + h.y) - sqrt((3.0 * h.x + h.y) * (h.x + 3.0 * h.y)));
+        float angle = atan2(p.y / h.y, p.x / h.x);
+        return (angle + M_PI_F) / (2.0 * M_PI_F) * perimeter;
+    }
+    if (uniforms.shape == SHAPE_POLYGON && uniforms.point_count >= 3) {
+        float best = 1e20;
+        float position = 0.0;
+        float travelled = 0.0;
+        for (uint i = 0; i < uniforms.point_count; i++) {
+            float2 a = points[i] * size;
+            float2 e = points[(i + 1) % uniforms.point_count] * size - a;
+            float t = clamp(dot(p - a, e) / max(dot(e, e), 1e-6), 0.0, 1.0);
+            float d = length(p - a - e * t);
+            if (d < best) {
+                best = d;
+                position = travelled + t * length(e);
+            }
+            travelled += length(e);
+        }
+        return position;
+    }
+    // Walk the rectangle clockwise from its top-left corner.
+    float2 edge = h - abs(p);
+    if (edge.y < edge.x) {
+        return p.y > 0.0 ? p.x + h.x : 3.0 * h.x + 2.0 * h.y - p.x;
+    }
+    return p.x > 0.0 ? 2.0 * h.x + h.y - p.y : 4.0 * h.x + 3.0 * h.y + p.y;
+}
+
+float dash_mask(float position, float2 dash) {
+    float period = dash.x + dash.y;
+    if (dash.x <= 0.0 || dash.y <= 0.0) {
+        return 1.0;
+    }
+    return fmod(position, period) < dash.x ? 1.0 : 0.0;
+}
+
+float calculate_shadow_expanded(float2 uv, float2 original_rect_size, constant Uniforms& uniforms, constant float2* points, float blur_radius) {
     float expansion = blur_radius;
     
     float2 expanded_size = original_rect_size + float2(expansion * 2.0, expansion * 2.0);
     
     float2 expanded_pos = (uv - 0.5) * expanded_size;
     
-    float2 half_original_size = original_rect_size * 0.5;
-    float dist = rounded_rect_sdf(expanded_pos, half_original_size, corner_radius);
+    float dist = shape_sdf(expanded_pos, original_rect_size, uniforms, points);
     
     float shadow_alpha = 1.0 - smoothstep(-blur_radius, blur_radius, dist);
     
@@ -65,13 +169,15 @@ fragment float4 fragment_main(VertexOut in [[stage_in]],
                              constant Uniforms& uniforms [[buffer(0)]],
                              texture2d<float> tex [[texture(0)]],
                              sampler texSampler [[sampler(0)]],
-                             constant ShadowUniforms& shadowUniforms [[buffer(2)]]) {
+                             constant ShadowUniforms& shadowUniforms [[buffer(2)]],
+                             constant float2* shapePoints [[buffer(3)]]) {
     
     if (shadowUniforms.enabled) {
         float shadow_alpha = calculate_shadow_expanded(
             in.uv, 
             uniforms.rect_size, 
-            uniforms.corner_radius, 
+            uniforms, 
+            shapePoints, 
             shadowUniforms.radius
         );
         
@@ -93,13 +199,29 @@ fragment float4 fragment_main(VertexOut in [[stage_in]],
         final_color = in.color;
     }
     
-    if (uniforms.corner_radius > 0.0) {
+    if (has_shape(uniforms)) {
         float2 local_pos = (in.uv - 0.5) * uniforms.rect_size;
-        float2 half_size = uniforms.rect_size * 0.5;
         
-        float dist = rounded_rect_sdf(local_pos, half_size, uniforms.corner_radius);
+        float dist = shape_sdf(local_pos, uniforms.rect_size, uniforms, shapePoints);
         
         float alpha = 1.0 - smoothstep(-1.0, 1.0, dist);
+        
+        if (uniforms.border_width > 0.0) {
+            float outer = uniforms.border_outset;
+            float inner = outer - uniforms.border_width;
+            float band = smoothstep(-0.5, 0.5, dist - inner) * (1.0 - smoothstep(-0.5, 0.5, dist - outer));
+            band *= dash_mask(outline_position(local_pos, uniforms.rect_size, uniforms, shapePoints), uniforms.border_dash);
+            
+            float border_alpha = uniforms.border_color.a * band;
+            float fill_alpha = final_color.a * alpha;
+            float out_alpha = border_alpha + fill_alpha * (1.0 - border_alpha);
+            if (out_alpha <= 0.0) {
+                discard_fragment();
+            }
+            float3 rgb = (uniforms.border_color.rgb * border_alpha + final_color.rgb * fill_alpha * (1.0 - border_alpha)) / out_alpha;
+            return float4(rgb, out_alpha);
+        }
+        
         if (alpha <= 0.0) {
             discard_fragment();
         }
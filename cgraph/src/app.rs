@@ -1,5 +1,27 @@
+mod application;
+mod events;
+mod geometry;
+mod gestures;
+mod keyboard;
+mod keymap;
+mod overlay;
+mod recording;
+mod shader_watcher;
 mod shared_objects;
+mod timers;
 mod window;
 
+pub use application::*;
+pub use events::*;
+pub use geometry::*;
+pub use gestures::{CursorIcon, GestureEvent, GestureSettings};
+pub use keyboard::*;
+pub use keymap::*;
+pub use recording::{
+    RecordedDeviceEvent, RecordedEvent, RecordedWindowEvent, Recording, RecordingError, ReplayEnd,
+    TimedEvent,
+};
+pub use shader_watcher::SHADER_DIR;
 pub use shared_objects::*;
+pub use timers::TimerId;
 pub use window::*;
@@ -0,0 +1,298 @@
+use std::time::Instant;
+
+use winit::{
+    event::{DeviceEvent, Event, WindowEvent},
+    event_loop::{ControlFlow, EventLoop, EventLoopBuilder, EventLoopProxy, EventLoopWindowTarget},
+    window::WindowId,
+};
+
+use crate::{
+    app::{
+        EventSender, Propagation, Window, WindowError, WindowOptions,
+        events::{LoopMessage, MessageSink, UserMessage},
+    },
+    renderer::{RenderDevice, RenderError},
+};
+
+/// A window driven by an application's event loop, whatever the type of its state.
+pub(crate) trait ManagedWindow {
+    /// Returns the id of the native window.
+    fn id(&self) -> WindowId;
+    /// Handles an event sent to this window.
+    fn handle_window_event(&mut self, event: &WindowEvent);
+    /// Handles an event coming from an input device.
+    fn handle_device_event(&mut self, event: &DeviceEvent);
+    /// Handles a message posted through an `EventSender`.
+    fn handle_user_event(&mut self, message: &mut UserMessage) -> Propagation;
+    /// Runs the timers that are due and asks the platform to redraw the window if it needs it.
+    fn prepare_frame(&mut self, now: Instant);
+    /// Returns when the next timer of the window is due.
+    fn next_deadline(&self) -> Option<Instant>;
+    /// Runs the frame callback and renders the window.
+    fn redraw(&mut self);
+    /// Takes the commands queued by the window's handlers.
+    fn take_commands(&mut self) -> WindowCommands;
+    /// Moves the window's renderer to another device, after the one it drew with was lost.
+    fn set_device(&mut self, device: &RenderDevice);
+    /// Releases the window's rendering resources.
+    fn destroy(&self);
+}
+
+/// What every window of an event loop is built with.
+pub(crate) struct LoopResources {
+    pub(crate) device: RenderDevice,
+    pub(crate) proxy: EventLoopProxy<LoopMessage>,
+}
+
+type OpenWindowFunction = dyn FnOnce(
+    &EventLoopWindowTarget<LoopMessage>,
+    &LoopResources,
+) -> Result<Box<dyn ManagedWindow>, WindowError>;
+
+/// Changes to the set of windows requested while handling an event, applied once it is handled.
+#[derive(Default)]
+pub(crate) struct WindowCommands {
+    open: Vec<Box<OpenWindowFunction>>,
+    close: Vec<WindowId>,
+    exit: bool,
+    device_lost: bool,
+}
+
+impl WindowCommands {
+    pub(crate) fn open<State: 'static, F>(
+        &mut self,
+        title: &str,
+        width: u32,
+        height: u32,
+        options: Option<WindowOptions>,
+        state: State,
+        setup: F,
+    ) where
+        F: FnOnce(&mut Window<State>) + 'static,
+    {
+        let title = title.to_string();
+        self.open.push(Box::new(move |target, resources| {
+            let mut window =
+                Window::build(target, resources, &title, width, height, options, state)?;
+            setup(&mut window);
+            Ok(Box::new(window))
+        }));
+    }
+
+    pub(crate) fn close(&mut self, id: WindowId) {
+        self.close.push(id);
+    }
+
+    pub(crate) fn exit(&mut self) {
+        self.exit = true;
+    }
+
+    /// Reports that the shared render device was lost, so every window moves to a new one.
+    pub(crate) fn device_lost(&mut self) {
+        self.device_lost = true;
+    }
+
+    /// Returns whether the commands close the given window, directly or by exiting.
+    pub(crate) fn closes(&self, id: WindowId) -> bool {
+        self.exit || self.close.contains(&id)
+    }
+
+    fn append(&mut self, mut other: WindowCommands) {
+        self.open.append(&mut other.open);
+        self.close.append(&mut other.close);
+        self.exit |= other.exit;
+        self.device_lost |= other.device_lost;
+    }
+}
+
+/// Runs several windows on one event loop, routing each event to the window it belongs to.
+///
+/// Windows must be created with `create_window` so they are built on the application's event loop
+/// and share its render device. The loop exits once the last window is closed.
+pub struct Application {
+    event_loop: EventLoop<LoopMessage>,
+    resources: LoopResources,
+    windows: Vec<Box<dyn ManagedWindow>>,
+}
+
+impl Application {
+    /// Creates a new application with its own event loop and the system's default render device.
+    pub fn new() -> Result<Self, RenderError> {
+        let event_loop = EventLoopBuilder::with_user_event().build();
+        let resources = LoopResources {
+            device: RenderDevice::system_default()?,
+            proxy: event_loop.create_proxy(),
+        };
+        Ok(Application::from_event_loop(event_loop, resources))
+    }
+
+    pub(crate) fn from_event_loop(
+        event_loop: EventLoop<LoopMessage>,
+        resources: LoopResources,
+    ) -> Self {
+        Application {
+            event_loop,
+            resources,
+            windows: Vec::new(),
+        }
+    }
+
+    /// Returns the render device shared by the application's windows.
+    pub fn device(&self) -> &RenderDevice {
+        &self.resources.device
+    }
+
+    /// Returns a sender that posts messages of type `T` to the event loop from any thread.
+    pub fn event_sender<T: Send + 'static>(&self) -> EventSender<T> {
+        EventSender::new(MessageSink::EventLoop(self.resources.proxy.clone()))
+    }
+
+    /// Creates a window on the application's event loop. It is shown once added with `add_window`.
+    pub fn create_window(
+        &self,
+        title: &str,
+        width: u32,
+        height: u32,
+        options: Option<WindowOptions>,
+    ) -> Result<Window, WindowError> {
+        self.create_window_with_state(title, width, height, options, ())
+    }
+
+    /// Creates a window on the application's event loop that passes `state` to its handlers.
+    pub fn create_window_with_state<State: 'static>(
+        &self,
+        title: &str,
+        width: u32,
+        height: u32,
+        options: Option<WindowOptions>,
+        state: State,
+    ) -> Result<Window<State>, WindowError> {
+        Window::build(
+            &self.event_loop,
+            &self.resources,
+            title,
+            width,
+            height,
+            options,
+            state,
+        )
+    }
+
+    /// Adds a window to the application and returns its id.
+    pub fn add_window<State: 'static>(&mut self, window: Window<State>) -> WindowId {
+        let id = window.id();
+        self.windows.push(Box::new(window));
+        id
+    }
+
+    /// Returns the number of open windows.
+    pub fn window_count(&self) -> usize {
+        self.windows.len()
+    }
+
+    /// Starts the event loop. It runs until every window is closed or a handler asks it to exit.
+    pub fn run(self) -> ! {
+        let mut windows = self.windows;
+        let mut resources = self.resources;
+
+        self.event_loop.run(move |event, target, control_flow| {
+            *control_flow = ControlFlow::Wait;
+
+            match event {
+                Event::WindowEvent { window_id, event } => {
+                    if let Some(window) = find_window(&mut windows, window_id) {
+                        window.handle_window_event(&event);
+                    }
+                }
+                Event::DeviceEvent { event, .. } => {
+                    for window in &mut windows {
+                        window.handle_device_event(&event);
+                    }
+                }
+                Event::UserEvent(LoopMessage(mut message)) => {
+                    // A window consuming the message keeps it from the windows after it.
+                    for window in &mut windows {
+                        if window.handle_user_event(&mut message) == Propagation::Stop {
+                            break;
+                        }
+                    }
+                }
+                Event::MainEventsCleared => {
+                    let now = Instant::now();
+                    for window in &mut windows {
+                        window.prepare_frame(now);
+                    }
+                }
+                Event::RedrawRequested(window_id) => {
+                    if let Some(window) = find_window(&mut windows, window_id) {
+                        window.redraw();
+                    }
+                }
+                _ => (),
+            }
+
+            let mut commands = WindowCommands::default();
+            for window in &mut windows {
+                commands.append(window.take_commands());
+            }
+            apply_commands(&mut windows, commands, target, &mut resources);
+
+            if windows.is_empty() {
+                *control_flow = ControlFlow::Exit;
+            } else if let Some(deadline) = windows
+                .iter()
+                .filter_map(|window| window.next_deadline())
+                .min()
+            {
+                // Sleep until the next timer instead of polling.
+                *control_flow = ControlFlow::WaitUntil(deadline);
+            }
+        })
+    }
+}
+
+fn find_window(
+    windows: &mut [Box<dyn ManagedWindow>],
+    id: WindowId,
+) -> Option<&mut Box<dyn ManagedWindow>> {
+    windows.iter_mut().find(|window| window.id() == id)
+}
+
+fn apply_commands(
+    windows: &mut Vec<Box<dyn ManagedWindow>>,
+    commands: WindowCommands,
+    target: &EventLoopWindowTarget<LoopMessage>,
+    resources: &mut LoopResources,
+) {
+    if commands.exit {
+        for window in windows.drain(..) {
+            window.destroy();
+        }
+        return;
+    }
+    if commands.device_lost {
+        // The windows share the lost device, so they all move to the system's current one.
+        match RenderDevice::system_default() {
+            Ok(device) => {
+                resources.device = device;
+                for window in windows.iter_mut() {
+                    window.set_device(&resources.device);
+                }
+            }
+            Err(error) => eprintln!("Cannot replace the lost render device: {error}"),
+        }
+    }
+    windows.retain(|window| {
+        let closed = commands.close.contains(&window.id());
+        if closed {
+            window.destroy();
+        }
+        !closed
+    });
+    for open in commands.open {
+        match open(target, resources) {
+            Ok(window) => windows.push(window),
+            Err(error) => eprintln!("Cannot open window: {error}"),
+        }
+    }
+}
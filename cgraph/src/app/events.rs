@@ -0,0 +1,732 @@
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    marker::PhantomData,
+    rc::Rc,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use winit::{
+    event::{
+        ElementState, Ime, KeyboardInput, ModifiersState, MouseButton, MouseScrollDelta,
+        VirtualKeyCode, WindowEvent,
+    },
+    event_loop::{EventLoopClosed, EventLoopProxy},
+    window::WindowId,
+};
+
+use crate::{
+    app::{
+        CoreEvent, CoreEventReference, CursorIcon, GestureEvent, PhysicalKey, SharedObjects,
+        TextInputEvent, TimerId, Window, WindowOptions, application::WindowCommands,
+        gestures::GestureTracker, timers::Scheduler,
+    },
+    renderer::Renderer,
+};
+
+/// Gives handlers and the frame callback access to the window, its renderer and shared objects.
+pub struct WindowContext<'a> {
+    /// The native window, or `None` in a headless window.
+    pub window: Option<&'a mut winit::window::Window>,
+    /// The renderer drawing the window, used to add, remove or clear objects.
+    pub renderer: &'a mut dyn Renderer,
+    /// The objects shared with the window.
+    pub objects: &'a mut SharedObjects,
+    pub(crate) commands: &'a mut WindowCommands,
+    pub(crate) id: WindowId,
+    pub(crate) sink: &'a MessageSink,
+    pub(crate) scheduler: &'a mut Scheduler,
+}
+
+impl WindowContext<'_> {
+    /// Opens a new window once the current event is handled, calling `setup` to register its handlers.
+    pub fn open_window<State: 'static, F>(
+        &mut self,
+        title: &str,
+        width: u32,
+        height: u32,
+        options: Option<WindowOptions>,
+        state: State,
+        setup: F,
+    ) where
+        F: FnOnce(&mut Window<State>) + 'static,
+    {
+        self.commands
+            .open(title, width, height, options, state, setup);
+    }
+
+    /// Changes the mouse cursor until the cursor moves onto another object.
+    pub fn set_cursor(&mut self, icon: CursorIcon) {
+        if let Some(window) = self.window.as_deref_mut() {
+            window.set_cursor_icon(icon.to_winit());
+        }
+    }
+
+    /// Returns the id of the window.
+    pub fn id(&self) -> WindowId {
+        self.id
+    }
+
+    /// Sets the title of the native window.
+    pub fn set_title(&mut self, title: &str) {
+        if let Some(window) = self.window.as_deref_mut() {
+            window.set_title(title);
+        }
+    }
+
+    /// Closes this window once the current event is handled.
+    pub fn close(&mut self) {
+        self.commands.close(self.id);
+    }
+
+    /// Closes another window of the application.
+    pub fn close_window(&mut self, id: WindowId) {
+        self.commands.close(id);
+    }
+
+    /// Closes every window and stops the event loop.
+    pub fn exit(&mut self) {
+        self.commands.exit();
+    }
+
+    /// Returns a sender that posts messages of type `T` to the event loop from any thread.
+    pub fn event_sender<T: Send + 'static>(&self) -> EventSender<T> {
+        EventSender::new(self.sink.clone())
+    }
+
+    /// Calls `callback` once after `delay`.
+    pub fn set_timeout<F>(&mut self, delay: Duration, callback: F) -> TimerId
+    where
+        F: FnMut(&mut WindowContext) + 'static,
+    {
+        self.scheduler.set_timeout(delay, Box::new(callback))
+    }
+
+    /// Calls `callback` every `period`, of at least a millisecond, until the timer is cleared.
+    ///
+    /// Timers don't redraw the window; call `request_redraw` from the callback after changing
+    /// what is drawn.
+    pub fn set_interval<F>(&mut self, period: Duration, callback: F) -> TimerId
+    where
+        F: FnMut(&mut WindowContext) + 'static,
+    {
+        self.scheduler.set_interval(period, Box::new(callback))
+    }
+
+    /// Cancels a timeout or interval.
+    pub fn clear_timer(&mut self, id: TimerId) {
+        self.scheduler.clear_timer(id);
+    }
+
+    /// Calls `callback` with the frame time right before the next frame is drawn.
+    ///
+    /// Requesting another frame from the callback keeps an animation running at display rate.
+    pub fn request_animation_frame<F>(&mut self, callback: F)
+    where
+        F: FnOnce(&mut WindowContext, Instant) + 'static,
+    {
+        self.scheduler.request_animation_frame(Box::new(callback));
+    }
+
+    /// Redraws the window on the next frame. Call it after changing what is drawn: windows are
+    /// only redrawn on request, after user events, and when resized or uncovered.
+    pub fn request_redraw(&mut self) {
+        self.scheduler.request_redraw();
+    }
+}
+
+/// The payload of a message posted to the event loop.
+pub(crate) type UserMessage = Box<dyn Any + Send>;
+
+/// A message posted to the event loop, dispatched to the handlers of its type.
+pub(crate) struct LoopMessage(pub(crate) UserMessage);
+
+/// Where the messages of an `EventSender` are posted.
+#[derive(Clone)]
+pub(crate) enum MessageSink {
+    /// The event loop, which is woken up to dispatch them.
+    EventLoop(EventLoopProxy<LoopMessage>),
+    /// The queue of a headless window, drained while it replays events.
+    Queue(mpsc::Sender<UserMessage>),
+}
+
+/// Posts messages of type `T` to the event loop from any thread, waking it up.
+///
+/// Messages are dispatched on the UI thread to the handlers registered with `on_user_event`
+/// and then to the `CoreEvent::UserEvent` subscribers of every window.
+pub struct EventSender<T> {
+    sink: MessageSink,
+    marker: PhantomData<fn(T)>,
+}
+
+impl<T> Clone for EventSender<T> {
+    fn clone(&self) -> Self {
+        EventSender {
+            sink: self.sink.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Send + 'static> EventSender<T> {
+    pub(crate) fn new(sink: MessageSink) -> Self {
+        EventSender {
+            sink,
+            marker: PhantomData,
+        }
+    }
+
+    /// Sends a message, returning it back if the event loop has already exited.
+    pub fn send(&self, message: T) -> Result<(), EventLoopClosed<T>> {
+        let message: UserMessage = Box::new(message);
+        let returned = match &self.sink {
+            MessageSink::EventLoop(proxy) => proxy
+                .send_event(LoopMessage(message))
+                .map_err(|EventLoopClosed(LoopMessage(message))| message),
+            MessageSink::Queue(queue) => queue
+                .send(message)
+                .map_err(|mpsc::SendError(message)| message),
+        };
+        returned.map_err(|message| EventLoopClosed(*message.downcast::<T>().unwrap()))
+    }
+}
+
+/// Tells the dispatcher whether an event should keep going to the next handlers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Propagation {
+    /// Lets handlers with a lower priority receive the event.
+    Continue,
+    /// Consumes the event so no other handler receives it.
+    Stop,
+}
+
+/// A keyboard key was pressed or released.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyEvent {
+    /// The platform-specific scancode of the key, identifying its physical position.
+    pub scancode: u32,
+    /// The logical key in the active keyboard layout, if it has a known virtual key code.
+    pub key: Option<VirtualKeyCode>,
+    /// Whether the key was pressed or released.
+    pub state: ElementState,
+    /// The modifiers held while the key changed.
+    pub modifiers: ModifiersState,
+}
+
+impl KeyEvent {
+    /// Returns whether the key was pressed.
+    pub fn is_pressed(&self) -> bool {
+        self.state == ElementState::Pressed
+    }
+
+    /// Returns the physical key, which stays the same when the keyboard layout changes.
+    pub fn physical_key(&self) -> PhysicalKey {
+        PhysicalKey(self.scancode)
+    }
+}
+
+/// A mouse event, with positions in physical pixels relative to the window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MouseEvent {
+    /// The cursor moved.
+    Moved {
+        /// The x coordinate of the cursor.
+        x: f64,
+        /// The y coordinate of the cursor.
+        y: f64,
+    },
+    /// A mouse button was pressed.
+    Pressed {
+        /// The button that was pressed.
+        button: MouseButton,
+        /// The x coordinate of the cursor.
+        x: f64,
+        /// The y coordinate of the cursor.
+        y: f64,
+    },
+    /// A mouse button was released.
+    Released {
+        /// The button that was released.
+        button: MouseButton,
+        /// The x coordinate of the cursor.
+        x: f64,
+        /// The y coordinate of the cursor.
+        y: f64,
+    },
+    /// The mouse wheel or touchpad scrolled.
+    Scrolled {
+        /// How far the content scrolled.
+        delta: MouseScrollDelta,
+        /// The x coordinate of the cursor.
+        x: f64,
+        /// The y coordinate of the cursor.
+        y: f64,
+    },
+    /// The cursor entered the window.
+    Entered,
+    /// The cursor left the window.
+    Left,
+}
+
+/// The window was resized.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResizeEvent {
+    /// The new width of the window in physical pixels.
+    pub width: f64,
+    /// The new height of the window in physical pixels.
+    pub height: f64,
+}
+
+/// A handle to an event handler that removes the handler when dropped.
+#[must_use = "the handler is removed as soon as the subscription is dropped"]
+pub struct Subscription {
+    id: u64,
+    removed: Rc<RefCell<Vec<u64>>>,
+    active: bool,
+}
+
+impl Subscription {
+    /// Removes the handler now.
+    pub fn unsubscribe(self) {
+        drop(self);
+    }
+
+    /// Keeps the handler registered for the lifetime of the window.
+    pub fn detach(mut self) {
+        self.active = false;
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if self.active {
+            self.removed.borrow_mut().push(self.id);
+        }
+    }
+}
+
+type HandlerFunction<State, E> =
+    dyn FnMut(&mut State, &mut WindowContext, &mut E) -> Propagation + 'static;
+
+struct Handler<State, E> {
+    id: u64,
+    priority: i32,
+    callback: Box<HandlerFunction<State, E>>,
+}
+
+/// The handlers subscribed to one kind of event, sorted by descending priority.
+pub(crate) struct HandlerList<State, E> {
+    handlers: Vec<Handler<State, E>>,
+}
+
+impl<State, E> Default for HandlerList<State, E> {
+    fn default() -> Self {
+        HandlerList {
+            handlers: Vec::new(),
+        }
+    }
+}
+
+impl<State, E> HandlerList<State, E> {
+    fn insert(&mut self, id: u64, priority: i32, callback: Box<HandlerFunction<State, E>>) {
+        // Handlers with the same priority keep their registration order.
+        let index = self
+            .handlers
+            .iter()
+            .position(|handler| handler.priority < priority)
+            .unwrap_or(self.handlers.len());
+        self.handlers.insert(
+            index,
+            Handler {
+                id,
+                priority,
+                callback,
+            },
+        );
+    }
+
+    fn remove(&mut self, ids: &[u64]) {
+        self.handlers.retain(|handler| !ids.contains(&handler.id));
+    }
+
+    /// Calls the handlers in order until one of them consumes the event.
+    fn dispatch(
+        &mut self,
+        state: &mut State,
+        context: &mut WindowContext,
+        event: &mut E,
+    ) -> Propagation {
+        for handler in &mut self.handlers {
+            if (handler.callback)(state, context, event) == Propagation::Stop {
+                return Propagation::Stop;
+            }
+        }
+        Propagation::Continue
+    }
+}
+
+/// Every handler registered on a window, along with the input state needed to build typed events.
+pub(crate) struct EventHandlers<State> {
+    next_id: u64,
+    removed: Rc<RefCell<Vec<u64>>>,
+    core: Vec<(CoreEventReference, HandlerList<State, CoreEvent>)>,
+    key: HandlerList<State, KeyEvent>,
+    mouse: HandlerList<State, MouseEvent>,
+    resize: HandlerList<State, ResizeEvent>,
+    text: HandlerList<State, TextInputEvent>,
+    gesture: HandlerList<State, GestureEvent>,
+    user: Vec<(TypeId, HandlerList<State, UserMessage>)>,
+    modifiers: ModifiersState,
+    cursor: (f64, f64),
+    composing: bool,
+    pub(crate) gestures: GestureTracker,
+}
+
+impl<State> Default for EventHandlers<State> {
+    fn default() -> Self {
+        EventHandlers {
+            next_id: 0,
+            removed: Rc::new(RefCell::new(Vec::new())),
+            core: Vec::new(),
+            key: HandlerList::default(),
+            mouse: HandlerList::default(),
+            resize: HandlerList::default(),
+            text: HandlerList::default(),
+            gesture: HandlerList::default(),
+            user: Vec::new(),
+            modifiers: ModifiersState::empty(),
+            cursor: (0.0, 0.0),
+            composing: false,
+            gestures: GestureTracker::default(),
+        }
+    }
+}
+
+impl<State> EventHandlers<State> {
+    fn subscription(&mut self) -> Subscription {
+        self.next_id += 1;
+        Subscription {
+            id: self.next_id,
+            removed: self.removed.clone(),
+            active: true,
+        }
+    }
+
+    pub(crate) fn add_core(
+        &mut self,
+        event: CoreEventReference,
+        priority: i32,
+        callback: Box<HandlerFunction<State, CoreEvent>>,
+    ) -> Subscription {
+        let subscription = self.subscription();
+        let list = match self
+            .core
+            .iter()
+            .position(|(reference, _)| *reference == event)
+        {
+            Some(index) => &mut self.core[index].1,
+            None => {
+                self.core.push((event, HandlerList::default()));
+                &mut self.core.last_mut().unwrap().1
+            }
+        };
+        list.insert(subscription.id, priority, callback);
+        subscription
+    }
+
+    pub(crate) fn add_key(
+        &mut self,
+        priority: i32,
+        callback: Box<HandlerFunction<State, KeyEvent>>,
+    ) -> Subscription {
+        let subscription = self.subscription();
+        self.key.insert(subscription.id, priority, callback);
+        subscription
+    }
+
+    pub(crate) fn add_mouse(
+        &mut self,
+        priority: i32,
+        callback: Box<HandlerFunction<State, MouseEvent>>,
+    ) -> Subscription {
+        let subscription = self.subscription();
+        self.mouse.insert(subscription.id, priority, callback);
+        subscription
+    }
+
+    pub(crate) fn add_resize(
+        &mut self,
+        priority: i32,
+        callback: Box<HandlerFunction<State, ResizeEvent>>,
+    ) -> Subscription {
+        let subscription = self.subscription();
+        self.resize.insert(subscription.id, priority, callback);
+        subscription
+    }
+
+    pub(crate) fn add_text(
+        &mut self,
+        priority: i32,
+        callback: Box<HandlerFunction<State, TextInputEvent>>,
+    ) -> Subscription {
+        let subscription = self.subscription();
+        self.text.insert(subscription.id, priority, callback);
+        subscription
+    }
+
+    pub(crate) fn add_gesture(
+        &mut self,
+        priority: i32,
+        callback: Box<HandlerFunction<State, GestureEvent>>,
+    ) -> Subscription {
+        let subscription = self.subscription();
+        self.gesture.insert(subscription.id, priority, callback);
+        subscription
+    }
+
+    pub(crate) fn add_user<T: 'static, F>(&mut self, priority: i32, mut callback: F) -> Subscription
+    where
+        F: FnMut(&mut State, &mut WindowContext, &mut T) -> Propagation + 'static,
+    {
+        let subscription = self.subscription();
+        let type_id = TypeId::of::<T>();
+        let list = match self.user.iter().position(|(id, _)| *id == type_id) {
+            Some(index) => &mut self.user[index].1,
+            None => {
+                self.user.push((type_id, HandlerList::default()));
+                &mut self.user.last_mut().unwrap().1
+            }
+        };
+        list.insert(
+            subscription.id,
+            priority,
+            Box::new(move |state, context, message: &mut UserMessage| {
+                match message.downcast_mut::<T>() {
+                    Some(message) => callback(state, context, message),
+                    None => Propagation::Continue,
+                }
+            }),
+        );
+        subscription
+    }
+
+    /// Drops the handlers whose subscriptions were dropped since the last dispatch.
+    fn remove_unsubscribed(&mut self) {
+        let removed: Vec<u64> = self.removed.borrow_mut().drain(..).collect();
+        if removed.is_empty() {
+            return;
+        }
+        for (_, list) in &mut self.core {
+            list.remove(&removed);
+        }
+        self.key.remove(&removed);
+        self.mouse.remove(&removed);
+        self.resize.remove(&removed);
+        self.text.remove(&removed);
+        self.gesture.remove(&removed);
+        for (_, list) in &mut self.user {
+            list.remove(&removed);
+        }
+    }
+
+    /// Sends a core event to the handlers subscribed to its kind.
+    pub(crate) fn dispatch_core(
+        &mut self,
+        state: &mut State,
+        context: &mut WindowContext,
+        event: &mut CoreEvent,
+    ) -> Propagation {
+        self.remove_unsubscribed();
+        let reference = CoreEventReference::of(event);
+        match self.core.iter_mut().find(|(r, _)| *r == reference) {
+            Some((_, list)) => list.dispatch(state, context, event),
+            None => Propagation::Continue,
+        }
+    }
+
+    /// Sends a user message to the handlers registered for its type.
+    pub(crate) fn dispatch_user(
+        &mut self,
+        state: &mut State,
+        context: &mut WindowContext,
+        message: &mut UserMessage,
+    ) -> Propagation {
+        self.remove_unsubscribed();
+        let type_id = (**message).type_id();
+        match self.user.iter_mut().find(|(id, _)| *id == type_id) {
+            Some((_, list)) => list.dispatch(state, context, message),
+            None => Propagation::Continue,
+        }
+    }
+
+    /// Sends a winit window event to the typed handlers it maps to.
+    pub(crate) fn dispatch_window_event(
+        &mut self,
+        state: &mut State,
+        context: &mut WindowContext,
+        event: &WindowEvent,
+    ) -> Propagation {
+        self.remove_unsubscribed();
+        match event {
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = *modifiers;
+                Propagation::Continue
+            }
+            WindowEvent::KeyboardInput { input, .. } => {
+                let mut key_event = self.key_event(input);
+                if self.key.dispatch(state, context, &mut key_event) == Propagation::Stop {
+                    return Propagation::Stop;
+                }
+                self.text
+                    .dispatch(state, context, &mut TextInputEvent::Key(key_event))
+            }
+            WindowEvent::ReceivedCharacter(character) => {
+                // Control characters come from keys like Backspace, which are sent as key events,
+                // and characters typed while composing are part of the preedit text.
+                if character.is_control() || self.composing {
+                    return Propagation::Continue;
+                }
+                self.text.dispatch(
+                    state,
+                    context,
+                    &mut TextInputEvent::Commit(character.to_string()),
+                )
+            }
+            WindowEvent::Ime(ime) => {
+                let mut text_event = match ime {
+                    Ime::Enabled => TextInputEvent::ImeEnabled,
+                    Ime::Preedit(text, cursor) => {
+                        self.composing = !text.is_empty();
+                        TextInputEvent::Preedit {
+                            text: text.clone(),
+                            cursor: *cursor,
+                        }
+                    }
+                    Ime::Commit(text) => {
+                        self.composing = false;
+                        TextInputEvent::Commit(text.clone())
+                    }
+                    Ime::Disabled => {
+                        self.composing = false;
+                        TextInputEvent::ImeDisabled
+                    }
+                };
+                self.text.dispatch(state, context, &mut text_event)
+            }
+            WindowEvent::Resized(size) => self.resize.dispatch(
+                state,
+                context,
+                &mut ResizeEvent {
+                    width: size.width as f64,
+                    height: size.height as f64,
+                },
+            ),
+            WindowEvent::CursorMoved { position, .. } => {
+                self.cursor = (position.x, position.y);
+                let (x, y) = self.cursor;
+                let propagation =
+                    self.mouse
+                        .dispatch(state, context, &mut MouseEvent::Moved { x, y });
+                let gestures = self.gestures.moved(x, y, context.renderer.objects_mut());
+                self.dispatch_gestures(state, context, propagation, gestures)
+            }
+            WindowEvent::CursorEntered { .. } => {
+                self.mouse
+                    .dispatch(state, context, &mut MouseEvent::Entered)
+            }
+            WindowEvent::CursorLeft { .. } => {
+                let propagation = self.mouse.dispatch(state, context, &mut MouseEvent::Left);
+                let gestures = self.gestures.left();
+                self.dispatch_gestures(state, context, propagation, gestures)
+            }
+            WindowEvent::MouseInput {
+                state: button_state,
+                button,
+                ..
+            } => {
+                let (x, y) = self.cursor;
+                let (mut mouse_event, gestures) = match button_state {
+                    ElementState::Pressed => (
+                        MouseEvent::Pressed {
+                            button: *button,
+                            x,
+                            y,
+                        },
+                        self.gestures
+                            .pressed(*button, x, y, context.scheduler.now()),
+                    ),
+                    ElementState::Released => (
+                        MouseEvent::Released {
+                            button: *button,
+                            x,
+                            y,
+                        },
+                        self.gestures.released(*button, x, y),
+                    ),
+                };
+                let propagation = self.mouse.dispatch(state, context, &mut mouse_event);
+                self.dispatch_gestures(state, context, propagation, gestures)
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let (x, y) = self.cursor;
+                let propagation = self.mouse.dispatch(
+                    state,
+                    context,
+                    &mut MouseEvent::Scrolled {
+                        delta: *delta,
+                        x,
+                        y,
+                    },
+                );
+                let gestures = vec![self.gestures.wheel(*delta, x, y)];
+                self.dispatch_gestures(state, context, propagation, gestures)
+            }
+            _ => Propagation::Continue,
+        }
+    }
+
+    /// Sends gestures to their handlers, unless the raw mouse event was consumed.
+    fn dispatch_gestures(
+        &mut self,
+        state: &mut State,
+        context: &mut WindowContext,
+        propagation: Propagation,
+        gestures: Vec<GestureEvent>,
+    ) -> Propagation {
+        let hover_changed = gestures.iter().any(|gesture| {
+            matches!(
+                gesture,
+                GestureEvent::HoverEnter { .. } | GestureEvent::HoverLeave { .. }
+            )
+        });
+        if hover_changed {
+            // The cursor follows the object under it.
+            let icon = self
+                .gestures
+                .hovered()
+                .and_then(|index| context.renderer.objects_mut().get(index))
+                .and_then(|object| object.cursor)
+                .unwrap_or_default();
+            context.set_cursor(icon);
+        }
+        if propagation == Propagation::Stop {
+            return Propagation::Stop;
+        }
+        for mut gesture in gestures {
+            if self.gesture.dispatch(state, context, &mut gesture) == Propagation::Stop {
+                return Propagation::Stop;
+            }
+        }
+        Propagation::Continue
+    }
+
+    fn key_event(&self, input: &KeyboardInput) -> KeyEvent {
+        KeyEvent {
+            scancode: input.scancode,
+            key: input.virtual_keycode,
+            state: input.state,
+            modifiers: self.modifiers,
+        }
+    }
+}
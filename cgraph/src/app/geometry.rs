@@ -0,0 +1,103 @@
+use std::{error::Error, fmt, io, path::Path, str::FromStr};
+
+/// The position, size and state of a window, saved so it reopens where it was closed.
+///
+/// Positions and sizes are in logical pixels. Geometry files hold one `key = value` pair per
+/// line, with the keys `x`, `y`, `width`, `height` and `maximized`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowGeometry {
+    /// The x coordinate of the window's outer top-left corner.
+    pub x: i32,
+    /// The y coordinate of the window's outer top-left corner.
+    pub y: i32,
+    /// The width of the window's content.
+    pub width: u32,
+    /// The height of the window's content.
+    pub height: u32,
+    /// Whether the window is maximized.
+    pub maximized: bool,
+}
+
+/// Error returned when a geometry file cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeometryError {
+    /// A line is not a `key = value` pair.
+    Syntax(usize),
+    /// A key is not a known geometry key.
+    UnknownKey(String),
+    /// A value cannot be parsed for its key.
+    InvalidValue(String),
+    /// A required key is missing.
+    MissingKey(&'static str),
+}
+
+impl fmt::Display for GeometryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeometryError::Syntax(line) => write!(f, "Line {line} is not a `key = value` pair"),
+            GeometryError::UnknownKey(key) => write!(f, "Unknown geometry key `{key}`"),
+            GeometryError::InvalidValue(key) => write!(f, "Invalid value for `{key}`"),
+            GeometryError::MissingKey(key) => write!(f, "Missing geometry key `{key}`"),
+        }
+    }
+}
+
+impl Error for GeometryError {}
+
+impl WindowGeometry {
+    /// Loads a geometry from a file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(text.parse()?)
+    }
+
+    /// Writes the geometry to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+}
+
+impl fmt::Display for WindowGeometry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "x = {}", self.x)?;
+        writeln!(f, "y = {}", self.y)?;
+        writeln!(f, "width = {}", self.width)?;
+        writeln!(f, "height = {}", self.height)?;
+        writeln!(f, "maximized = {}", self.maximized)
+    }
+}
+
+impl FromStr for WindowGeometry {
+    type Err = GeometryError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (mut x, mut y, mut width, mut height) = (None, None, None, None);
+        let mut maximized = false;
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(GeometryError::Syntax(index + 1))?;
+            let (key, value) = (key.trim(), value.trim());
+            let invalid = || GeometryError::InvalidValue(key.to_string());
+            match key {
+                "x" => x = Some(value.parse().map_err(|_| invalid())?),
+                "y" => y = Some(value.parse().map_err(|_| invalid())?),
+                "width" => width = Some(value.parse().map_err(|_| invalid())?),
+                "height" => height = Some(value.parse().map_err(|_| invalid())?),
+                "maximized" => maximized = value.parse().map_err(|_| invalid())?,
+                _ => return Err(GeometryError::UnknownKey(key.to_string())),
+            }
+        }
+        Ok(WindowGeometry {
+            x: x.ok_or(GeometryError::MissingKey("x"))?,
+            y: y.ok_or(GeometryError::MissingKey("y"))?,
+            width: width.ok_or(GeometryError::MissingKey("width"))?,
+            height: height.ok_or(GeometryError::MissingKey("height"))?,
+            maximized,
+        })
+    }
+}
@@ -0,0 +1,324 @@
+use std::time::{Duration, Instant};
+
+use winit::event::{MouseButton, MouseScrollDelta};
+
+use crate::object::Object;
+
+/// The shape of the mouse cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CursorIcon {
+    /// The platform's default arrow.
+    #[default]
+    Default,
+    /// A pointing hand, used over links and buttons.
+    Pointer,
+    /// An I-beam, used over editable or selectable text.
+    Text,
+    /// A crosshair.
+    Crosshair,
+    /// Arrows in all four directions, used over things that can be moved.
+    Move,
+    /// An open hand.
+    Grab,
+    /// A closed hand.
+    Grabbing,
+    /// A left and right arrow, used over vertical splitters.
+    ResizeHorizontal,
+    /// An up and down arrow, used over horizontal splitters.
+    ResizeVertical,
+    /// A diagonal arrow from the top left to the bottom right corner.
+    ResizeDiagonalDown,
+    /// A diagonal arrow from the bottom left to the top right corner.
+    ResizeDiagonalUp,
+    /// A sign showing that the action is not allowed.
+    NotAllowed,
+    /// A busy indicator.
+    Wait,
+}
+
+impl CursorIcon {
+    pub(crate) fn to_winit(self) -> winit::window::CursorIcon {
+        match self {
+            CursorIcon::Default => winit::window::CursorIcon::Default,
+            CursorIcon::Pointer => winit::window::CursorIcon::Hand,
+            CursorIcon::Text => winit::window::CursorIcon::Text,
+            CursorIcon::Crosshair => winit::window::CursorIcon::Crosshair,
+            CursorIcon::Move => winit::window::CursorIcon::Move,
+            CursorIcon::Grab => winit::window::CursorIcon::Grab,
+            CursorIcon::Grabbing => winit::window::CursorIcon::Grabbing,
+            CursorIcon::ResizeHorizontal => winit::window::CursorIcon::EwResize,
+            CursorIcon::ResizeVertical => winit::window::CursorIcon::NsResize,
+            CursorIcon::ResizeDiagonalDown => winit::window::CursorIcon::NwseResize,
+            CursorIcon::ResizeDiagonalUp => winit::window::CursorIcon::NeswResize,
+            CursorIcon::NotAllowed => winit::window::CursorIcon::NotAllowed,
+            CursorIcon::Wait => winit::window::CursorIcon::Wait,
+        }
+    }
+}
+
+/// A higher-level mouse event built from the raw mouse input, with positions in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GestureEvent {
+    /// A button was pressed and released without dragging.
+    Click {
+        /// The button that was clicked.
+        button: MouseButton,
+        /// 1 for a single click, 2 for a double click, 3 for a triple click and so on.
+        count: u32,
+        /// The x coordinate of the cursor.
+        x: f64,
+        /// The y coordinate of the cursor.
+        y: f64,
+    },
+    /// The cursor moved past the drag threshold while a button was held.
+    DragStart {
+        /// The button being held.
+        button: MouseButton,
+        /// The x coordinate where the button was pressed.
+        x: f64,
+        /// The y coordinate where the button was pressed.
+        y: f64,
+    },
+    /// The cursor moved during a drag.
+    DragMove {
+        /// The button being held.
+        button: MouseButton,
+        /// The x coordinate of the cursor.
+        x: f64,
+        /// The y coordinate of the cursor.
+        y: f64,
+        /// The horizontal distance from where the drag started.
+        dx: f64,
+        /// The vertical distance from where the drag started.
+        dy: f64,
+    },
+    /// The button was released, ending the drag.
+    DragEnd {
+        /// The button that was released.
+        button: MouseButton,
+        /// The x coordinate of the cursor.
+        x: f64,
+        /// The y coordinate of the cursor.
+        y: f64,
+    },
+    /// The cursor entered an object. Takes the object's index in the renderer.
+    HoverEnter {
+        /// The index of the object in the renderer.
+        object: usize,
+        /// The x coordinate of the cursor.
+        x: f64,
+        /// The y coordinate of the cursor.
+        y: f64,
+    },
+    /// The cursor left an object.
+    HoverLeave {
+        /// The index of the object in the renderer.
+        object: usize,
+    },
+    /// The wheel or touchpad scrolled.
+    Wheel {
+        /// The horizontal scroll distance in pixels.
+        dx: f64,
+        /// The vertical scroll distance in pixels.
+        dy: f64,
+        /// The scroll distance in lines, for content that scrolls line by line.
+        lines: (f64, f64),
+        /// Whether the distance came in pixels from a touchpad rather than in lines from a wheel.
+        precise: bool,
+        /// The x coordinate of the cursor.
+        x: f64,
+        /// The y coordinate of the cursor.
+        y: f64,
+    },
+}
+
+/// Thresholds used to recognize gestures.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureSettings {
+    /// How far, in pixels, the cursor must move with a button held before a drag starts.
+    pub drag_threshold: f64,
+    /// The longest time between two clicks that still counts them as a multiple click.
+    pub multi_click_time: Duration,
+    /// How far, in pixels, two clicks can be apart and still count as a multiple click.
+    pub multi_click_distance: f64,
+    /// How many pixels one line of wheel scrolling stands for.
+    pub line_height: f64,
+}
+
+impl Default for GestureSettings {
+    fn default() -> Self {
+        GestureSettings {
+            drag_threshold: 4.0,
+            multi_click_time: Duration::from_millis(500),
+            multi_click_distance: 4.0,
+            line_height: 20.0,
+        }
+    }
+}
+
+struct Press {
+    button: MouseButton,
+    x: f64,
+    y: f64,
+    count: u32,
+    dragging: bool,
+}
+
+struct LastClick {
+    button: MouseButton,
+    x: f64,
+    y: f64,
+    time: Instant,
+    count: u32,
+}
+
+/// Turns raw mouse input into gestures.
+#[derive(Default)]
+pub(crate) struct GestureTracker {
+    pub(crate) settings: GestureSettings,
+    press: Option<Press>,
+    last_click: Option<LastClick>,
+    hovered: Option<usize>,
+}
+
+impl GestureTracker {
+    pub(crate) fn pressed(
+        &mut self,
+        button: MouseButton,
+        x: f64,
+        y: f64,
+        now: Instant,
+    ) -> Vec<GestureEvent> {
+        let count = match &self.last_click {
+            Some(last)
+                if last.button == button
+                    && now.duration_since(last.time) <= self.settings.multi_click_time
+                    && distance(last.x, last.y, x, y) <= self.settings.multi_click_distance =>
+            {
+                last.count + 1
+            }
+            _ => 1,
+        };
+        self.last_click = Some(LastClick {
+            button,
+            x,
+            y,
+            time: now,
+            count,
+        });
+        self.press = Some(Press {
+            button,
+            x,
+            y,
+            count,
+            dragging: false,
+        });
+        Vec::new()
+    }
+
+    pub(crate) fn released(&mut self, button: MouseButton, x: f64, y: f64) -> Vec<GestureEvent> {
+        match self.press.take() {
+            Some(press) if press.button == button => {
+                if press.dragging {
+                    vec![GestureEvent::DragEnd { button, x, y }]
+                } else {
+                    vec![GestureEvent::Click {
+                        button,
+                        count: press.count,
+                        x,
+                        y,
+                    }]
+                }
+            }
+            // Another button is still held.
+            press => {
+                self.press = press;
+                Vec::new()
+            }
+        }
+    }
+
+    pub(crate) fn moved(&mut self, x: f64, y: f64, objects: &[Object]) -> Vec<GestureEvent> {
+        let mut events = Vec::new();
+        if let Some(press) = &mut self.press {
+            if !press.dragging && distance(press.x, press.y, x, y) >= self.settings.drag_threshold {
+                press.dragging = true;
+                // A drag is not a click, so the next click starts counting again.
+                self.last_click = None;
+                events.push(GestureEvent::DragStart {
+                    button: press.button,
+                    x: press.x,
+                    y: press.y,
+                });
+            }
+            if press.dragging {
+                events.push(GestureEvent::DragMove {
+                    button: press.button,
+                    x,
+                    y,
+                    dx: x - press.x,
+                    dy: y - press.y,
+                });
+            }
+        }
+
+        // The object drawn last is on top.
+        let point = glam::Vec2::new(x as f32, y as f32);
+        let hovered = objects
+            .iter()
+            .rposition(|object| object.contains_point(point));
+        if hovered != self.hovered {
+            if let Some(object) = self.hovered {
+                events.push(GestureEvent::HoverLeave { object });
+            }
+            if let Some(object) = hovered {
+                events.push(GestureEvent::HoverEnter { object, x, y });
+            }
+            self.hovered = hovered;
+        }
+        events
+    }
+
+    pub(crate) fn left(&mut self) -> Vec<GestureEvent> {
+        self.hovered
+            .take()
+            .map(|object| GestureEvent::HoverLeave { object })
+            .into_iter()
+            .collect()
+    }
+
+    pub(crate) fn wheel(&self, delta: MouseScrollDelta, x: f64, y: f64) -> GestureEvent {
+        let line_height = self.settings.line_height;
+        let (dx, dy, lines, precise) = match delta {
+            MouseScrollDelta::LineDelta(columns, rows) => (
+                columns as f64 * line_height,
+                rows as f64 * line_height,
+                (columns as f64, rows as f64),
+                false,
+            ),
+            MouseScrollDelta::PixelDelta(position) => (
+                position.x,
+                position.y,
+                (position.x / line_height, position.y / line_height),
+                true,
+            ),
+        };
+        GestureEvent::Wheel {
+            dx,
+            dy,
+            lines,
+            precise,
+            x,
+            y,
+        }
+    }
+
+    /// Returns the object under the cursor.
+    pub(crate) fn hovered(&self) -> Option<usize> {
+        self.hovered
+    }
+}
+
+fn distance(x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
+    ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt()
+}
@@ -0,0 +1,297 @@
+use std::{fmt, str::FromStr};
+
+use thiserror::Error;
+use winit::event::{ModifiersState, VirtualKeyCode};
+
+use crate::app::KeyEvent;
+
+/// A key identified by its position on the keyboard, the same whatever the active layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PhysicalKey(pub u32);
+
+/// An event of the unified text input stream, merging key presses, typed text and IME composition.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextInputEvent {
+    /// A key was pressed or released.
+    Key(KeyEvent),
+    /// Text was typed or committed by the input method and should be inserted.
+    Commit(String),
+    /// The input method is composing text that is not committed yet.
+    Preedit {
+        /// The text being composed. It is empty once the composition is cleared.
+        text: String,
+        /// The byte range of the cursor in the text, or `None` to hide the cursor.
+        cursor: Option<(usize, usize)>,
+    },
+    /// The input method was enabled, so composition events may follow.
+    ImeEnabled,
+    /// The input method was disabled.
+    ImeDisabled,
+}
+
+/// A key combined with modifiers, such as `Cmd+Shift+P`.
+///
+/// Chords are parsed case-insensitively. `CmdOrCtrl` stands for `Cmd` on macOS and `Ctrl`
+/// elsewhere, so one binding works on every platform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    /// The modifiers held with the key.
+    pub modifiers: ModifiersState,
+    /// The logical key.
+    pub key: VirtualKeyCode,
+}
+
+impl KeyChord {
+    /// Creates a chord from modifiers and a key.
+    pub fn new(modifiers: ModifiersState, key: VirtualKeyCode) -> Self {
+        KeyChord { modifiers, key }
+    }
+
+    /// Returns the chord of a key press, or `None` if the key is unknown or was released.
+    pub fn from_event(event: &KeyEvent) -> Option<Self> {
+        if !event.is_pressed() {
+            return None;
+        }
+        event.key.map(|key| KeyChord::new(event.modifiers, key))
+    }
+
+    /// Returns whether a key press matches the chord.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        KeyChord::from_event(event) == Some(*self)
+    }
+}
+
+/// The modifier that platform shortcuts use: `Cmd` on macOS and `Ctrl` elsewhere.
+pub fn primary_modifier() -> ModifiersState {
+    if cfg!(target_os = "macos") {
+        ModifiersState::LOGO
+    } else {
+        ModifiersState::CTRL
+    }
+}
+
+/// Error returned when a chord cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ChordParseError {
+    /// The chord has no key, like `Ctrl+`.
+    #[error("Key chord has no key")]
+    MissingKey,
+    /// A part of the chord is not a known key or modifier.
+    #[error("Unknown key `{0}`")]
+    UnknownKey(String),
+    /// The chord has more than one non-modifier key.
+    #[error("Key chord `{0}` has more than one key")]
+    MultipleKeys(String),
+}
+
+impl FromStr for KeyChord {
+    type Err = ChordParseError;
+
+    fn from_str(chord: &str) -> Result<Self, Self::Err> {
+        let chord = chord.trim();
+        let mut modifiers = ModifiersState::empty();
+        let mut key = None;
+
+        // `+` separates parts, so `Ctrl++` and a lone `+` name the plus key itself.
+        let mut parts: Vec<&str> = chord.split('+').map(str::trim).collect();
+        if chord.ends_with('+') && parts.len() >= 2 && parts[parts.len() - 2].is_empty() {
+            parts.truncate(parts.len() - 2);
+            parts.push("Plus");
+        }
+
+        for part in parts {
+            if part.is_empty() {
+                return Err(ChordParseError::MissingKey);
+            }
+            if let Some(modifier) = modifier_from_name(part) {
+                modifiers |= modifier;
+                continue;
+            }
+            let parsed =
+                key_from_name(part).ok_or_else(|| ChordParseError::UnknownKey(part.to_string()))?;
+            if key.replace(parsed).is_some() {
+                return Err(ChordParseError::MultipleKeys(chord.to_string()));
+            }
+        }
+
+        key.map(|key| KeyChord::new(modifiers, key))
+            .ok_or(ChordParseError::MissingKey)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Modifiers are written in the order macOS menus use.
+        let names = [
+            (ModifiersState::CTRL, "Ctrl"),
+            (ModifiersState::ALT, "Alt"),
+            (ModifiersState::SHIFT, "Shift"),
+            (
+                ModifiersState::LOGO,
+                if cfg!(target_os = "macos") {
+                    "Cmd"
+                } else {
+                    "Super"
+                },
+            ),
+        ];
+        for (modifier, name) in names {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{name}+")?;
+            }
+        }
+        match key_name(self.key) {
+            Some(name) => write!(f, "{name}"),
+            None => write!(f, "{:?}", self.key),
+        }
+    }
+}
+
+fn modifier_from_name(name: &str) -> Option<ModifiersState> {
+    match name.to_ascii_lowercase().as_str() {
+        "ctrl" | "control" => Some(ModifiersState::CTRL),
+        "alt" | "option" | "opt" => Some(ModifiersState::ALT),
+        "shift" => Some(ModifiersState::SHIFT),
+        "cmd" | "command" | "super" | "meta" | "win" | "logo" => Some(ModifiersState::LOGO),
+        "cmdorctrl" | "primary" | "mod" => Some(primary_modifier()),
+        _ => None,
+    }
+}
+
+/// Returns the key with a name, ignoring case.
+pub(crate) fn key_from_name(name: &str) -> Option<VirtualKeyCode> {
+    KEY_NAMES
+        .iter()
+        .find(|(key_name, _)| key_name.eq_ignore_ascii_case(name))
+        .map(|(_, key)| *key)
+}
+
+fn key_name(key: VirtualKeyCode) -> Option<&'static str> {
+    KEY_NAMES
+        .iter()
+        .find(|(_, named)| *named == key)
+        .map(|(name, _)| *name)
+}
+
+macro_rules! key_names {
+    ($(($name:expr, $key:ident),)* ; $($variant:ident)*) => {
+        /// Key names used in chords and recordings. The first name of a key is the one chords are
+        /// written with, and every key can also be named by its `VirtualKeyCode` variant.
+        const KEY_NAMES: &[(&str, VirtualKeyCode)] = &[
+            $(($name, VirtualKeyCode::$key),)*
+            $((stringify!($variant), VirtualKeyCode::$variant),)*
+        ];
+    };
+}
+
+key_names! {
+    ("A", A),
+    ("B", B),
+    ("C", C),
+    ("D", D),
+    ("E", E),
+    ("F", F),
+    ("G", G),
+    ("H", H),
+    ("I", I),
+    ("J", J),
+    ("K", K),
+    ("L", L),
+    ("M", M),
+    ("N", N),
+    ("O", O),
+    ("P", P),
+    ("Q", Q),
+    ("R", R),
+    ("S", S),
+    ("T", T),
+    ("U", U),
+    ("V", V),
+    ("W", W),
+    ("X", X),
+    ("Y", Y),
+    ("Z", Z),
+    ("0", Key0),
+    ("1", Key1),
+    ("2", Key2),
+    ("3", Key3),
+    ("4", Key4),
+    ("5", Key5),
+    ("6", Key6),
+    ("7", Key7),
+    ("8", Key8),
+    ("9", Key9),
+    ("F1", F1),
+    ("F2", F2),
+    ("F3", F3),
+    ("F4", F4),
+    ("F5", F5),
+    ("F6", F6),
+    ("F7", F7),
+    ("F8", F8),
+    ("F9", F9),
+    ("F10", F10),
+    ("F11", F11),
+    ("F12", F12),
+    ("F13", F13),
+    ("F14", F14),
+    ("F15", F15),
+    ("F16", F16),
+    ("F17", F17),
+    ("F18", F18),
+    ("F19", F19),
+    ("F20", F20),
+    ("Escape", Escape),
+    ("Esc", Escape),
+    ("Enter", Return),
+    ("Return", Return),
+    ("Tab", Tab),
+    ("Space", Space),
+    ("Backspace", Back),
+    ("Delete", Delete),
+    ("Del", Delete),
+    ("Insert", Insert),
+    ("Home", Home),
+    ("End", End),
+    ("PageUp", PageUp),
+    ("PageDown", PageDown),
+    ("Up", Up),
+    ("Down", Down),
+    ("Left", Left),
+    ("Right", Right),
+    ("Plus", Plus),
+    ("-", Minus),
+    ("Minus", Minus),
+    ("=", Equals),
+    ("Equals", Equals),
+    (",", Comma),
+    ("Comma", Comma),
+    (".", Period),
+    ("Period", Period),
+    ("/", Slash),
+    ("Slash", Slash),
+    ("\\", Backslash),
+    ("Backslash", Backslash),
+    (";", Semicolon),
+    ("Semicolon", Semicolon),
+    ("'", Apostrophe),
+    ("Quote", Apostrophe),
+    ("[", LBracket),
+    ("]", RBracket),
+    ("`", Grave),
+    ("Backquote", Grave),
+;
+    Key1 Key2 Key3 Key4 Key5 Key6 Key7 Key8 Key9 Key0
+    A B C D E F G H I J K L M N O P Q R S T U V W X Y Z
+    Escape F1 F2 F3 F4 F5 F6 F7 F8 F9 F10 F11 F12 F13 F14 F15 F16 F17 F18 F19 F20 F21 F22 F23 F24
+    Snapshot Scroll Pause Insert Home Delete End PageDown PageUp Left Up Right Down
+    Back Return Space Compose Caret Numlock
+    Numpad0 Numpad1 Numpad2 Numpad3 Numpad4 Numpad5 Numpad6 Numpad7 Numpad8 Numpad9
+    NumpadAdd NumpadDivide NumpadDecimal NumpadComma NumpadEnter NumpadEquals NumpadMultiply
+    NumpadSubtract AbntC1 AbntC2 Apostrophe Apps Asterisk At Ax Backslash Calculator Capital
+    Colon Comma Convert Equals Grave Kana Kanji LAlt LBracket LControl LShift LWin Mail
+    MediaSelect MediaStop Minus Mute MyComputer NavigateForward NavigateBackward NextTrack
+    NoConvert OEM102 Period PlayPause Plus Power PrevTrack RAlt RBracket RControl RShift RWin
+    Semicolon Slash Sleep Stop Sysrq Tab Underline Unlabeled VolumeDown VolumeUp Wake WebBack
+    WebFavorites WebForward WebHome WebRefresh WebSearch WebStop Yen Copy Paste Cut
+}
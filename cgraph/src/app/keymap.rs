@@ -0,0 +1,612 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt,
+    path::Path,
+    str::FromStr,
+};
+
+use winit::event::VirtualKeyCode;
+
+use crate::app::{ChordParseError, KeyChord, KeyEvent, WindowContext};
+
+/// One or more chords pressed one after the other, such as `Ctrl+K Ctrl+S`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeySequence(pub Vec<KeyChord>);
+
+impl KeySequence {
+    /// Returns whether this sequence starts with all the chords of `prefix`.
+    pub fn starts_with(&self, prefix: &[KeyChord]) -> bool {
+        self.0.starts_with(prefix)
+    }
+}
+
+impl FromStr for KeySequence {
+    type Err = ChordParseError;
+
+    fn from_str(sequence: &str) -> Result<Self, Self::Err> {
+        let chords = sequence
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<KeyChord>, _>>()?;
+        if chords.is_empty() {
+            return Err(ChordParseError::MissingKey);
+        }
+        Ok(KeySequence(chords))
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, chord) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{chord}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The context keys that are currently true, such as `editorFocus`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyContext {
+    keys: HashSet<String>,
+}
+
+impl KeyContext {
+    /// Creates an empty context.
+    pub fn new() -> Self {
+        KeyContext::default()
+    }
+
+    /// Sets whether a context key is true.
+    pub fn set(&mut self, key: &str, value: bool) {
+        if value {
+            self.keys.insert(key.to_string());
+        } else {
+            self.keys.remove(key);
+        }
+    }
+
+    /// Returns whether a context key is true.
+    pub fn contains(&self, key: &str) -> bool {
+        self.keys.contains(key)
+    }
+}
+
+/// A condition on the context keys, such as `editorFocus && !readOnly`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum When {
+    /// True when the context key is set.
+    Key(String),
+    /// True when the condition is false.
+    Not(Box<When>),
+    /// True when both conditions are true.
+    And(Box<When>, Box<When>),
+    /// True when either condition is true.
+    Or(Box<When>, Box<When>),
+}
+
+impl When {
+    /// Evaluates the condition in a context.
+    pub fn eval(&self, context: &KeyContext) -> bool {
+        match self {
+            When::Key(key) => context.contains(key),
+            When::Not(condition) => !condition.eval(context),
+            When::And(left, right) => left.eval(context) && right.eval(context),
+            When::Or(left, right) => left.eval(context) || right.eval(context),
+        }
+    }
+}
+
+impl fmt::Display for When {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            When::Key(key) => write!(f, "{key}"),
+            When::Not(condition) => match **condition {
+                When::Key(_) | When::Not(_) => write!(f, "!{condition}"),
+                _ => write!(f, "!({condition})"),
+            },
+            When::And(left, right) => {
+                for (index, side) in [left, right].into_iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " && ")?;
+                    }
+                    match **side {
+                        When::Or(..) => write!(f, "({side})")?,
+                        _ => write!(f, "{side}")?,
+                    }
+                }
+                Ok(())
+            }
+            When::Or(left, right) => write!(f, "{left} || {right}"),
+        }
+    }
+}
+
+impl FromStr for When {
+    type Err = KeymapError;
+
+    fn from_str(condition: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(condition)?;
+        let mut parser = ConditionParser {
+            tokens: &tokens,
+            position: 0,
+        };
+        let when = parser.or()?;
+        if parser.position < tokens.len() {
+            return Err(KeymapError::Condition(format!(
+                "unexpected `{}` in `{condition}`",
+                tokens[parser.position]
+            )));
+        }
+        Ok(when)
+    }
+}
+
+fn tokenize(condition: &str) -> Result<Vec<String>, KeymapError> {
+    let mut tokens = Vec::new();
+    let mut chars = condition.chars().peekable();
+    while let Some(&character) = chars.peek() {
+        if character.is_whitespace() {
+            chars.next();
+        } else if character == '!' || character == '(' || character == ')' {
+            tokens.push(character.to_string());
+            chars.next();
+        } else if character == '&' || character == '|' {
+            chars.next();
+            if chars.next() != Some(character) {
+                return Err(KeymapError::Condition(format!(
+                    "expected `{character}{character}` in `{condition}`"
+                )));
+            }
+            tokens.push(format!("{character}{character}"));
+        } else if character.is_alphanumeric() || "_.:-".contains(character) {
+            let mut key = String::new();
+            while let Some(&character) = chars.peek() {
+                if !(character.is_alphanumeric() || "_.:-".contains(character)) {
+                    break;
+                }
+                key.push(character);
+                chars.next();
+            }
+            tokens.push(key);
+        } else {
+            return Err(KeymapError::Condition(format!(
+                "unexpected `{character}` in `{condition}`"
+            )));
+        }
+    }
+    Ok(tokens)
+}
+
+struct ConditionParser<'a> {
+    tokens: &'a [String],
+    position: usize,
+}
+
+impl<'a> ConditionParser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).map(String::as_str)
+    }
+
+    fn or(&mut self) -> Result<When, KeymapError> {
+        let mut left = self.and()?;
+        while self.peek() == Some("||") {
+            self.position += 1;
+            left = When::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<When, KeymapError> {
+        let mut left = self.not()?;
+        while self.peek() == Some("&&") {
+            self.position += 1;
+            left = When::And(Box::new(left), Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<When, KeymapError> {
+        match self.peek() {
+            Some("!") => {
+                self.position += 1;
+                Ok(When::Not(Box::new(self.not()?)))
+            }
+            Some("(") => {
+                self.position += 1;
+                let when = self.or()?;
+                if self.peek() != Some(")") {
+                    return Err(KeymapError::Condition("missing `)`".to_string()));
+                }
+                self.position += 1;
+                Ok(when)
+            }
+            Some(token) if !["&&", "||", ")"].contains(&token) => {
+                self.position += 1;
+                Ok(When::Key(token.to_string()))
+            }
+            Some(token) => Err(KeymapError::Condition(format!("unexpected `{token}`"))),
+            None => Err(KeymapError::Condition("missing context key".to_string())),
+        }
+    }
+}
+
+/// Where a binding was defined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingSource {
+    /// The application's default keymap.
+    Default,
+    /// The user's keymap file.
+    User,
+}
+
+/// A key sequence bound to a command, optionally only when a condition holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    /// The keys that run the command.
+    pub sequence: KeySequence,
+    /// The name of the command, such as `editor.save`.
+    pub command: String,
+    /// The condition under which the binding is active.
+    pub when: Option<When>,
+    /// Where the binding was defined.
+    pub source: BindingSource,
+}
+
+impl Binding {
+    fn is_active(&self, context: &KeyContext) -> bool {
+        self.when.as_ref().is_none_or(|when| when.eval(context))
+    }
+}
+
+/// Why two bindings conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// Both bindings use the same keys under the same condition, so only the later one runs.
+    Duplicate,
+    /// The first binding is a prefix of the second one, which can then never be reached.
+    Prefix,
+}
+
+/// Two bindings that cannot both be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    /// The kind of conflict.
+    pub kind: ConflictKind,
+    /// The binding that wins.
+    pub winner: Binding,
+    /// The binding that never runs.
+    pub shadowed: Binding,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ConflictKind::Duplicate => write!(
+                f,
+                "`{}` runs `{}`, shadowing `{}`",
+                self.winner.sequence, self.winner.command, self.shadowed.command
+            ),
+            ConflictKind::Prefix => write!(
+                f,
+                "`{}` runs `{}`, so `{}` for `{}` is unreachable",
+                self.winner.sequence,
+                self.winner.command,
+                self.shadowed.sequence,
+                self.shadowed.command
+            ),
+        }
+    }
+}
+
+/// Error returned when a keymap or condition cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum KeymapError {
+    /// A key sequence could not be parsed.
+    #[error(transparent)]
+    Chord(#[from] ChordParseError),
+    /// A condition could not be parsed.
+    #[error("Invalid condition: {0}")]
+    Condition(String),
+    /// A line of a keymap file is invalid. Takes the line number and the error.
+    #[error("Line {0}: {1}")]
+    Line(usize, Box<KeymapError>),
+    /// A line of a keymap file is not a binding.
+    #[error("Expected `keys = command [when condition]`, found `{0}`")]
+    Syntax(String),
+}
+
+/// The key bindings of an application, made of platform defaults and the user's overrides.
+///
+/// Keymap files have one binding per line, such as `ctrl+k ctrl+s = keymap.open when editorFocus`.
+/// A command prefixed with `-` removes the default bindings of that command, or only the one with
+/// those keys. Lines after a `[macos]`, `[windows]` or `[linux]` header only apply on that
+/// platform, until an `[all]` header. Lines starting with `#` are comments.
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    bindings: Vec<Binding>,
+    removals: Vec<(Option<KeySequence>, String)>,
+}
+
+impl Keymap {
+    /// Creates an empty keymap.
+    pub fn new() -> Self {
+        Keymap::default()
+    }
+
+    /// Parses the text of a keymap file.
+    pub fn parse(text: &str, source: BindingSource) -> Result<Self, KeymapError> {
+        let mut keymap = Keymap::new();
+        let mut applies = true;
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(section) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+                applies = section == "all" || section == std::env::consts::OS;
+                continue;
+            }
+            if !applies {
+                continue;
+            }
+            keymap
+                .parse_line(line, source)
+                .map_err(|error| KeymapError::Line(index + 1, Box::new(error)))?;
+        }
+        Ok(keymap)
+    }
+
+    fn parse_line(&mut self, line: &str, source: BindingSource) -> Result<(), KeymapError> {
+        let (keys, rest) = line
+            .split_once('=')
+            .ok_or_else(|| KeymapError::Syntax(line.to_string()))?;
+        let (command, when) = match rest.split_once(" when ") {
+            Some((command, when)) => (command.trim(), Some(when.parse::<When>()?)),
+            None => (rest.trim(), None),
+        };
+        let keys = keys.trim();
+        if command.is_empty() {
+            return Err(KeymapError::Syntax(line.to_string()));
+        }
+
+        if let Some(command) = command.strip_prefix('-') {
+            let sequence = if keys.is_empty() {
+                None
+            } else {
+                Some(keys.parse()?)
+            };
+            self.removals.push((sequence, command.to_string()));
+            return Ok(());
+        }
+        self.bindings.push(Binding {
+            sequence: keys.parse()?,
+            command: command.to_string(),
+            when,
+            source,
+        });
+        Ok(())
+    }
+
+    /// Loads a user keymap file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(Keymap::parse(&text, BindingSource::User)?)
+    }
+
+    /// Binds a key sequence to a command, taking precedence over earlier bindings.
+    pub fn bind(
+        &mut self,
+        keys: &str,
+        command: &str,
+        when: Option<&str>,
+    ) -> Result<(), KeymapError> {
+        self.bindings.push(Binding {
+            sequence: keys.parse()?,
+            command: command.to_string(),
+            when: when.map(str::parse).transpose()?,
+            source: BindingSource::Default,
+        });
+        Ok(())
+    }
+
+    /// Removes every binding of a command.
+    pub fn unbind(&mut self, command: &str) {
+        self.bindings.retain(|binding| binding.command != command);
+    }
+
+    /// Applies a keymap on top of this one, such as the user's keymap over the defaults.
+    pub fn extend(&mut self, overrides: Keymap) {
+        for (sequence, command) in &overrides.removals {
+            self.bindings.retain(|binding| {
+                binding.command != *command
+                    || sequence
+                        .as_ref()
+                        .is_some_and(|sequence| binding.sequence != *sequence)
+            });
+        }
+        self.bindings.extend(overrides.bindings);
+        self.removals.extend(overrides.removals);
+    }
+
+    /// Returns the bindings, from lowest to highest precedence.
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+
+    /// Returns the first sequence bound to a command, to show it in menus.
+    pub fn sequence_for(&self, command: &str) -> Option<&KeySequence> {
+        self.bindings
+            .iter()
+            .rev()
+            .find(|binding| binding.command == command)
+            .map(|binding| &binding.sequence)
+    }
+
+    /// Reports the bindings that can never run because of another binding.
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut conflicts = Vec::new();
+        for (index, earlier) in self.bindings.iter().enumerate() {
+            for later in &self.bindings[index + 1..] {
+                if earlier.when != later.when {
+                    continue;
+                }
+                if earlier.sequence == later.sequence {
+                    if earlier.command != later.command {
+                        conflicts.push(Conflict {
+                            kind: ConflictKind::Duplicate,
+                            winner: later.clone(),
+                            shadowed: earlier.clone(),
+                        });
+                    }
+                } else if later.sequence.starts_with(&earlier.sequence.0) {
+                    conflicts.push(Conflict {
+                        kind: ConflictKind::Prefix,
+                        winner: earlier.clone(),
+                        shadowed: later.clone(),
+                    });
+                } else if earlier.sequence.starts_with(&later.sequence.0) {
+                    conflicts.push(Conflict {
+                        kind: ConflictKind::Prefix,
+                        winner: later.clone(),
+                        shadowed: earlier.clone(),
+                    });
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+/// The result of feeding a chord to a `KeyResolver`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyResolution {
+    /// The chords pressed so far form a binding of this command.
+    Command(String),
+    /// The chords pressed so far start a longer binding, so the next chord is awaited.
+    Pending,
+    /// No binding starts with the chords pressed so far.
+    Unmatched,
+}
+
+/// Matches pressed chords against a keymap, keeping track of partially typed sequences.
+#[derive(Debug, Clone, Default)]
+pub struct KeyResolver {
+    pending: Vec<KeyChord>,
+}
+
+impl KeyResolver {
+    /// Creates a resolver with no pending chords.
+    pub fn new() -> Self {
+        KeyResolver::default()
+    }
+
+    /// Returns the chords of the sequence being typed.
+    pub fn pending(&self) -> &[KeyChord] {
+        &self.pending
+    }
+
+    /// Adds a chord to the sequence being typed and resolves it.
+    pub fn feed(
+        &mut self,
+        keymap: &Keymap,
+        chord: KeyChord,
+        context: &KeyContext,
+    ) -> KeyResolution {
+        self.pending.push(chord);
+        let mut longer = false;
+        // Later bindings take precedence, so user bindings override the defaults.
+        for binding in keymap.bindings.iter().rev() {
+            if !binding.is_active(context) || !binding.sequence.starts_with(&self.pending) {
+                continue;
+            }
+            if binding.sequence.0.len() == self.pending.len() {
+                self.pending.clear();
+                return KeyResolution::Command(binding.command.clone());
+            }
+            longer = true;
+        }
+        if longer {
+            KeyResolution::Pending
+        } else {
+            self.pending.clear();
+            KeyResolution::Unmatched
+        }
+    }
+
+    /// Drops the chords of the sequence being typed.
+    pub fn reset(&mut self) {
+        self.pending.clear();
+    }
+}
+
+type CommandFunction<State> = dyn FnMut(&mut State, &mut WindowContext) + 'static;
+
+/// Named commands that key bindings, menus or a command palette can run.
+pub struct Commands<State = ()> {
+    handlers: HashMap<String, Box<CommandFunction<State>>>,
+}
+
+impl<State> Default for Commands<State> {
+    fn default() -> Self {
+        Commands {
+            handlers: HashMap::new(),
+        }
+    }
+}
+
+impl<State> Commands<State> {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Commands::default()
+    }
+
+    /// Registers a command, replacing any command with the same name.
+    pub fn register<F>(&mut self, name: &str, handler: F)
+    where
+        F: FnMut(&mut State, &mut WindowContext) + 'static,
+    {
+        self.handlers.insert(name.to_string(), Box::new(handler));
+    }
+
+    /// Returns whether a command is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+
+    /// Returns the names of the registered commands.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.handlers.keys().map(String::as_str)
+    }
+
+    /// Runs a command, returning `false` if no command has that name.
+    pub fn run(&mut self, name: &str, state: &mut State, context: &mut WindowContext) -> bool {
+        match self.handlers.get_mut(name) {
+            Some(handler) => {
+                handler(state, context);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Returns whether a key is a modifier, which never completes a chord by itself.
+pub(crate) fn is_modifier_key(event: &KeyEvent) -> bool {
+    matches!(
+        event.key,
+        Some(
+            VirtualKeyCode::LShift
+                | VirtualKeyCode::RShift
+                | VirtualKeyCode::LControl
+                | VirtualKeyCode::RControl
+                | VirtualKeyCode::LAlt
+                | VirtualKeyCode::RAlt
+                | VirtualKeyCode::LWin
+                | VirtualKeyCode::RWin
+        )
+    )
+}
@@ -0,0 +1,103 @@
+use std::{collections::VecDeque, time::Duration};
+
+use glam::{Vec2, Vec4};
+
+use crate::object::{Object, Vertex};
+
+const SAMPLES: usize = 120;
+const BAR_WIDTH: f32 = 2.0;
+const GRAPH_HEIGHT: f32 = 80.0;
+const MARGIN: f32 = 8.0;
+const PADDING: f32 = 4.0;
+const Z_INDEX: f32 = 99.0;
+// The frame time at the top of the graph, twice the budget of a 60 Hz display.
+const SCALE: Duration = Duration::from_micros(33_333);
+const BUDGET: Duration = Duration::from_micros(16_667);
+
+/// The times of the last frames of a window, drawn as a bar graph in its top-right corner.
+#[derive(Default)]
+pub(crate) struct FrameHistory {
+    frames: VecDeque<Duration>,
+}
+
+impl FrameHistory {
+    /// Records the time a frame took, dropping the oldest one when the graph is full.
+    pub(crate) fn push(&mut self, frame_time: Duration) {
+        if self.frames.len() == SAMPLES {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame_time);
+    }
+
+    /// Builds the graph for a framebuffer of the given size, in physical pixels.
+    pub(crate) fn to_object(&self, framebuffer_size: [f32; 2]) -> Object {
+        let width = SAMPLES as f32 * BAR_WIDTH + PADDING * 2.0;
+        let height = GRAPH_HEIGHT + PADDING * 2.0;
+        let left = framebuffer_size[0] - width - MARGIN;
+        let top = MARGIN;
+        let bottom = top + PADDING + GRAPH_HEIGHT;
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        push_rect(
+            &mut vertices,
+            &mut indices,
+            [left, top, left + width, top + height],
+            Vec4::new(0.0, 0.0, 0.0, 0.6),
+        );
+
+        // The newest frame is drawn on the right.
+        let first = left + PADDING + (SAMPLES - self.frames.len()) as f32 * BAR_WIDTH;
+        for (index, frame_time) in self.frames.iter().enumerate() {
+            let ratio = (frame_time.as_secs_f32() / SCALE.as_secs_f32()).min(1.0);
+            let x = first + index as f32 * BAR_WIDTH;
+            push_rect(
+                &mut vertices,
+                &mut indices,
+                [
+                    x,
+                    bottom - ratio * GRAPH_HEIGHT,
+                    x + BAR_WIDTH - 0.5,
+                    bottom,
+                ],
+                bar_color(*frame_time),
+            );
+        }
+
+        let budget = bottom - BUDGET.as_secs_f32() / SCALE.as_secs_f32() * GRAPH_HEIGHT;
+        push_rect(
+            &mut vertices,
+            &mut indices,
+            [left + PADDING, budget, left + width - PADDING, budget + 1.0],
+            Vec4::new(1.0, 1.0, 1.0, 0.5),
+        );
+
+        let mut object = Object::new(vertices, indices);
+        object.position = Vec2::new(0.0, 0.0);
+        object.scale = Vec2::new(1.0, 1.0);
+        object.update_buffer();
+        object
+    }
+}
+
+fn bar_color(frame_time: Duration) -> Vec4 {
+    if frame_time <= BUDGET {
+        Vec4::new(0.3, 0.85, 0.4, 1.0)
+    } else if frame_time <= SCALE {
+        Vec4::new(0.95, 0.8, 0.2, 1.0)
+    } else {
+        Vec4::new(0.95, 0.3, 0.25, 1.0)
+    }
+}
+
+fn push_rect(vertices: &mut Vec<Vertex>, indices: &mut Vec<u32>, rect: [f32; 4], color: Vec4) {
+    let [left, top, right, bottom] = rect;
+    let first = vertices.len() as u32;
+    vertices.extend([
+        Vertex::new(left, bottom, Z_INDEX, color, Vec2::new(0.0, 0.0)),
+        Vertex::new(right, bottom, Z_INDEX, color, Vec2::new(1.0, 0.0)),
+        Vertex::new(right, top, Z_INDEX, color, Vec2::new(1.0, 1.0)),
+        Vertex::new(left, top, Z_INDEX, color, Vec2::new(0.0, 1.0)),
+    ]);
+    indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+}
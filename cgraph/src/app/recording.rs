@@ -0,0 +1,938 @@
+use std::{
+    error::Error,
+    fmt,
+    fs::File,
+    io::{self, LineWriter, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    event::{
+        DeviceEvent, DeviceId, ElementState, Ime, KeyboardInput, ModifiersState, MouseButton,
+        MouseScrollDelta, Touch, TouchPhase, VirtualKeyCode, WindowEvent,
+    },
+    window::Theme,
+};
+
+use crate::app::keyboard::key_from_name;
+
+/// The first word of a recording file, followed by the format version.
+const HEADER: &str = "cgraph-recording";
+const VERSION: u32 = 1;
+
+/// A window event that can be written to a recording and replayed, mirroring `CoreWindowEvent`.
+///
+/// Positions and sizes are in physical pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordedWindowEvent {
+    /// The window was resized to a width and height.
+    Resized(u32, u32),
+    /// The window was moved to a position on the screen.
+    Moved(i32, i32),
+    /// The user asked to close the window.
+    Closing,
+    /// The window was destroyed.
+    Destroyed,
+    /// A file was dropped onto the window.
+    DroppedFile(PathBuf),
+    /// A file is being hovered over the window.
+    HoveredFile(PathBuf),
+    /// The hovering of a file was cancelled.
+    HoveredFileCancelled,
+    /// The window gained or lost focus.
+    Focused(bool),
+    /// A character was typed.
+    ReceivedChar(char),
+    /// A key was pressed or released.
+    KeyboardInput {
+        /// The platform scancode of the key.
+        scancode: u32,
+        /// Whether the key was pressed or released.
+        state: ElementState,
+        /// The logical key, if known.
+        key: Option<VirtualKeyCode>,
+    },
+    /// The input method sent a composition event.
+    Ime(Ime),
+    /// The held modifiers changed.
+    ModifiersChanged(ModifiersState),
+    /// The cursor moved to a position in the window.
+    CursorMoved(f64, f64),
+    /// The cursor entered the window.
+    CursorEntered,
+    /// The cursor left the window.
+    CursorLeft,
+    /// The mouse wheel or touchpad scrolled.
+    MouseScroll(MouseScrollDelta, TouchPhase),
+    /// A mouse button was pressed or released.
+    MouseClick(MouseButton, ElementState),
+    /// The touchpad was pressed. Takes the pressure and the click stage.
+    TouchpadPressure(f32, i64),
+    /// An axis of an input device moved. Takes the axis and the value.
+    AxisMotion(u32, f64),
+    /// A finger touched, moved on or left the screen.
+    Touch {
+        /// The identifier of the finger.
+        id: u64,
+        /// The phase of the touch.
+        phase: TouchPhase,
+        /// The x coordinate of the touch.
+        x: f64,
+        /// The y coordinate of the touch.
+        y: f64,
+    },
+    /// The scale factor changed. Takes the scale factor and the new width and height.
+    DPIChanged(f64, u32, u32),
+    /// The system theme changed.
+    ThemeChanged(Theme),
+    /// The window was hidden or shown again.
+    Occluded(bool),
+}
+
+/// A device event that can be written to a recording and replayed, mirroring `CoreDeviceEvent`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordedDeviceEvent {
+    /// A device was connected.
+    DeviceConnected,
+    /// A device was disconnected.
+    DeviceDisconnected,
+    /// The mouse moved by a delta.
+    MouseMotion(f64, f64),
+    /// The mouse wheel scrolled.
+    MouseWheel(MouseScrollDelta),
+    /// An axis moved. Takes the axis and the value.
+    Motion(u32, f64),
+    /// A button was pressed or released.
+    Button(u32, ElementState),
+    /// A key was pressed or released.
+    Key {
+        /// The platform scancode of the key.
+        scancode: u32,
+        /// Whether the key was pressed or released.
+        state: ElementState,
+        /// The logical key, if known.
+        key: Option<VirtualKeyCode>,
+    },
+    /// A character was typed.
+    Text(char),
+}
+
+/// An event stored in a recording.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordedEvent {
+    /// An event of the recorded window.
+    Window(RecordedWindowEvent),
+    /// An event of an input device.
+    Device(RecordedDeviceEvent),
+}
+
+/// A recorded event with the time it arrived, counted from the start of the recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedEvent {
+    /// The time since the recording started.
+    pub time: Duration,
+    /// The event.
+    pub event: RecordedEvent,
+}
+
+/// A stream of events recorded from a window, which can be saved and replayed.
+///
+/// Recordings are text files with one event per line, like `16000 window cursor-moved 10 20`,
+/// where the first number is the time in microseconds.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Recording {
+    /// The recorded events, in the order they arrived.
+    pub events: Vec<TimedEvent>,
+}
+
+/// Error returned when a recording cannot be parsed.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum RecordingError {
+    /// The file does not start with the recording header.
+    #[error("File is not an event recording")]
+    MissingHeader,
+    /// The recording was written by a newer format version.
+    #[error("Unsupported recording version {0}")]
+    UnsupportedVersion(u32),
+    /// A line could not be parsed. Takes the line number and the problem.
+    #[error("Line {0}: {1}")]
+    Line(usize, String),
+}
+
+impl Recording {
+    /// Creates an empty recording.
+    pub fn new() -> Self {
+        Recording::default()
+    }
+
+    /// Adds an event at a time since the start of the recording.
+    pub fn push(&mut self, time: Duration, event: RecordedEvent) {
+        self.events.push(TimedEvent { time, event });
+    }
+
+    /// Returns the time of the last event.
+    pub fn duration(&self) -> Duration {
+        self.events
+            .last()
+            .map(|event| event.time)
+            .unwrap_or_default()
+    }
+
+    /// Parses a recording from its text.
+    pub fn parse(text: &str) -> Result<Self, RecordingError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let (_, header) = lines.next().ok_or(RecordingError::MissingHeader)?;
+        let version = header
+            .strip_prefix(HEADER)
+            .and_then(|version| version.trim().parse::<u32>().ok())
+            .ok_or(RecordingError::MissingHeader)?;
+        if version > VERSION {
+            return Err(RecordingError::UnsupportedVersion(version));
+        }
+
+        let mut recording = Recording::new();
+        for (number, line) in lines {
+            let event =
+                parse_timed_event(line).map_err(|error| RecordingError::Line(number, error))?;
+            recording.events.push(event);
+        }
+        Ok(recording)
+    }
+
+    /// Loads a recording from a file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(Recording::parse(&text)?)
+    }
+
+    /// Writes the recording to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+}
+
+impl FromStr for Recording {
+    type Err = RecordingError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Recording::parse(text)
+    }
+}
+
+impl fmt::Display for Recording {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{HEADER} {VERSION}")?;
+        for event in &self.events {
+            writeln!(f, "{event}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TimedEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.time.as_micros(), self.event)
+    }
+}
+
+impl fmt::Display for RecordedEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordedEvent::Window(event) => write!(f, "window {event}"),
+            RecordedEvent::Device(event) => write!(f, "device {event}"),
+        }
+    }
+}
+
+impl fmt::Display for RecordedWindowEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordedWindowEvent::Resized(width, height) => write!(f, "resized {width} {height}"),
+            RecordedWindowEvent::Moved(x, y) => write!(f, "moved {x} {y}"),
+            RecordedWindowEvent::Closing => write!(f, "closing"),
+            RecordedWindowEvent::Destroyed => write!(f, "destroyed"),
+            RecordedWindowEvent::DroppedFile(path) => {
+                write!(f, "dropped-file {}", quote(&path.to_string_lossy()))
+            }
+            RecordedWindowEvent::HoveredFile(path) => {
+                write!(f, "hovered-file {}", quote(&path.to_string_lossy()))
+            }
+            RecordedWindowEvent::HoveredFileCancelled => write!(f, "hovered-file-cancelled"),
+            RecordedWindowEvent::Focused(focused) => write!(f, "focused {focused}"),
+            RecordedWindowEvent::ReceivedChar(character) => {
+                write!(f, "char {}", quote(&character.to_string()))
+            }
+            RecordedWindowEvent::KeyboardInput {
+                scancode,
+                state,
+                key,
+            } => write!(
+                f,
+                "key {scancode} {} {}",
+                state_name(*state),
+                key_name(*key)
+            ),
+            RecordedWindowEvent::Ime(Ime::Enabled) => write!(f, "ime enabled"),
+            RecordedWindowEvent::Ime(Ime::Disabled) => write!(f, "ime disabled"),
+            RecordedWindowEvent::Ime(Ime::Commit(text)) => write!(f, "ime commit {}", quote(text)),
+            RecordedWindowEvent::Ime(Ime::Preedit(text, cursor)) => {
+                write!(f, "ime preedit {}", quote(text))?;
+                match cursor {
+                    Some((start, end)) => write!(f, " {start} {end}"),
+                    None => write!(f, " -"),
+                }
+            }
+            RecordedWindowEvent::ModifiersChanged(modifiers) => {
+                write!(f, "modifiers {}", modifiers_name(*modifiers))
+            }
+            RecordedWindowEvent::CursorMoved(x, y) => write!(f, "cursor-moved {x} {y}"),
+            RecordedWindowEvent::CursorEntered => write!(f, "cursor-entered"),
+            RecordedWindowEvent::CursorLeft => write!(f, "cursor-left"),
+            RecordedWindowEvent::MouseScroll(delta, phase) => {
+                write!(
+                    f,
+                    "mouse-scroll {} {}",
+                    delta_name(*delta),
+                    phase_name(*phase)
+                )
+            }
+            RecordedWindowEvent::MouseClick(button, state) => {
+                write!(
+                    f,
+                    "mouse-click {} {}",
+                    button_name(*button),
+                    state_name(*state)
+                )
+            }
+            RecordedWindowEvent::TouchpadPressure(pressure, stage) => {
+                write!(f, "touchpad-pressure {pressure} {stage}")
+            }
+            RecordedWindowEvent::AxisMotion(axis, value) => write!(f, "axis-motion {axis} {value}"),
+            RecordedWindowEvent::Touch { id, phase, x, y } => {
+                write!(f, "touch {id} {} {x} {y}", phase_name(*phase))
+            }
+            RecordedWindowEvent::DPIChanged(scale_factor, width, height) => {
+                write!(f, "dpi-changed {scale_factor} {width} {height}")
+            }
+            RecordedWindowEvent::ThemeChanged(theme) => write!(
+                f,
+                "theme-changed {}",
+                match theme {
+                    Theme::Light => "light",
+                    Theme::Dark => "dark",
+                }
+            ),
+            RecordedWindowEvent::Occluded(occluded) => write!(f, "occluded {occluded}"),
+        }
+    }
+}
+
+impl fmt::Display for RecordedDeviceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordedDeviceEvent::DeviceConnected => write!(f, "connected"),
+            RecordedDeviceEvent::DeviceDisconnected => write!(f, "disconnected"),
+            RecordedDeviceEvent::MouseMotion(dx, dy) => write!(f, "mouse-motion {dx} {dy}"),
+            RecordedDeviceEvent::MouseWheel(delta) => {
+                write!(f, "mouse-wheel {}", delta_name(*delta))
+            }
+            RecordedDeviceEvent::Motion(axis, value) => write!(f, "motion {axis} {value}"),
+            RecordedDeviceEvent::Button(button, state) => {
+                write!(f, "button {button} {}", state_name(*state))
+            }
+            RecordedDeviceEvent::Key {
+                scancode,
+                state,
+                key,
+            } => write!(
+                f,
+                "key {scancode} {} {}",
+                state_name(*state),
+                key_name(*key)
+            ),
+            RecordedDeviceEvent::Text(character) => {
+                write!(f, "text {}", quote(&character.to_string()))
+            }
+        }
+    }
+}
+
+impl RecordedWindowEvent {
+    /// Converts a winit event, or returns `None` for events that are not recorded.
+    pub(crate) fn from_winit(event: &WindowEvent) -> Option<Self> {
+        Some(match event {
+            WindowEvent::Resized(size) => RecordedWindowEvent::Resized(size.width, size.height),
+            WindowEvent::Moved(position) => RecordedWindowEvent::Moved(position.x, position.y),
+            WindowEvent::CloseRequested => RecordedWindowEvent::Closing,
+            WindowEvent::Destroyed => RecordedWindowEvent::Destroyed,
+            WindowEvent::DroppedFile(path) => RecordedWindowEvent::DroppedFile(path.clone()),
+            WindowEvent::HoveredFile(path) => RecordedWindowEvent::HoveredFile(path.clone()),
+            WindowEvent::HoveredFileCancelled => RecordedWindowEvent::HoveredFileCancelled,
+            WindowEvent::ReceivedCharacter(character) => {
+                RecordedWindowEvent::ReceivedChar(*character)
+            }
+            WindowEvent::Focused(focused) => RecordedWindowEvent::Focused(*focused),
+            WindowEvent::KeyboardInput { input, .. } => RecordedWindowEvent::KeyboardInput {
+                scancode: input.scancode,
+                state: input.state,
+                key: input.virtual_keycode,
+            },
+            WindowEvent::ModifiersChanged(modifiers) => {
+                RecordedWindowEvent::ModifiersChanged(*modifiers)
+            }
+            WindowEvent::Ime(ime) => RecordedWindowEvent::Ime(ime.clone()),
+            WindowEvent::CursorMoved { position, .. } => {
+                RecordedWindowEvent::CursorMoved(position.x, position.y)
+            }
+            WindowEvent::CursorEntered { .. } => RecordedWindowEvent::CursorEntered,
+            WindowEvent::CursorLeft { .. } => RecordedWindowEvent::CursorLeft,
+            WindowEvent::MouseWheel { delta, phase, .. } => {
+                RecordedWindowEvent::MouseScroll(*delta, *phase)
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                RecordedWindowEvent::MouseClick(*button, *state)
+            }
+            WindowEvent::TouchpadPressure {
+                pressure, stage, ..
+            } => RecordedWindowEvent::TouchpadPressure(*pressure, *stage),
+            WindowEvent::AxisMotion { axis, value, .. } => {
+                RecordedWindowEvent::AxisMotion(*axis, *value)
+            }
+            WindowEvent::Touch(touch) => RecordedWindowEvent::Touch {
+                id: touch.id,
+                phase: touch.phase,
+                x: touch.location.x,
+                y: touch.location.y,
+            },
+            WindowEvent::ScaleFactorChanged {
+                scale_factor,
+                new_inner_size,
+            } => RecordedWindowEvent::DPIChanged(
+                *scale_factor,
+                new_inner_size.width,
+                new_inner_size.height,
+            ),
+            WindowEvent::ThemeChanged(theme) => RecordedWindowEvent::ThemeChanged(*theme),
+            WindowEvent::Occluded(occluded) => RecordedWindowEvent::Occluded(*occluded),
+            _ => return None,
+        })
+    }
+
+    /// Converts the event back to a winit event. `size` holds the new size of a `DPIChanged`.
+    #[allow(deprecated)]
+    pub(crate) fn to_winit<'a>(&self, size: &'a mut PhysicalSize<u32>) -> WindowEvent<'a> {
+        let device_id = replay_device_id();
+        match self {
+            RecordedWindowEvent::Resized(width, height) => {
+                WindowEvent::Resized(PhysicalSize::new(*width, *height))
+            }
+            RecordedWindowEvent::Moved(x, y) => WindowEvent::Moved(PhysicalPosition::new(*x, *y)),
+            RecordedWindowEvent::Closing => WindowEvent::CloseRequested,
+            RecordedWindowEvent::Destroyed => WindowEvent::Destroyed,
+            RecordedWindowEvent::DroppedFile(path) => WindowEvent::DroppedFile(path.clone()),
+            RecordedWindowEvent::HoveredFile(path) => WindowEvent::HoveredFile(path.clone()),
+            RecordedWindowEvent::HoveredFileCancelled => WindowEvent::HoveredFileCancelled,
+            RecordedWindowEvent::Focused(focused) => WindowEvent::Focused(*focused),
+            RecordedWindowEvent::ReceivedChar(character) => {
+                WindowEvent::ReceivedCharacter(*character)
+            }
+            RecordedWindowEvent::KeyboardInput {
+                scancode,
+                state,
+                key,
+            } => WindowEvent::KeyboardInput {
+                device_id,
+                input: keyboard_input(*scancode, *state, *key),
+                is_synthetic: false,
+            },
+            RecordedWindowEvent::Ime(ime) => WindowEvent::Ime(ime.clone()),
+            RecordedWindowEvent::ModifiersChanged(modifiers) => {
+                WindowEvent::ModifiersChanged(*modifiers)
+            }
+            RecordedWindowEvent::CursorMoved(x, y) => WindowEvent::CursorMoved {
+                device_id,
+                position: PhysicalPosition::new(*x, *y),
+                modifiers: ModifiersState::empty(),
+            },
+            RecordedWindowEvent::CursorEntered => WindowEvent::CursorEntered { device_id },
+            RecordedWindowEvent::CursorLeft => WindowEvent::CursorLeft { device_id },
+            RecordedWindowEvent::MouseScroll(delta, phase) => WindowEvent::MouseWheel {
+                device_id,
+                delta: *delta,
+                phase: *phase,
+                modifiers: ModifiersState::empty(),
+            },
+            RecordedWindowEvent::MouseClick(button, state) => WindowEvent::MouseInput {
+                device_id,
+                state: *state,
+                button: *button,
+                modifiers: ModifiersState::empty(),
+            },
+            RecordedWindowEvent::TouchpadPressure(pressure, stage) => {
+                WindowEvent::TouchpadPressure {
+                    device_id,
+                    pressure: *pressure,
+                    stage: *stage,
+                }
+            }
+            RecordedWindowEvent::AxisMotion(axis, value) => WindowEvent::AxisMotion {
+                device_id,
+                axis: *axis,
+                value: *value,
+            },
+            RecordedWindowEvent::Touch { id, phase, x, y } => WindowEvent::Touch(Touch {
+                device_id,
+                phase: *phase,
+                location: PhysicalPosition::new(*x, *y),
+                force: None,
+                id: *id,
+            }),
+            RecordedWindowEvent::DPIChanged(scale_factor, width, height) => {
+                *size = PhysicalSize::new(*width, *height);
+                WindowEvent::ScaleFactorChanged {
+                    scale_factor: *scale_factor,
+                    new_inner_size: size,
+                }
+            }
+            RecordedWindowEvent::ThemeChanged(theme) => WindowEvent::ThemeChanged(*theme),
+            RecordedWindowEvent::Occluded(occluded) => WindowEvent::Occluded(*occluded),
+        }
+    }
+}
+
+impl RecordedDeviceEvent {
+    /// Converts a winit event.
+    pub(crate) fn from_winit(event: &DeviceEvent) -> Self {
+        match event {
+            DeviceEvent::Added => RecordedDeviceEvent::DeviceConnected,
+            DeviceEvent::Removed => RecordedDeviceEvent::DeviceDisconnected,
+            DeviceEvent::MouseMotion { delta } => {
+                RecordedDeviceEvent::MouseMotion(delta.0, delta.1)
+            }
+            DeviceEvent::MouseWheel { delta } => RecordedDeviceEvent::MouseWheel(*delta),
+            DeviceEvent::Motion { axis, value } => RecordedDeviceEvent::Motion(*axis, *value),
+            DeviceEvent::Button { button, state } => RecordedDeviceEvent::Button(*button, *state),
+            DeviceEvent::Key(input) => RecordedDeviceEvent::Key {
+                scancode: input.scancode,
+                state: input.state,
+                key: input.virtual_keycode,
+            },
+            DeviceEvent::Text { codepoint } => RecordedDeviceEvent::Text(*codepoint),
+        }
+    }
+
+    /// Converts the event back to a winit event.
+    pub(crate) fn to_winit(&self) -> DeviceEvent {
+        match self {
+            RecordedDeviceEvent::DeviceConnected => DeviceEvent::Added,
+            RecordedDeviceEvent::DeviceDisconnected => DeviceEvent::Removed,
+            RecordedDeviceEvent::MouseMotion(dx, dy) => {
+                DeviceEvent::MouseMotion { delta: (*dx, *dy) }
+            }
+            RecordedDeviceEvent::MouseWheel(delta) => DeviceEvent::MouseWheel { delta: *delta },
+            RecordedDeviceEvent::Motion(axis, value) => DeviceEvent::Motion {
+                axis: *axis,
+                value: *value,
+            },
+            RecordedDeviceEvent::Button(button, state) => DeviceEvent::Button {
+                button: *button,
+                state: *state,
+            },
+            RecordedDeviceEvent::Key {
+                scancode,
+                state,
+                key,
+            } => DeviceEvent::Key(keyboard_input(*scancode, *state, *key)),
+            RecordedDeviceEvent::Text(character) => DeviceEvent::Text {
+                codepoint: *character,
+            },
+        }
+    }
+}
+
+/// The device id given to replayed events.
+fn replay_device_id() -> DeviceId {
+    // SAFETY: replayed events only reach the window's own handlers, never a winit function.
+    unsafe { DeviceId::dummy() }
+}
+
+#[allow(deprecated)]
+fn keyboard_input(
+    scancode: u32,
+    state: ElementState,
+    key: Option<VirtualKeyCode>,
+) -> KeyboardInput {
+    KeyboardInput {
+        scancode,
+        state,
+        virtual_keycode: key,
+        // Handlers track the modifiers through `ModifiersChanged`, which is recorded too.
+        modifiers: ModifiersState::empty(),
+    }
+}
+
+/// Writes the events of a window to a file as they arrive.
+pub(crate) struct EventRecorder {
+    start: Instant,
+    writer: LineWriter<File>,
+}
+
+impl EventRecorder {
+    /// Creates the file and writes the header.
+    pub(crate) fn create(path: &Path) -> io::Result<Self> {
+        let mut writer = LineWriter::new(File::create(path)?);
+        writeln!(writer, "{HEADER} {VERSION}")?;
+        Ok(EventRecorder {
+            start: Instant::now(),
+            writer,
+        })
+    }
+
+    /// Appends an event. Each line is flushed right away, so a crash keeps the events before it.
+    pub(crate) fn record(&mut self, event: RecordedEvent) -> io::Result<()> {
+        let event = TimedEvent {
+            time: self.start.elapsed(),
+            event,
+        };
+        writeln!(self.writer, "{event}")
+    }
+}
+
+/// How a replay ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayEnd {
+    /// Every event of the recording was replayed.
+    Finished,
+    /// A handler closed the window or exited before the recording ended.
+    Closed,
+}
+
+fn parse_timed_event(line: &str) -> Result<TimedEvent, String> {
+    let tokens = tokenize(line)?;
+    let mut tokens = Tokens {
+        tokens: tokens.iter().map(String::as_str).collect(),
+        position: 0,
+    };
+    let time = Duration::from_micros(tokens.parse("time")?);
+    let event = match tokens.next("event source")? {
+        "window" => RecordedEvent::Window(parse_window_event(&mut tokens)?),
+        "device" => RecordedEvent::Device(parse_device_event(&mut tokens)?),
+        other => return Err(format!("Unknown event source `{other}`")),
+    };
+    if let Some(extra) = tokens.tokens.get(tokens.position) {
+        return Err(format!("Unexpected `{extra}`"));
+    }
+    Ok(TimedEvent { time, event })
+}
+
+fn parse_window_event(tokens: &mut Tokens) -> Result<RecordedWindowEvent, String> {
+    Ok(match tokens.next("event")? {
+        "resized" => RecordedWindowEvent::Resized(tokens.parse("width")?, tokens.parse("height")?),
+        "moved" => RecordedWindowEvent::Moved(tokens.parse("x")?, tokens.parse("y")?),
+        "closing" => RecordedWindowEvent::Closing,
+        "destroyed" => RecordedWindowEvent::Destroyed,
+        "dropped-file" => RecordedWindowEvent::DroppedFile(tokens.next("path")?.into()),
+        "hovered-file" => RecordedWindowEvent::HoveredFile(tokens.next("path")?.into()),
+        "hovered-file-cancelled" => RecordedWindowEvent::HoveredFileCancelled,
+        "focused" => RecordedWindowEvent::Focused(tokens.parse("focus")?),
+        "char" => RecordedWindowEvent::ReceivedChar(tokens.character()?),
+        "key" => RecordedWindowEvent::KeyboardInput {
+            scancode: tokens.parse("scancode")?,
+            state: tokens.state()?,
+            key: tokens.key()?,
+        },
+        "ime" => RecordedWindowEvent::Ime(match tokens.next("IME event")? {
+            "enabled" => Ime::Enabled,
+            "disabled" => Ime::Disabled,
+            "commit" => Ime::Commit(tokens.next("text")?.to_string()),
+            "preedit" => {
+                let text = tokens.next("text")?.to_string();
+                let cursor = if tokens.peek() == Some("-") {
+                    tokens.position += 1;
+                    None
+                } else {
+                    Some((tokens.parse("cursor start")?, tokens.parse("cursor end")?))
+                };
+                Ime::Preedit(text, cursor)
+            }
+            other => return Err(format!("Unknown IME event `{other}`")),
+        }),
+        "modifiers" => RecordedWindowEvent::ModifiersChanged(tokens.modifiers()?),
+        "cursor-moved" => RecordedWindowEvent::CursorMoved(tokens.parse("x")?, tokens.parse("y")?),
+        "cursor-entered" => RecordedWindowEvent::CursorEntered,
+        "cursor-left" => RecordedWindowEvent::CursorLeft,
+        "mouse-scroll" => RecordedWindowEvent::MouseScroll(tokens.delta()?, tokens.phase()?),
+        "mouse-click" => RecordedWindowEvent::MouseClick(tokens.button()?, tokens.state()?),
+        "touchpad-pressure" => {
+            RecordedWindowEvent::TouchpadPressure(tokens.parse("pressure")?, tokens.parse("stage")?)
+        }
+        "axis-motion" => {
+            RecordedWindowEvent::AxisMotion(tokens.parse("axis")?, tokens.parse("value")?)
+        }
+        "touch" => RecordedWindowEvent::Touch {
+            id: tokens.parse("touch id")?,
+            phase: tokens.phase()?,
+            x: tokens.parse("x")?,
+            y: tokens.parse("y")?,
+        },
+        "dpi-changed" => RecordedWindowEvent::DPIChanged(
+            tokens.parse("scale factor")?,
+            tokens.parse("width")?,
+            tokens.parse("height")?,
+        ),
+        "theme-changed" => RecordedWindowEvent::ThemeChanged(match tokens.next("theme")? {
+            "light" => Theme::Light,
+            "dark" => Theme::Dark,
+            other => return Err(format!("Unknown theme `{other}`")),
+        }),
+        "occluded" => RecordedWindowEvent::Occluded(tokens.parse("occlusion")?),
+        other => return Err(format!("Unknown window event `{other}`")),
+    })
+}
+
+fn parse_device_event(tokens: &mut Tokens) -> Result<RecordedDeviceEvent, String> {
+    Ok(match tokens.next("event")? {
+        "connected" => RecordedDeviceEvent::DeviceConnected,
+        "disconnected" => RecordedDeviceEvent::DeviceDisconnected,
+        "mouse-motion" => {
+            RecordedDeviceEvent::MouseMotion(tokens.parse("dx")?, tokens.parse("dy")?)
+        }
+        "mouse-wheel" => RecordedDeviceEvent::MouseWheel(tokens.delta()?),
+        "motion" => RecordedDeviceEvent::Motion(tokens.parse("axis")?, tokens.parse("value")?),
+        "button" => RecordedDeviceEvent::Button(tokens.parse("button")?, tokens.state()?),
+        "key" => RecordedDeviceEvent::Key {
+            scancode: tokens.parse("scancode")?,
+            state: tokens.state()?,
+            key: tokens.key()?,
+        },
+        "text" => RecordedDeviceEvent::Text(tokens.character()?),
+        other => return Err(format!("Unknown device event `{other}`")),
+    })
+}
+
+/// Splits a line on whitespace, keeping quoted strings together and unescaping them.
+fn tokenize(line: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut token = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some('n') => token.push('\n'),
+                        Some('r') => token.push('\r'),
+                        Some('t') => token.push('\t'),
+                        Some(escaped @ ('"' | '\\')) => token.push(escaped),
+                        _ => return Err("Invalid escape in string".to_string()),
+                    },
+                    Some(c) => token.push(c),
+                    None => return Err("Unterminated string".to_string()),
+                }
+            }
+            tokens.push(token);
+        } else {
+            let mut token = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                token.push(c);
+                chars.next();
+            }
+            tokens.push(token);
+        }
+    }
+    Ok(tokens)
+}
+
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+struct Tokens<'a> {
+    tokens: Vec<&'a str>,
+    position: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn next(&mut self, what: &str) -> Result<&'a str, String> {
+        let token = self.peek().ok_or_else(|| format!("Missing {what}"))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn parse<T: FromStr>(&mut self, what: &str) -> Result<T, String> {
+        let token = self.next(what)?;
+        token
+            .parse()
+            .map_err(|_| format!("Invalid {what} `{token}`"))
+    }
+
+    fn character(&mut self) -> Result<char, String> {
+        let token = self.next("character")?;
+        let mut chars = token.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(format!("Expected one character, found `{token}`")),
+        }
+    }
+
+    fn state(&mut self) -> Result<ElementState, String> {
+        match self.next("button state")? {
+            "pressed" => Ok(ElementState::Pressed),
+            "released" => Ok(ElementState::Released),
+            other => Err(format!("Unknown button state `{other}`")),
+        }
+    }
+
+    fn key(&mut self) -> Result<Option<VirtualKeyCode>, String> {
+        match self.next("key")? {
+            "-" => Ok(None),
+            name => key_from_name(name)
+                .map(Some)
+                .ok_or_else(|| format!("Unknown key `{name}`")),
+        }
+    }
+
+    fn button(&mut self) -> Result<MouseButton, String> {
+        Ok(match self.next("mouse button")? {
+            "left" => MouseButton::Left,
+            "right" => MouseButton::Right,
+            "middle" => MouseButton::Middle,
+            other => MouseButton::Other(
+                other
+                    .parse()
+                    .map_err(|_| format!("Unknown mouse button `{other}`"))?,
+            ),
+        })
+    }
+
+    fn phase(&mut self) -> Result<TouchPhase, String> {
+        match self.next("touch phase")? {
+            "started" => Ok(TouchPhase::Started),
+            "moved" => Ok(TouchPhase::Moved),
+            "ended" => Ok(TouchPhase::Ended),
+            "cancelled" => Ok(TouchPhase::Cancelled),
+            other => Err(format!("Unknown touch phase `{other}`")),
+        }
+    }
+
+    fn delta(&mut self) -> Result<MouseScrollDelta, String> {
+        match self.next("scroll unit")? {
+            "lines" => Ok(MouseScrollDelta::LineDelta(
+                self.parse("columns")?,
+                self.parse("rows")?,
+            )),
+            "pixels" => Ok(MouseScrollDelta::PixelDelta(PhysicalPosition::new(
+                self.parse("dx")?,
+                self.parse("dy")?,
+            ))),
+            other => Err(format!("Unknown scroll unit `{other}`")),
+        }
+    }
+
+    fn modifiers(&mut self) -> Result<ModifiersState, String> {
+        let token = self.next("modifiers")?;
+        let mut modifiers = ModifiersState::empty();
+        if token == "-" {
+            return Ok(modifiers);
+        }
+        for name in token.split('+') {
+            modifiers |= match name {
+                "shift" => ModifiersState::SHIFT,
+                "ctrl" => ModifiersState::CTRL,
+                "alt" => ModifiersState::ALT,
+                "logo" => ModifiersState::LOGO,
+                other => return Err(format!("Unknown modifier `{other}`")),
+            };
+        }
+        Ok(modifiers)
+    }
+}
+
+fn state_name(state: ElementState) -> &'static str {
+    match state {
+        ElementState::Pressed => "pressed",
+        ElementState::Released => "released",
+    }
+}
+
+fn button_name(button: MouseButton) -> String {
+    match button {
+        MouseButton::Left => "left".to_string(),
+        MouseButton::Right => "right".to_string(),
+        MouseButton::Middle => "middle".to_string(),
+        MouseButton::Other(button) => button.to_string(),
+    }
+}
+
+fn phase_name(phase: TouchPhase) -> &'static str {
+    match phase {
+        TouchPhase::Started => "started",
+        TouchPhase::Moved => "moved",
+        TouchPhase::Ended => "ended",
+        TouchPhase::Cancelled => "cancelled",
+    }
+}
+
+fn delta_name(delta: MouseScrollDelta) -> String {
+    match delta {
+        MouseScrollDelta::LineDelta(columns, rows) => format!("lines {columns} {rows}"),
+        MouseScrollDelta::PixelDelta(position) => format!("pixels {} {}", position.x, position.y),
+    }
+}
+
+fn modifiers_name(modifiers: ModifiersState) -> String {
+    let names = [
+        (ModifiersState::SHIFT, "shift"),
+        (ModifiersState::CTRL, "ctrl"),
+        (ModifiersState::ALT, "alt"),
+        (ModifiersState::LOGO, "logo"),
+    ];
+    let held: Vec<&str> = names
+        .iter()
+        .filter(|(modifier, _)| modifiers.contains(*modifier))
+        .map(|(_, name)| *name)
+        .collect();
+    if held.is_empty() {
+        "-".to_string()
+    } else {
+        held.join("+")
+    }
+}
+
+/// Writes a key by its variant name, which `key_from_name` reads back.
+fn key_name(key: Option<VirtualKeyCode>) -> String {
+    key.map(|key| format!("{key:?}"))
+        .unwrap_or_else(|| "-".to_string())
+}
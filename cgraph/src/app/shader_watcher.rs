@@ -0,0 +1,69 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// The directory holding the built-in Metal shaders, for watching them during development.
+pub const SHADER_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/metal");
+
+/// Watches a directory of Metal shaders and reads them again when one of them changes.
+pub(crate) struct ShaderWatcher {
+    dir: PathBuf,
+    modified: SystemTime,
+}
+
+impl ShaderWatcher {
+    pub(crate) fn new(dir: &Path) -> Self {
+        ShaderWatcher {
+            dir: dir.to_path_buf(),
+            modified: last_modified(dir).unwrap_or(SystemTime::UNIX_EPOCH),
+        }
+    }
+
+    /// Returns the shader source, concatenated like the build script does, if a shader changed.
+    pub(crate) fn poll(&mut self) -> io::Result<Option<String>> {
+        let modified = last_modified(&self.dir)?;
+        if modified <= self.modified {
+            return Ok(None);
+        }
+        self.modified = modified;
+        let mut source = String::new();
+        collect_shaders(&self.dir, &mut source)?;
+        Ok(Some(source))
+    }
+}
+
+fn is_shader(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "metal")
+}
+
+fn last_modified(dir: &Path) -> io::Result<SystemTime> {
+    let mut latest = SystemTime::UNIX_EPOCH;
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let modified = if path.is_dir() {
+            last_modified(&path)?
+        } else if is_shader(&path) {
+            fs::metadata(&path)?.modified()?
+        } else {
+            continue;
+        };
+        latest = latest.max(modified);
+    }
+    Ok(latest)
+}
+
+fn collect_shaders(dir: &Path, output: &mut String) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_shaders(&path, output)?;
+        } else if is_shader(&path) {
+            let content = fs::read_to_string(&path)?;
+            output.push_str(&format!("// File: {}\n{}\n\n", path.display(), content));
+        }
+    }
+    Ok(())
+}
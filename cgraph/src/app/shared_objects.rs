@@ -1,75 +1,189 @@
-/// Strcuture that encapsulates shared objects in the application.
-pub struct SharedObjects {
-    /// A vector that holds all shared objects.
-    pub objects: Vec<Box<dyn std::any::Any>>,
+use std::{
+    any::{Any, TypeId},
+    cell::{Ref, RefCell, RefMut},
+    collections::HashMap,
+    error::Error,
+    fmt,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ResourceKey {
+    type_id: TypeId,
+    name: Option<String>,
 }
 
-/// Macro that gets a single object by index from the shared objects.
-#[macro_export]
-macro_rules! get_object {
-    ($objects:expr, $type:ty, $index:expr) => {
-        $objects.get_object_at_mut::<$type>($index)
-    };
+impl ResourceKey {
+    fn of<T: 'static>(name: Option<&str>) -> Self {
+        ResourceKey {
+            type_id: TypeId::of::<T>(),
+            name: name.map(str::to_string),
+        }
+    }
 }
 
-/// Macro that gets all objects of a type from the shared objects.
-#[macro_export]
-macro_rules! get_objects {
-    ($objects:expr, $type:ty) => {
-        $objects.get_object_mut::<$type>()
-    };
+/// Error returned when a shared object cannot be borrowed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SharedObjectError {
+    /// No object of the requested type and name was shared.
+    Missing(&'static str),
+    /// The object is already borrowed in a way that conflicts with the request.
+    AlreadyBorrowed(&'static str),
 }
 
-impl Default for SharedObjects {
-    fn default() -> Self {
-        SharedObjects::new()
+impl fmt::Display for SharedObjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SharedObjectError::Missing(type_name) => {
+                write!(f, "No shared object of type {type_name}")
+            }
+            SharedObjectError::AlreadyBorrowed(type_name) => {
+                write!(f, "Shared object of type {type_name} is already borrowed")
+            }
+        }
     }
 }
 
+impl Error for SharedObjectError {}
+
+/// Typed store of the objects shared between event handlers and the frame callback.
+///
+/// Each type can be stored once without a name and any number of times under different names.
+/// Objects live in their own cell, so several of them can be borrowed mutably at the same time
+/// through `borrow_mut`, with conflicting borrows of the same object checked at runtime.
+#[derive(Default)]
+pub struct SharedObjects {
+    objects: HashMap<ResourceKey, RefCell<Box<dyn Any>>>,
+}
+
 impl SharedObjects {
     /// Creates a new instance of `SharedObjects`.
     pub fn new() -> Self {
         SharedObjects {
-            objects: Vec::new(),
+            objects: HashMap::new(),
         }
     }
 
-    /// Adds a new object to the shared objects.
-    pub fn add_object<T: 'static>(&mut self, object: T) {
-        self.objects.push(Box::new(object));
+    /// Shares an object, returning the previous object of the same type if there was one.
+    pub fn insert<T: 'static>(&mut self, object: T) -> Option<T> {
+        self.insert_key(ResourceKey::of::<T>(None), object)
+    }
+
+    /// Shares an object under a name, returning the previous object with that type and name.
+    pub fn insert_named<T: 'static>(&mut self, name: &str, object: T) -> Option<T> {
+        self.insert_key(ResourceKey::of::<T>(Some(name)), object)
     }
 
-    /// Removes an object from the shared objects by index.
-    pub fn get_object<T: 'static>(&self) -> Vec<&T> {
+    fn insert_key<T: 'static>(&mut self, key: ResourceKey, object: T) -> Option<T> {
         self.objects
-            .iter()
-            .filter_map(|obj| obj.downcast_ref::<T>())
-            .collect()
+            .insert(key, RefCell::new(Box::new(object)))
+            .and_then(|previous| previous.into_inner().downcast::<T>().ok())
+            .map(|previous| *previous)
+    }
+
+    /// Removes the object of a type and returns it.
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.remove_key(&ResourceKey::of::<T>(None))
+    }
+
+    /// Removes the object of a type shared under a name and returns it.
+    pub fn remove_named<T: 'static>(&mut self, name: &str) -> Option<T> {
+        self.remove_key(&ResourceKey::of::<T>(Some(name)))
     }
 
-    /// Gets a mutable reference to all objects of a type.
-    pub fn get_object_mut<T: 'static>(&mut self) -> Vec<&mut T> {
+    fn remove_key<T: 'static>(&mut self, key: &ResourceKey) -> Option<T> {
         self.objects
-            .iter_mut()
-            .filter_map(|obj| obj.downcast_mut::<T>())
-            .collect()
+            .remove(key)
+            .and_then(|object| object.into_inner().downcast::<T>().ok())
+            .map(|object| *object)
     }
 
-    /// Gets a reference to an object at a specific index.
-    pub fn get_object_at<T: 'static>(&self, index: usize) -> Option<&T> {
-        if index < self.objects.len() {
-            self.objects[index].downcast_ref::<T>()
-        } else {
-            None
-        }
+    /// Returns whether an object of a type is shared.
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.objects.contains_key(&ResourceKey::of::<T>(None))
     }
 
-    /// Gets a mutable reference to an object at a specific index.
-    pub fn get_object_at_mut<T: 'static>(&mut self, index: usize) -> Option<&mut T> {
-        if index < self.objects.len() {
-            self.objects[index].downcast_mut::<T>()
-        } else {
-            None
-        }
+    /// Returns whether an object of a type is shared under a name.
+    pub fn contains_named<T: 'static>(&self, name: &str) -> bool {
+        self.objects.contains_key(&ResourceKey::of::<T>(Some(name)))
+    }
+
+    /// Returns the number of shared objects.
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// Returns whether no objects are shared.
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Gets a mutable reference to the object of a type.
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.objects
+            .get_mut(&ResourceKey::of::<T>(None))
+            .and_then(|object| object.get_mut().downcast_mut::<T>())
+    }
+
+    /// Gets a mutable reference to the object of a type shared under a name.
+    pub fn get_named_mut<T: 'static>(&mut self, name: &str) -> Option<&mut T> {
+        self.objects
+            .get_mut(&ResourceKey::of::<T>(Some(name)))
+            .and_then(|object| object.get_mut().downcast_mut::<T>())
+    }
+
+    /// Borrows the object of a type, or returns `None` if it is missing or mutably borrowed.
+    pub fn borrow<T: 'static>(&self) -> Option<Ref<'_, T>> {
+        self.try_borrow::<T>(None).ok()
+    }
+
+    /// Borrows the object of a type shared under a name.
+    pub fn borrow_named<T: 'static>(&self, name: &str) -> Option<Ref<'_, T>> {
+        self.try_borrow::<T>(Some(name)).ok()
+    }
+
+    /// Mutably borrows the object of a type, or returns `None` if it is missing or already borrowed.
+    ///
+    /// Objects of different types or names can be borrowed mutably at the same time.
+    pub fn borrow_mut<T: 'static>(&self) -> Option<RefMut<'_, T>> {
+        self.try_borrow_mut::<T>(None).ok()
+    }
+
+    /// Mutably borrows the object of a type shared under a name.
+    pub fn borrow_named_mut<T: 'static>(&self, name: &str) -> Option<RefMut<'_, T>> {
+        self.try_borrow_mut::<T>(Some(name)).ok()
+    }
+
+    /// Borrows the object of a type, optionally shared under a name, explaining any failure.
+    pub fn try_borrow<T: 'static>(
+        &self,
+        name: Option<&str>,
+    ) -> Result<Ref<'_, T>, SharedObjectError> {
+        let type_name = std::any::type_name::<T>();
+        let object = self
+            .objects
+            .get(&ResourceKey::of::<T>(name))
+            .ok_or(SharedObjectError::Missing(type_name))?;
+        let object = object
+            .try_borrow()
+            .map_err(|_| SharedObjectError::AlreadyBorrowed(type_name))?;
+        Ref::filter_map(object, |object| object.downcast_ref::<T>())
+            .map_err(|_| SharedObjectError::Missing(type_name))
+    }
+
+    /// Mutably borrows the object of a type, optionally shared under a name, explaining any failure.
+    pub fn try_borrow_mut<T: 'static>(
+        &self,
+        name: Option<&str>,
+    ) -> Result<RefMut<'_, T>, SharedObjectError> {
+        let type_name = std::any::type_name::<T>();
+        let object = self
+            .objects
+            .get(&ResourceKey::of::<T>(name))
+            .ok_or(SharedObjectError::Missing(type_name))?;
+        let object = object
+            .try_borrow_mut()
+            .map_err(|_| SharedObjectError::AlreadyBorrowed(type_name))?;
+        RefMut::filter_map(object, |object| object.downcast_mut::<T>())
+            .map_err(|_| SharedObjectError::Missing(type_name))
     }
 }
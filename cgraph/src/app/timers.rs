@@ -0,0 +1,154 @@
+use std::time::{Duration, Instant};
+
+use crate::app::WindowContext;
+
+/// The shortest period of an interval, so a zero period can't keep the event loop spinning.
+const MIN_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Identifies a timeout or interval so it can be cleared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerId(u64);
+
+type TimerFunction = dyn FnMut(&mut WindowContext) + 'static;
+type AnimationFrameFunction = dyn FnOnce(&mut WindowContext, Instant) + 'static;
+
+/// A callback waiting for its deadline.
+pub(crate) struct Timer {
+    id: TimerId,
+    deadline: Instant,
+    interval: Option<Duration>,
+    callback: Box<TimerFunction>,
+}
+
+impl Timer {
+    /// Runs the callback.
+    pub(crate) fn fire(&mut self, context: &mut WindowContext) {
+        (self.callback)(context);
+    }
+}
+
+/// The timers, animation frame callbacks and pending redraw of a window.
+pub(crate) struct Scheduler {
+    next_id: u64,
+    timers: Vec<Timer>,
+    // Timers cleared while they were running, so they are not rescheduled.
+    cleared: Vec<TimerId>,
+    animation_frames: Vec<Box<AnimationFrameFunction>>,
+    redraw_requested: bool,
+    // The replayed time, so timers fire at the same moments as in the recording.
+    clock: Option<Instant>,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Scheduler {
+            next_id: 0,
+            timers: Vec::new(),
+            cleared: Vec::new(),
+            animation_frames: Vec::new(),
+            // The first frame is always drawn.
+            redraw_requested: true,
+            clock: None,
+        }
+    }
+}
+
+impl Scheduler {
+    /// Returns the current time, which is the replayed time while a recording is replayed.
+    pub(crate) fn now(&self) -> Instant {
+        self.clock.unwrap_or_else(Instant::now)
+    }
+
+    /// Sets the replayed time, or goes back to the system clock with `None`.
+    pub(crate) fn set_clock(&mut self, clock: Option<Instant>) {
+        self.clock = clock;
+    }
+
+    fn add_timer(
+        &mut self,
+        delay: Duration,
+        interval: Option<Duration>,
+        callback: Box<TimerFunction>,
+    ) -> TimerId {
+        self.next_id += 1;
+        let id = TimerId(self.next_id);
+        self.timers.push(Timer {
+            id,
+            deadline: self.now() + delay,
+            interval,
+            callback,
+        });
+        id
+    }
+
+    pub(crate) fn set_timeout(&mut self, delay: Duration, callback: Box<TimerFunction>) -> TimerId {
+        self.add_timer(delay, None, callback)
+    }
+
+    pub(crate) fn set_interval(
+        &mut self,
+        period: Duration,
+        callback: Box<TimerFunction>,
+    ) -> TimerId {
+        let period = period.max(MIN_INTERVAL);
+        self.add_timer(period, Some(period), callback)
+    }
+
+    pub(crate) fn clear_timer(&mut self, id: TimerId) {
+        let count = self.timers.len();
+        self.timers.retain(|timer| timer.id != id);
+        if self.timers.len() == count {
+            self.cleared.push(id);
+        }
+    }
+
+    pub(crate) fn request_animation_frame(&mut self, callback: Box<AnimationFrameFunction>) {
+        self.animation_frames.push(callback);
+        self.redraw_requested = true;
+    }
+
+    pub(crate) fn request_redraw(&mut self) {
+        self.redraw_requested = true;
+    }
+
+    /// Returns whether a redraw was requested since the last call, clearing the request.
+    pub(crate) fn take_redraw(&mut self) -> bool {
+        std::mem::take(&mut self.redraw_requested)
+    }
+
+    /// Returns the earliest deadline of the pending timers.
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        self.timers.iter().map(|timer| timer.deadline).min()
+    }
+
+    /// Removes the timers whose deadline has passed, in deadline order.
+    pub(crate) fn take_due(&mut self, now: Instant) -> Vec<Timer> {
+        self.cleared.clear();
+        let (mut due, pending): (Vec<Timer>, Vec<Timer>) = std::mem::take(&mut self.timers)
+            .into_iter()
+            .partition(|timer| timer.deadline <= now);
+        self.timers = pending;
+        due.sort_by_key(|timer| timer.deadline);
+        due
+    }
+
+    /// Puts an interval that has just fired back in the queue, unless it was cleared meanwhile.
+    pub(crate) fn reschedule(&mut self, mut timer: Timer, now: Instant) {
+        let Some(interval) = timer.interval else {
+            return;
+        };
+        if self.cleared.contains(&timer.id) {
+            return;
+        }
+        // Skip the ticks that were missed instead of firing them in a burst.
+        timer.deadline += interval;
+        if timer.deadline <= now {
+            timer.deadline = now + interval;
+        }
+        self.timers.push(timer);
+    }
+
+    pub(crate) fn take_animation_frames(&mut self) -> Vec<Box<AnimationFrameFunction>> {
+        std::mem::take(&mut self.animation_frames)
+    }
+}
@@ -1,13 +1,39 @@
-use std::hash::{DefaultHasher, Hash, Hasher};
+use std::{
+    any::Any,
+    hash::{DefaultHasher, Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
 
 use winit::{
-    dpi::PhysicalPosition,
-    event::{DeviceId, Event, MouseScrollDelta, WindowEvent},
-    event_loop::{ControlFlow, EventLoop},
-    window::WindowBuilder,
+    dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize},
+    error::OsError,
+    event::{DeviceId, MouseScrollDelta, WindowEvent},
+    event_loop::{EventLoop, EventLoopBuilder, EventLoopWindowTarget},
+    window::{BadIcon, Icon, WindowBuilder, WindowId, WindowLevel},
 };
 
-use crate::{app::SharedObjects, object::primitives::Color, renderer::create_renderer};
+use crate::{
+    app::{
+        Application, Commands, CursorIcon, EventSender, GestureEvent, GestureSettings, KeyChord,
+        KeyContext, KeyEvent, KeyResolution, KeyResolver, Keymap, MouseEvent, Propagation,
+        RecordedDeviceEvent, RecordedEvent, RecordedWindowEvent, Recording, ReplayEnd, ResizeEvent,
+        SharedObjects, Subscription, TextInputEvent, TimerId, WindowContext, WindowGeometry,
+        application::{LoopResources, ManagedWindow, WindowCommands},
+        events::{EventHandlers, LoopMessage, MessageSink, UserMessage},
+        keymap::is_modifier_key,
+        overlay::FrameHistory,
+        recording::EventRecorder,
+        shader_watcher::ShaderWatcher,
+        timers::Scheduler,
+    },
+    object::primitives::Color,
+    renderer::{
+        FrameStats, HeadlessRenderer, RenderDevice, RenderError, create_renderer_with_device,
+    },
+};
 #[cfg(target_os = "macos")]
 /// Context information for the application.
 pub struct Context {
@@ -41,6 +67,8 @@ pub enum CoreWindowEvent {
     RecievedChar(char), // character
     /// A keyboard input event occurred. Takes the keyboard input as a parameter.
     KeyboardInput(winit::event::KeyboardInput), // input
+    /// The input method sent a composition event. Takes the IME event as a parameter.
+    Ime(winit::event::Ime), // ime
     /// The modifiers (like Shift, Ctrl, etc.) were changed. Takes the modifiers state as a parameter.
     ModifierChanged(winit::event::ModifiersState), // modifiers
     /// The DPI (dots per inch) scale factor was changed. Takes the new scale factor as parameters.
@@ -105,8 +133,8 @@ pub enum CoreEvent {
     WindowEvent(CoreWindowEvent),
     /// A device event occurred. Takes the device event as a parameter.
     DeviceEvent(CoreDeviceEvent),
-    /// A user-defined event occurred.
-    UserEvent,
+    /// A message was posted through an `EventSender`. Takes the message as a parameter.
+    UserEvent(Box<dyn Any + Send>),
     /// The application was suspended.
     AppSuspended,
     /// The application was resumed.
@@ -116,6 +144,7 @@ pub enum CoreEvent {
 }
 
 /// Represents a reference to a core event type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CoreEventReference {
     /// A reference to a window event.
     WindowEvent,
@@ -131,22 +160,63 @@ pub enum CoreEventReference {
     MemoryWarning,
 }
 
-type DelegateResponse = dyn Fn(&mut winit::window::Window, &mut CoreEvent) + 'static;
-struct EventDelegate {
-    response: Box<DelegateResponse>,
-    event: CoreEventReference,
+impl CoreEventReference {
+    /// Returns the reference matching the type of a core event.
+    pub fn of(event: &CoreEvent) -> Self {
+        match event {
+            CoreEvent::WindowEvent(_) => CoreEventReference::WindowEvent,
+            CoreEvent::DeviceEvent(_) => CoreEventReference::DeviceEvent,
+            CoreEvent::UserEvent(_) => CoreEventReference::UserEvent,
+            CoreEvent::AppSuspended => CoreEventReference::AppSuspended,
+            CoreEvent::AppResumed => CoreEventReference::AppResumed,
+            CoreEvent::MemoryWarning => CoreEventReference::MemoryWarning,
+        }
+    }
 }
 
-type RenderFunction = dyn Fn(&mut winit::window::Window, &mut crate::macos::metal::MetalRenderer, &mut SharedObjects)
-    + 'static;
+/// The priority of keymap handlers, so bindings run before ordinary key handlers.
+const KEYMAP_PRIORITY: i32 = 100;
+
+type RenderFunction<State> = dyn FnMut(&mut State, &mut WindowContext) + 'static;
+
+/// An image shown as the window's icon in the title bar and task switcher.
+#[derive(Clone)]
+pub struct WindowIcon(Icon);
+
+impl WindowIcon {
+    /// Creates an icon from RGBA pixels, four bytes per pixel.
+    pub fn from_rgba(rgba: Vec<u8>, width: u32, height: u32) -> Result<Self, BadIcon> {
+        Icon::from_rgba(rgba, width, height).map(WindowIcon)
+    }
+
+    /// Loads an icon from an image file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let image = image::open(path)?.into_rgba8();
+        let (width, height) = image.dimensions();
+        Ok(WindowIcon::from_rgba(image.into_raw(), width, height)?)
+    }
+}
 
 /// Represents the options for creating a window.
+///
+/// Options combine through the `with_*` methods, like
+/// `WindowOptions::new().with_min_size(400, 300).with_maximized(true)`.
+/// Sizes and positions are in logical pixels.
 pub struct WindowOptions {
     decorations: bool,
     resizable: bool,
     transparent: bool,
     fullscreen: bool,
     no_titlebar: bool,
+    size: Option<(u32, u32)>,
+    min_size: Option<(u32, u32)>,
+    max_size: Option<(u32, u32)>,
+    position: Option<(i32, i32)>,
+    maximized: bool,
+    minimized: bool,
+    always_on_top: bool,
+    icon: Option<WindowIcon>,
+    background_color: Color,
 }
 
 impl Clone for WindowOptions {
@@ -157,6 +227,15 @@ impl Clone for WindowOptions {
             transparent: self.transparent,
             fullscreen: self.fullscreen,
             no_titlebar: self.no_titlebar,
+            size: self.size,
+            min_size: self.min_size,
+            max_size: self.max_size,
+            position: self.position,
+            maximized: self.maximized,
+            minimized: self.minimized,
+            always_on_top: self.always_on_top,
+            icon: self.icon.clone(),
+            background_color: self.background_color,
         }
     }
 }
@@ -169,85 +248,172 @@ impl Default for WindowOptions {
             transparent: false,
             fullscreen: false,
             no_titlebar: false,
+            size: None,
+            min_size: None,
+            max_size: None,
+            position: None,
+            maximized: false,
+            minimized: false,
+            always_on_top: false,
+            icon: None,
+            background_color: Color::new(0.05, 0.05, 0.05, 1.0),
         }
     }
 }
 
 impl WindowOptions {
+    /// Creates the default options: a decorated, resizable and opaque window.
+    pub fn new() -> Self {
+        WindowOptions::default()
+    }
+
     /// Creates a new instance of `WindowOptions` with no decorations.
     pub fn no_decorations() -> Self {
-        WindowOptions {
-            decorations: false,
-            resizable: true,
-            transparent: false,
-            fullscreen: false,
-            no_titlebar: false,
-        }
+        WindowOptions::new().with_decorations(false)
     }
 
     /// Creates a new instance of `WindowOptions` with resizable enabled.
     pub fn resizable() -> Self {
-        WindowOptions {
-            decorations: true,
-            resizable: true,
-            transparent: false,
-            fullscreen: false,
-            no_titlebar: false,
-        }
+        WindowOptions::new().with_resizable(true)
     }
 
     /// Creates a new instance of `WindowOptions` with transparent background.
     pub fn transparent() -> Self {
-        WindowOptions {
-            decorations: true,
-            resizable: true,
-            transparent: true,
-            fullscreen: false,
-            no_titlebar: false,
-        }
+        WindowOptions::new().with_transparent(true)
     }
 
     /// Creates a new instance of `WindowOptions` with fullscreen enabled.
     pub fn fullscreen() -> Self {
-        WindowOptions {
-            decorations: true,
-            resizable: true,
-            transparent: false,
-            fullscreen: true,
-            no_titlebar: false,
-        }
+        WindowOptions::new().with_fullscreen(true)
     }
 
     /// Creates a new instance of `WindowOptions` with no titlebar.
     pub fn no_titlebar() -> Self {
-        WindowOptions {
-            decorations: true,
-            resizable: true,
-            transparent: false,
-            fullscreen: false,
-            no_titlebar: true,
-        }
+        WindowOptions::new().with_titlebar(false)
+    }
+
+    /// Shows or hides the window's border and title bar.
+    pub fn with_decorations(mut self, decorations: bool) -> Self {
+        self.decorations = decorations;
+        self
+    }
+
+    /// Lets the user resize the window or not.
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Makes the window's background transparent or opaque.
+    pub fn with_transparent(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        self
+    }
+
+    /// Opens the window fullscreen or not.
+    pub fn with_fullscreen(mut self, fullscreen: bool) -> Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    /// Shows or hides the title bar while keeping the window's border. Only supported on macOS.
+    pub fn with_titlebar(mut self, titlebar: bool) -> Self {
+        self.no_titlebar = !titlebar;
+        self
+    }
+
+    /// Opens the window with this size instead of the one it was created with.
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+
+    /// Keeps the user from making the window smaller than a size.
+    pub fn with_min_size(mut self, width: u32, height: u32) -> Self {
+        self.min_size = Some((width, height));
+        self
+    }
+
+    /// Keeps the user from making the window larger than a size.
+    pub fn with_max_size(mut self, width: u32, height: u32) -> Self {
+        self.max_size = Some((width, height));
+        self
+    }
+
+    /// Opens the window with its top-left corner at a position on the screen.
+    pub fn with_position(mut self, x: i32, y: i32) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    /// Opens the window maximized or not.
+    pub fn with_maximized(mut self, maximized: bool) -> Self {
+        self.maximized = maximized;
+        self
+    }
+
+    /// Opens the window minimized or not.
+    pub fn with_minimized(mut self, minimized: bool) -> Self {
+        self.minimized = minimized;
+        self
+    }
+
+    /// Keeps the window above the other windows or not.
+    pub fn with_always_on_top(mut self, always_on_top: bool) -> Self {
+        self.always_on_top = always_on_top;
+        self
+    }
+
+    /// Sets the window's icon.
+    pub fn with_icon(mut self, icon: WindowIcon) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Clears the window with a color before drawing its objects.
+    pub fn with_background_color(mut self, color: Color) -> Self {
+        self.background_color = color;
+        self
+    }
+
+    /// Opens the window at a saved position, size and state.
+    pub fn with_geometry(self, geometry: &WindowGeometry) -> Self {
+        self.with_position(geometry.x, geometry.y)
+            .with_size(geometry.width, geometry.height)
+            .with_maximized(geometry.maximized)
     }
 }
 
-/// Represents a window in the application.
-pub struct Window {
+/// Represents a window in the application, holding the application state passed to its handlers.
+pub struct Window<State = ()> {
     /// The title of the window.
     pub title: String,
     /// The width of the window.
     pub width: u32,
     /// The height of the window.
     pub height: u32,
-    #[cfg(target_os = "macos")]
     /// The update function that will be called each frame.
-    pub update: Box<RenderFunction>,
+    pub update: Box<RenderFunction<State>>,
     /// The background color of the window.
     pub background_color: Color,
     shared_objects: SharedObjects,
     renderer: Box<dyn crate::renderer::Renderer>,
-    window: winit::window::Window,
-    event_loop: EventLoop<()>,
-    events: Vec<EventDelegate>,
+    window: Option<winit::window::Window>,
+    id: WindowId,
+    // The event loop of a window created with `new`, with the device its renderer draws with.
+    event_loop: Option<(EventLoop<LoopMessage>, LoopResources)>,
+    sink: MessageSink,
+    // Messages posted to a headless window, which has no event loop to receive them.
+    inbox: Option<mpsc::Receiver<UserMessage>>,
+    recorder: Option<EventRecorder>,
+    geometry_path: Option<PathBuf>,
+    frame_history: FrameHistory,
+    show_overlay: bool,
+    occluded: bool,
+    events: EventHandlers<State>,
+    commands: WindowCommands,
+    scheduler: Scheduler,
+    state: State,
 }
 
 fn apply_window_options(
@@ -267,39 +433,288 @@ fn apply_window_options(
     if options.fullscreen {
         builder = builder.with_fullscreen(Some(winit::window::Fullscreen::Borderless(None)));
     }
+    if let Some((width, height)) = options.min_size {
+        builder = builder.with_min_inner_size(LogicalSize::new(width, height));
+    }
+    if let Some((width, height)) = options.max_size {
+        builder = builder.with_max_inner_size(LogicalSize::new(width, height));
+    }
+    if let Some((x, y)) = options.position {
+        builder = builder.with_position(LogicalPosition::new(x, y));
+    }
+    if options.maximized {
+        builder = builder.with_maximized(true);
+    }
+    if options.always_on_top {
+        builder = builder.with_window_level(WindowLevel::AlwaysOnTop);
+    }
+    if let Some(icon) = &options.icon {
+        builder = builder.with_window_icon(Some(icon.0.clone()));
+    }
     builder
 }
 
+/// Errors returned when creating a window.
+#[derive(Debug, thiserror::Error)]
+pub enum WindowError {
+    /// The platform could not create the native window.
+    #[error("cannot create window: {0}")]
+    Create(#[from] OsError),
+    /// The window's renderer could not be created.
+    #[error("cannot create renderer: {0}")]
+    Render(#[from] RenderError),
+    /// The window has no event loop of its own to launch.
+    #[error(
+        "the window has no event loop; windows of an Application are shown with Application::run"
+    )]
+    NoEventLoop,
+}
+
 impl Window {
     /// Creates a new instance of `Window` with the specified title, width, height, and options.
-    pub fn new(title: &str, width: u32, height: u32, options: Option<WindowOptions>) -> Self {
-        let event_loop = EventLoop::new();
+    pub fn new(
+        title: &str,
+        width: u32,
+        height: u32,
+        options: Option<WindowOptions>,
+    ) -> Result<Self, WindowError> {
+        Window::with_state(title, width, height, options, ())
+    }
+}
+
+impl<State: 'static> Window<State> {
+    /// Creates a new window that passes `state` to its event handlers and frame callback.
+    ///
+    /// The window owns its event loop, so only one such window can exist. Use an `Application`
+    /// to run several windows.
+    pub fn with_state(
+        title: &str,
+        width: u32,
+        height: u32,
+        options: Option<WindowOptions>,
+        state: State,
+    ) -> Result<Self, WindowError> {
+        let event_loop = EventLoopBuilder::with_user_event().build();
+        let resources = LoopResources {
+            device: RenderDevice::system_default()?,
+            proxy: event_loop.create_proxy(),
+        };
+        let mut window = Window::build(
+            &event_loop,
+            &resources,
+            title,
+            width,
+            height,
+            options,
+            state,
+        )?;
+        window.event_loop = Some((event_loop, resources));
+        Ok(window)
+    }
+
+    /// Creates a window on an event loop, drawing with its shared render device.
+    pub(crate) fn build(
+        target: &EventLoopWindowTarget<LoopMessage>,
+        resources: &LoopResources,
+        title: &str,
+        width: u32,
+        height: u32,
+        options: Option<WindowOptions>,
+        state: State,
+    ) -> Result<Self, WindowError> {
+        let options = options.unwrap_or_default();
+        let (width, height) = options.size.unwrap_or((width, height));
         let mut window_builder = WindowBuilder::new()
             .with_title(title)
-            .with_inner_size(winit::dpi::LogicalSize::new(width, height));
-        let cloned_options = options.clone();
-        window_builder = apply_window_options(&window_builder, &options.unwrap_or_default());
+            .with_inner_size(LogicalSize::new(width, height));
+        window_builder = apply_window_options(&window_builder, &options);
 
-        let window = window_builder
-            .build(&event_loop)
-            .expect("Cannot create window");
+        let window = window_builder.build(target)?;
 
-        if cloned_options.is_some() && cloned_options.unwrap().no_titlebar {
+        if options.minimized {
+            window.set_minimized(true);
+        }
+        if options.no_titlebar {
             #[cfg(target_os = "macos")]
             crate::macos::win_custom::customize_window(&window);
         }
-        Window {
+        let mut window = Window {
             title: title.to_string(),
             width,
             height,
-            renderer: create_renderer(&window, Color::new(0.05, 0.05, 0.05, 1.0)),
-            window,
-            event_loop,
-            update: Box::new(|_, _, _| ()),
+            renderer: create_renderer_with_device(
+                &window,
+                options.background_color,
+                &resources.device,
+            )?,
+            id: window.id(),
+            window: Some(window),
+            event_loop: None,
+            sink: MessageSink::EventLoop(resources.proxy.clone()),
+            inbox: None,
+            recorder: None,
+            geometry_path: None,
+            frame_history: FrameHistory::default(),
+            show_overlay: false,
+            occluded: false,
+            update: Box::new(|_, _| ()),
             shared_objects: SharedObjects::new(),
-            events: Vec::new(),
-            background_color: Color::new(0.05, 0.05, 0.05, 1.0),
+            events: EventHandlers::default(),
+            commands: WindowCommands::default(),
+            scheduler: Scheduler::default(),
+            state,
+            background_color: options.background_color,
+        };
+        window.renderer.resize(width as f64, height as f64);
+        Ok(window)
+    }
+
+    /// Creates a window without a native window or event loop, to replay recorded events in tests.
+    ///
+    /// Objects are kept but never drawn, and windows opened by its handlers are ignored.
+    pub fn headless(title: &str, width: u32, height: u32, state: State) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let background_color = Color::new(0.05, 0.05, 0.05, 1.0);
+        let mut window = Window {
+            title: title.to_string(),
+            width,
+            height,
+            renderer: Box::new(HeadlessRenderer::with_color(background_color)),
+            window: None,
+            // SAFETY: the id of a headless window never reaches winit, as it has no event loop.
+            id: unsafe { WindowId::dummy() },
+            event_loop: None,
+            sink: MessageSink::Queue(sender),
+            inbox: Some(receiver),
+            recorder: None,
+            geometry_path: None,
+            frame_history: FrameHistory::default(),
+            show_overlay: false,
+            occluded: false,
+            update: Box::new(|_, _| ()),
+            shared_objects: SharedObjects::new(),
+            events: EventHandlers::default(),
+            commands: WindowCommands::default(),
+            scheduler: Scheduler::default(),
+            state,
+            background_color,
+        };
+        window.renderer.resize(width as f64, height as f64);
+        window
+    }
+
+    /// Records the events the window receives to a file, replacing any recording in progress.
+    pub fn record_events(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.recorder = Some(EventRecorder::create(path.as_ref())?);
+        Ok(())
+    }
+
+    /// Stops recording events.
+    pub fn stop_recording(&mut self) {
+        self.recorder = None;
+    }
+
+    /// Replays a recording into the window without going through winit.
+    ///
+    /// Timers and animation frames run on the recorded clock, so a replay behaves the same every
+    /// time. Messages posted through event senders are dispatched before each event.
+    pub fn replay(&mut self, recording: &Recording) -> ReplayEnd {
+        let start = Instant::now();
+        let mut end = ReplayEnd::Finished;
+        for timed in &recording.events {
+            let now = start + timed.time;
+            self.scheduler.set_clock(Some(now));
+            self.prepare_frame(now);
+            self.receive_messages();
+            match &timed.event {
+                RecordedEvent::Window(event) => {
+                    let mut size = PhysicalSize::new(self.width, self.height);
+                    self.handle_window_event(&event.to_winit(&mut size));
+                }
+                RecordedEvent::Device(event) => self.handle_device_event(&event.to_winit()),
+            }
+            if self.scheduler.take_redraw() {
+                self.draw_frame(now);
+            }
+
+            let commands = std::mem::take(&mut self.commands);
+            if commands.closes(self.id) {
+                end = ReplayEnd::Closed;
+                break;
+            }
+        }
+        self.scheduler.set_clock(None);
+        end
+    }
+
+    /// Dispatches the messages posted to a headless window.
+    fn receive_messages(&mut self) {
+        let messages: Vec<UserMessage> = match &self.inbox {
+            Some(inbox) => inbox.try_iter().collect(),
+            None => return,
+        };
+        for mut message in messages {
+            self.handle_user_event(&mut message);
+        }
+    }
+
+    /// Writes an event to the recording, stopping it if the file cannot be written.
+    fn record(&mut self, event: Option<RecordedEvent>) {
+        let (Some(recorder), Some(event)) = (&mut self.recorder, event) else {
+            return;
+        };
+        if let Err(error) = recorder.record(event) {
+            eprintln!("Stopped recording events: {error}");
+            self.recorder = None;
+        }
+    }
+
+    /// Runs the animation frames and the frame callback, then draws the window.
+    fn draw_frame(&mut self, frame_time: Instant) {
+        let start = Instant::now();
+        let frames = self.scheduler.take_animation_frames();
+        let mut context = WindowContext {
+            window: self.window.as_mut(),
+            id: self.id,
+            renderer: self.renderer.as_mut(),
+            objects: &mut self.shared_objects,
+            commands: &mut self.commands,
+            sink: &self.sink,
+            scheduler: &mut self.scheduler,
+        };
+        for frame in frames {
+            frame(&mut context, frame_time);
+        }
+        (self.update)(&mut self.state, &mut context);
+        let overlay = self
+            .show_overlay
+            .then(|| self.frame_history.to_object(self.framebuffer_size()));
+        self.renderer.set_overlay(overlay);
+        // Nothing is drawn while the window is hidden, but callbacks still run.
+        let rendered = match &self.window {
+            Some(window) if !self.occluded => self.renderer.render(window),
+            _ => Ok(()),
+        };
+        match rendered {
+            Ok(()) => (),
+            // The application moves every window to a new device once the event is handled.
+            Err(RenderError::DeviceLost) => self.commands.device_lost(),
+            Err(error) => eprintln!("Cannot draw the frame: {error}"),
         }
+        self.frame_history.push(start.elapsed());
+    }
+
+    /// Returns the timings and counters of the last frame the renderer drew.
+    pub fn frame_stats(&self) -> FrameStats {
+        self.renderer.frame_stats()
+    }
+
+    /// Shows or hides a graph of the last frame times in the window's top-right corner.
+    ///
+    /// Bars are green within a 60 Hz frame budget, yellow up to twice it and red beyond.
+    pub fn show_performance_overlay(&mut self, show: bool) {
+        self.show_overlay = show;
+        self.scheduler.request_redraw();
     }
 
     /// Sets the background color of the window.
@@ -308,160 +723,344 @@ impl Window {
         self.renderer.set_background_color(color);
     }
 
-    fn execute_event_static(
-        window: &mut winit::window::Window,
-        events: &[EventDelegate],
-        event: &winit::event::Event<()>,
-    ) {
-        match event {
-            Event::WindowEvent { event, .. } => {
-                for delegate in events {
-                    if let CoreEventReference::WindowEvent = delegate.event {
-                        (delegate.response)(
-                            window,
-                            &mut CoreEvent::WindowEvent(window_from_winit_event(event)),
-                        );
-                    }
-                }
-            }
-            Event::DeviceEvent { event, .. } => {
-                for delegate in events {
-                    if let CoreEventReference::DeviceEvent = delegate.event {
-                        (delegate.response)(
-                            window,
-                            &mut CoreEvent::DeviceEvent(device_from_winit_event(event)),
-                        );
-                    }
-                }
-            }
-            _ => (),
-        }
+    /// Returns whether the system shows the window in light or dark mode, if it is known.
+    pub fn theme(&self) -> Option<winit::window::Theme> {
+        self.window.as_ref().and_then(|window| window.theme())
+    }
+
+    /// Returns the id of the native window, used to route events and close it.
+    pub fn id(&self) -> WindowId {
+        self.id
+    }
+
+    /// Returns the application state.
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Returns the application state mutably.
+    pub fn state_mut(&mut self) -> &mut State {
+        &mut self.state
     }
 
     /// Handles core events and delegates them to the appropriate handlers.
     pub fn handle_core_event(&mut self, core_event: &mut CoreEvent) {
-        match core_event {
-            CoreEvent::WindowEvent(_) => {
-                for delegate in &self.events {
-                    if let CoreEventReference::WindowEvent = delegate.event {
-                        (delegate.response)(&mut self.window, core_event);
-                    }
-                }
-            }
-            CoreEvent::DeviceEvent(_) => {
-                for delegate in &self.events {
-                    if let CoreEventReference::DeviceEvent = delegate.event {
-                        (delegate.response)(&mut self.window, core_event);
-                    }
-                }
-            }
-            CoreEvent::UserEvent => {
-                for delegate in &self.events {
-                    if let CoreEventReference::UserEvent = delegate.event {
-                        (delegate.response)(&mut self.window, core_event);
-                    }
-                }
-            }
-            CoreEvent::AppSuspended => {
-                for delegate in &self.events {
-                    if let CoreEventReference::AppSuspended = delegate.event {
-                        (delegate.response)(&mut self.window, core_event);
-                    }
-                }
+        let mut context = WindowContext {
+            window: self.window.as_mut(),
+            id: self.id,
+            renderer: self.renderer.as_mut(),
+            objects: &mut self.shared_objects,
+            commands: &mut self.commands,
+            sink: &self.sink,
+            scheduler: &mut self.scheduler,
+        };
+        self.events
+            .dispatch_core(&mut self.state, &mut context, core_event);
+    }
+
+    /// Adds an event handler for a specific core event type that stays registered for the lifetime of the window.
+    ///
+    /// The handler takes the native window, so headless windows never call it.
+    pub fn on_event<F>(&mut self, event_type: CoreEventReference, handler: F)
+    where
+        F: Fn(&mut winit::window::Window, &mut CoreEvent) + 'static,
+    {
+        self.subscribe(event_type, move |_, context, event| {
+            if let Some(window) = context.window.as_deref_mut() {
+                handler(window, event);
             }
-            CoreEvent::AppResumed => {
-                for delegate in &self.events {
-                    if let CoreEventReference::AppResumed = delegate.event {
-                        (delegate.response)(&mut self.window, core_event);
-                    }
-                }
+            Propagation::Continue
+        })
+        .detach();
+    }
+
+    /// Subscribes to a core event type until the returned subscription is dropped.
+    pub fn subscribe<F>(&mut self, event_type: CoreEventReference, handler: F) -> Subscription
+    where
+        F: FnMut(&mut State, &mut WindowContext, &mut CoreEvent) -> Propagation + 'static,
+    {
+        self.subscribe_with_priority(event_type, 0, handler)
+    }
+
+    /// Subscribes to a core event type, calling handlers with a higher priority first.
+    pub fn subscribe_with_priority<F>(
+        &mut self,
+        event_type: CoreEventReference,
+        priority: i32,
+        handler: F,
+    ) -> Subscription
+    where
+        F: FnMut(&mut State, &mut WindowContext, &mut CoreEvent) -> Propagation + 'static,
+    {
+        self.events
+            .add_core(event_type, priority, Box::new(handler))
+    }
+
+    /// Subscribes to keyboard input until the returned subscription is dropped.
+    pub fn on_key<F>(&mut self, handler: F) -> Subscription
+    where
+        F: FnMut(&mut State, &mut WindowContext, &mut KeyEvent) -> Propagation + 'static,
+    {
+        self.on_key_with_priority(0, handler)
+    }
+
+    /// Subscribes to keyboard input, calling handlers with a higher priority first.
+    pub fn on_key_with_priority<F>(&mut self, priority: i32, handler: F) -> Subscription
+    where
+        F: FnMut(&mut State, &mut WindowContext, &mut KeyEvent) -> Propagation + 'static,
+    {
+        self.events.add_key(priority, Box::new(handler))
+    }
+
+    /// Runs commands when their key bindings are pressed, before the other key handlers.
+    ///
+    /// Bindings are matched against the `KeyContext` shared with the window, if there is one.
+    /// Keys that complete or continue a binding are consumed.
+    pub fn bind_keymap(&mut self, keymap: Keymap, mut commands: Commands<State>) -> Subscription {
+        let mut resolver = KeyResolver::new();
+        self.on_key_with_priority(KEYMAP_PRIORITY, move |state, context, event| {
+            if is_modifier_key(event) {
+                return Propagation::Continue;
             }
-            CoreEvent::MemoryWarning => {
-                for delegate in &self.events {
-                    if let CoreEventReference::MemoryWarning = delegate.event {
-                        (delegate.response)(&mut self.window, core_event);
+            let Some(chord) = KeyChord::from_event(event) else {
+                return Propagation::Continue;
+            };
+            let was_pending = !resolver.pending().is_empty();
+            let resolution = {
+                let key_context = context.objects.borrow::<KeyContext>();
+                let empty = KeyContext::new();
+                resolver.feed(&keymap, chord, key_context.as_deref().unwrap_or(&empty))
+            };
+            match resolution {
+                KeyResolution::Command(name) => {
+                    if commands.run(&name, state, context) {
+                        Propagation::Stop
+                    } else {
+                        Propagation::Continue
                     }
                 }
+                KeyResolution::Pending => Propagation::Stop,
+                // The rest of a sequence that went nowhere is swallowed too.
+                KeyResolution::Unmatched if was_pending => Propagation::Stop,
+                KeyResolution::Unmatched => Propagation::Continue,
             }
+        })
+    }
+
+    /// Subscribes to the text input stream until the returned subscription is dropped.
+    ///
+    /// Text editors should use this instead of `on_key`, as it also carries typed text and
+    /// IME composition. Composition is only reported once `set_ime_allowed(true)` is called.
+    pub fn on_text_input<F>(&mut self, handler: F) -> Subscription
+    where
+        F: FnMut(&mut State, &mut WindowContext, &mut TextInputEvent) -> Propagation + 'static,
+    {
+        self.on_text_input_with_priority(0, handler)
+    }
+
+    /// Subscribes to the text input stream, calling handlers with a higher priority first.
+    pub fn on_text_input_with_priority<F>(&mut self, priority: i32, handler: F) -> Subscription
+    where
+        F: FnMut(&mut State, &mut WindowContext, &mut TextInputEvent) -> Propagation + 'static,
+    {
+        self.events.add_text(priority, Box::new(handler))
+    }
+
+    /// Enables or disables the input method, which composes text for languages like Chinese or Japanese.
+    pub fn set_ime_allowed(&mut self, allowed: bool) {
+        if let Some(window) = &self.window {
+            window.set_ime_allowed(allowed);
         }
     }
 
-    /// Adds an event handler for a specific core event type.
-    pub fn on_event<F>(&mut self, event_type: CoreEventReference, handler: F)
+    /// Moves the input method's candidate window next to the text cursor, in physical pixels.
+    pub fn set_ime_position(&mut self, x: f64, y: f64) {
+        if let Some(window) = &self.window {
+            window.set_ime_position(winit::dpi::PhysicalPosition::new(x, y));
+        }
+    }
+
+    /// Subscribes to clicks, drags, hovering and scrolling until the returned subscription is dropped.
+    ///
+    /// Gestures are built from the raw mouse input, so a mouse handler that stops an event
+    /// also keeps its gestures from being reported.
+    pub fn on_gesture<F>(&mut self, handler: F) -> Subscription
     where
-        F: Fn(&mut winit::window::Window, &mut CoreEvent) + 'static,
+        F: FnMut(&mut State, &mut WindowContext, &mut GestureEvent) -> Propagation + 'static,
     {
-        self.events.push(EventDelegate {
-            response: Box::new(handler),
-            event: event_type,
-        });
+        self.on_gesture_with_priority(0, handler)
+    }
+
+    /// Subscribes to gestures, calling handlers with a higher priority first.
+    pub fn on_gesture_with_priority<F>(&mut self, priority: i32, handler: F) -> Subscription
+    where
+        F: FnMut(&mut State, &mut WindowContext, &mut GestureEvent) -> Propagation + 'static,
+    {
+        self.events.add_gesture(priority, Box::new(handler))
+    }
+
+    /// Changes the thresholds used to recognize drags and multiple clicks.
+    pub fn set_gesture_settings(&mut self, settings: GestureSettings) {
+        self.events.gestures.settings = settings;
+    }
+
+    /// Changes the mouse cursor until the cursor moves onto another object.
+    pub fn set_cursor_icon(&mut self, icon: CursorIcon) {
+        if let Some(window) = &self.window {
+            window.set_cursor_icon(icon.to_winit());
+        }
+    }
+
+    /// Subscribes to mouse input until the returned subscription is dropped.
+    pub fn on_mouse<F>(&mut self, handler: F) -> Subscription
+    where
+        F: FnMut(&mut State, &mut WindowContext, &mut MouseEvent) -> Propagation + 'static,
+    {
+        self.on_mouse_with_priority(0, handler)
+    }
+
+    /// Subscribes to mouse input, calling handlers with a higher priority first.
+    pub fn on_mouse_with_priority<F>(&mut self, priority: i32, handler: F) -> Subscription
+    where
+        F: FnMut(&mut State, &mut WindowContext, &mut MouseEvent) -> Propagation + 'static,
+    {
+        self.events.add_mouse(priority, Box::new(handler))
+    }
+
+    /// Subscribes to window resizes until the returned subscription is dropped.
+    pub fn on_resize<F>(&mut self, handler: F) -> Subscription
+    where
+        F: FnMut(&mut State, &mut WindowContext, &mut ResizeEvent) -> Propagation + 'static,
+    {
+        self.on_resize_with_priority(0, handler)
+    }
+
+    /// Subscribes to window resizes, calling handlers with a higher priority first.
+    pub fn on_resize_with_priority<F>(&mut self, priority: i32, handler: F) -> Subscription
+    where
+        F: FnMut(&mut State, &mut WindowContext, &mut ResizeEvent) -> Propagation + 'static,
+    {
+        self.events.add_resize(priority, Box::new(handler))
+    }
+
+    /// Subscribes to messages of type `T` posted through an `EventSender` until the returned
+    /// subscription is dropped.
+    pub fn on_user_event<T: 'static, F>(&mut self, handler: F) -> Subscription
+    where
+        F: FnMut(&mut State, &mut WindowContext, &mut T) -> Propagation + 'static,
+    {
+        self.on_user_event_with_priority(0, handler)
+    }
+
+    /// Subscribes to messages of type `T`, calling handlers with a higher priority first.
+    pub fn on_user_event_with_priority<T: 'static, F>(
+        &mut self,
+        priority: i32,
+        handler: F,
+    ) -> Subscription
+    where
+        F: FnMut(&mut State, &mut WindowContext, &mut T) -> Propagation + 'static,
+    {
+        self.events.add_user(priority, handler)
+    }
+
+    /// Returns a sender that posts messages of type `T` to the event loop from any thread.
+    pub fn event_sender<T: Send + 'static>(&self) -> EventSender<T> {
+        EventSender::new(self.sink.clone())
+    }
+
+    /// Calls `callback` once after `delay`.
+    pub fn set_timeout<F>(&mut self, delay: Duration, callback: F) -> TimerId
+    where
+        F: FnMut(&mut WindowContext) + 'static,
+    {
+        self.scheduler.set_timeout(delay, Box::new(callback))
+    }
+
+    /// Calls `callback` every `period`, of at least a millisecond, until the timer is cleared.
+    ///
+    /// Timers don't redraw the window; call `request_redraw` from the callback after changing
+    /// what is drawn.
+    pub fn set_interval<F>(&mut self, period: Duration, callback: F) -> TimerId
+    where
+        F: FnMut(&mut WindowContext) + 'static,
+    {
+        self.scheduler.set_interval(period, Box::new(callback))
+    }
+
+    /// Recompiles the shaders whenever a `.metal` file in `dir` changes, for live shader editing.
+    ///
+    /// Pass `SHADER_DIR` to edit the built-in shaders. The directory is checked twice a second;
+    /// compile errors are reported and the previous shaders are kept. Clear the returned timer
+    /// to stop watching.
+    pub fn watch_shaders(&mut self, dir: impl AsRef<Path>) -> TimerId {
+        let mut watcher = ShaderWatcher::new(dir.as_ref());
+        self.set_interval(Duration::from_millis(500), move |context| {
+            match watcher.poll() {
+                Ok(Some(source)) => match context.renderer.reload_shaders(&source) {
+                    Ok(()) => context.request_redraw(),
+                    Err(error) => eprintln!("Cannot reload shaders: {error}"),
+                },
+                Ok(None) => (),
+                Err(error) => eprintln!("Cannot read shaders: {error}"),
+            }
+        })
+    }
+
+    /// Cancels a timeout or interval.
+    pub fn clear_timer(&mut self, id: TimerId) {
+        self.scheduler.clear_timer(id);
+    }
+
+    /// Calls `callback` with the frame time right before the next frame is drawn.
+    pub fn request_animation_frame<F>(&mut self, callback: F)
+    where
+        F: FnOnce(&mut WindowContext, Instant) + 'static,
+    {
+        self.scheduler.request_animation_frame(Box::new(callback));
+    }
+
+    /// Redraws the window on the next frame.
+    pub fn request_redraw(&mut self) {
+        self.scheduler.request_redraw();
     }
 
-    #[cfg(target_os = "macos")]
     /// Sets the update function that will be called each frame.
+    ///
+    /// Frames are drawn on demand, so use `request_animation_frame` or a timer to animate.
     pub fn each_frame<F>(&mut self, update: F)
     where
-        F: Fn(
-                &mut winit::window::Window,
-                &mut crate::macos::metal::MetalRenderer,
-                &mut SharedObjects,
-            ) + 'static,
+        F: FnMut(&mut State, &mut WindowContext) + 'static,
     {
         self.update = Box::new(update);
     }
 
-    /// Shares an object with the window's shared objects.
-    pub fn share_object<T: 'static + Send + Sync>(&mut self, object: T) {
-        self.shared_objects.add_object(object);
+    /// Shares an object with the window's shared objects, replacing any object of the same type.
+    pub fn share_object<T: 'static>(&mut self, object: T) {
+        self.shared_objects.insert(object);
+    }
+
+    /// Shares an object under a name, replacing any object with the same type and name.
+    pub fn share_named_object<T: 'static>(&mut self, name: &str, object: T) {
+        self.shared_objects.insert_named(name, object);
+    }
+
+    /// Gets the objects shared with the window.
+    pub fn shared_objects(&mut self) -> &mut SharedObjects {
+        &mut self.shared_objects
     }
 
     /// Launches the window and starts the event loop.
-    pub fn launch(self) {
-        let mut window = self.window;
-        let mut objects = self.shared_objects;
-        let update = self.update;
-        let mut renderer = self.renderer;
-        let events = self.events;
-
-        renderer.resize(self.width as f64, self.height as f64);
-
-        self.event_loop.run(move |event, _, control_flow| {
-            *control_flow = ControlFlow::Wait;
-
-            // Handle custom events before processing winit events
-            Self::execute_event_static(&mut window, &events, &event);
-
-            match event {
-                Event::WindowEvent { event, .. } => match event {
-                    WindowEvent::CloseRequested => {
-                        renderer.destroy();
-                        *control_flow = ControlFlow::Exit;
-                    }
-                    WindowEvent::Resized(physical_size) => {
-                        renderer.resize(physical_size.width as f64, physical_size.height as f64);
-                    }
-                    _ => (),
-                },
-                Event::MainEventsCleared => {
-                    window.request_redraw();
-                }
-                Event::RedrawRequested(_) => {
-                    #[cfg(target_os = "macos")]
-                    {
-                        if let Some(metal_renderer) = renderer
-                            .as_any_mut()
-                            .downcast_mut::<crate::macos::metal::MetalRenderer>(
-                        ) {
-                            (update)(&mut window, metal_renderer, &mut objects);
-                        } else {
-                            panic!("Renderer is not a MetalRenderer");
-                        }
-                    }
-                    renderer.render(&window);
-                }
-                _ => (),
-            }
-        });
+    ///
+    /// Windows created by an `Application` are shown with `Application::run` instead, and headless
+    /// windows are driven by hand; both return `WindowError::NoEventLoop`.
+    pub fn launch(mut self) -> Result<(), WindowError> {
+        let Some((event_loop, resources)) = self.event_loop.take() else {
+            return Err(WindowError::NoEventLoop);
+        };
+        let mut application = Application::from_event_loop(event_loop, resources);
+        application.add_window(self);
+        application.run();
     }
 
     /// Adds an object to the renderer.
@@ -482,22 +1081,334 @@ impl Window {
     /// Sets the title of the window.
     pub fn set_title(&mut self, title: &str) {
         self.title = title.to_string();
-        self.window.set_title(&self.title);
+        if let Some(window) = &self.window {
+            window.set_title(&self.title);
+        }
     }
 
     /// Sets the size of the window.
     pub fn set_size(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
-        self.window
-            .set_inner_size(winit::dpi::LogicalSize::new(width, height));
+        if let Some(window) = &self.window {
+            window.set_inner_size(LogicalSize::new(width, height));
+        }
         self.renderer.resize(width as f64, height as f64);
     }
 
     /// Gets the current framebuffer size of the window. In macOS and iOS, this is usually twice the size of the window.
     pub fn framebuffer_size(&self) -> [f32; 2] {
-        let size = self.window.inner_size();
-        [size.width as f32, size.height as f32]
+        match &self.window {
+            Some(window) => {
+                let size = window.inner_size();
+                [size.width as f32, size.height as f32]
+            }
+            None => [self.width as f32, self.height as f32],
+        }
+    }
+
+    /// Moves the window's top-left corner to a position on the screen, in logical pixels.
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        if let Some(window) = &self.window {
+            window.set_outer_position(LogicalPosition::new(x, y));
+        }
+    }
+
+    /// Returns the position of the window's top-left corner, in logical pixels.
+    pub fn position(&self) -> Option<(i32, i32)> {
+        let window = self.window.as_ref()?;
+        let position = window
+            .outer_position()
+            .ok()?
+            .to_logical::<i32>(window.scale_factor());
+        Some((position.x, position.y))
+    }
+
+    /// Sets the smallest size the user can resize the window to, or removes it with `None`.
+    pub fn set_min_size(&mut self, size: Option<(u32, u32)>) {
+        if let Some(window) = &self.window {
+            window.set_min_inner_size(size.map(|(width, height)| LogicalSize::new(width, height)));
+        }
+    }
+
+    /// Sets the largest size the user can resize the window to, or removes it with `None`.
+    pub fn set_max_size(&mut self, size: Option<(u32, u32)>) {
+        if let Some(window) = &self.window {
+            window.set_max_inner_size(size.map(|(width, height)| LogicalSize::new(width, height)));
+        }
+    }
+
+    /// Maximizes or restores the window.
+    pub fn set_maximized(&mut self, maximized: bool) {
+        if let Some(window) = &self.window {
+            window.set_maximized(maximized);
+        }
+    }
+
+    /// Returns whether the window is maximized.
+    pub fn is_maximized(&self) -> bool {
+        self.window
+            .as_ref()
+            .is_some_and(|window| window.is_maximized())
+    }
+
+    /// Minimizes or restores the window.
+    pub fn set_minimized(&mut self, minimized: bool) {
+        if let Some(window) = &self.window {
+            window.set_minimized(minimized);
+        }
+    }
+
+    /// Makes the window fullscreen or windowed.
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        if let Some(window) = &self.window {
+            window
+                .set_fullscreen(fullscreen.then_some(winit::window::Fullscreen::Borderless(None)));
+        }
+    }
+
+    /// Lets the user resize the window or not.
+    pub fn set_resizable(&mut self, resizable: bool) {
+        if let Some(window) = &self.window {
+            window.set_resizable(resizable);
+        }
+    }
+
+    /// Shows or hides the window's border and title bar.
+    pub fn set_decorations(&mut self, decorations: bool) {
+        if let Some(window) = &self.window {
+            window.set_decorations(decorations);
+        }
+    }
+
+    /// Keeps the window above the other windows or not.
+    pub fn set_always_on_top(&mut self, always_on_top: bool) {
+        if let Some(window) = &self.window {
+            window.set_window_level(if always_on_top {
+                WindowLevel::AlwaysOnTop
+            } else {
+                WindowLevel::Normal
+            });
+        }
+    }
+
+    /// Sets the window's icon, or removes it with `None`.
+    pub fn set_icon(&mut self, icon: Option<WindowIcon>) {
+        if let Some(window) = &self.window {
+            window.set_window_icon(icon.map(|icon| icon.0));
+        }
+    }
+
+    /// Returns the current position, size and state of the window.
+    pub fn geometry(&self) -> Option<WindowGeometry> {
+        let window = self.window.as_ref()?;
+        let (x, y) = self.position()?;
+        let size = window.inner_size().to_logical::<u32>(window.scale_factor());
+        Some(WindowGeometry {
+            x,
+            y,
+            width: size.width,
+            height: size.height,
+            maximized: window.is_maximized(),
+        })
+    }
+
+    /// Moves and resizes the window to a saved geometry.
+    ///
+    /// The position is skipped if no monitor shows it anymore, so the window is never restored
+    /// off screen.
+    pub fn restore_geometry(&mut self, geometry: &WindowGeometry) {
+        let Some(window) = &self.window else {
+            return;
+        };
+        let scale_factor = window.scale_factor();
+        let position =
+            LogicalPosition::new(geometry.x, geometry.y).to_physical::<i32>(scale_factor);
+        let on_screen = window.available_monitors().any(|monitor| {
+            let origin = monitor.position();
+            let size = monitor.size();
+            position.x >= origin.x
+                && position.y >= origin.y
+                && position.x < origin.x + size.width as i32
+                && position.y < origin.y + size.height as i32
+        });
+        if on_screen {
+            self.set_position(geometry.x, geometry.y);
+        }
+        self.set_size(geometry.width, geometry.height);
+        self.set_maximized(geometry.maximized);
+    }
+
+    /// Restores the geometry saved in a file, if any, and saves it there when the window closes.
+    pub fn remember_geometry(&mut self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        if let Ok(geometry) = WindowGeometry::load(path) {
+            self.restore_geometry(&geometry);
+        }
+        self.geometry_path = Some(path.to_path_buf());
+    }
+
+    /// Writes the window's geometry to the file given to `remember_geometry`.
+    fn save_geometry(&self) {
+        let (Some(path), Some(mut geometry)) = (&self.geometry_path, self.geometry()) else {
+            return;
+        };
+        // A maximized window keeps the size it is restored to when unmaximized.
+        let previous = geometry
+            .maximized
+            .then(|| WindowGeometry::load(path).ok())
+            .flatten();
+        if let Some(previous) = previous {
+            geometry = WindowGeometry {
+                maximized: true,
+                ..previous
+            };
+        }
+        if let Err(error) = geometry.save(path) {
+            eprintln!("Cannot save the window geometry: {error}");
+        }
+    }
+}
+
+impl<State: 'static> ManagedWindow for Window<State> {
+    fn id(&self) -> WindowId {
+        self.id
+    }
+
+    fn handle_window_event(&mut self, event: &WindowEvent) {
+        self.record(RecordedWindowEvent::from_winit(event).map(RecordedEvent::Window));
+        let mut context = WindowContext {
+            window: self.window.as_mut(),
+            id: self.id,
+            renderer: self.renderer.as_mut(),
+            objects: &mut self.shared_objects,
+            commands: &mut self.commands,
+            sink: &self.sink,
+            scheduler: &mut self.scheduler,
+        };
+        // Typed handlers run first and can keep the event from the generic ones.
+        if self
+            .events
+            .dispatch_window_event(&mut self.state, &mut context, event)
+            == Propagation::Continue
+        {
+            self.events.dispatch_core(
+                &mut self.state,
+                &mut context,
+                &mut CoreEvent::WindowEvent(window_from_winit_event(event)),
+            );
+        }
+
+        // Handlers that change what is drawn ask for a redraw through their context.
+        match event {
+            WindowEvent::CloseRequested => self.commands.close(self.id),
+            WindowEvent::Resized(physical_size) => {
+                self.renderer
+                    .resize(physical_size.width as f64, physical_size.height as f64);
+                self.scheduler.request_redraw();
+            }
+            WindowEvent::ScaleFactorChanged { .. } | WindowEvent::ThemeChanged(_) => {
+                self.scheduler.request_redraw();
+            }
+            WindowEvent::Occluded(occluded) => {
+                self.occluded = *occluded;
+                if !occluded {
+                    self.scheduler.request_redraw();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn handle_device_event(&mut self, event: &winit::event::DeviceEvent) {
+        self.record(Some(RecordedEvent::Device(
+            RecordedDeviceEvent::from_winit(event),
+        )));
+        self.handle_core_event(&mut CoreEvent::DeviceEvent(device_from_winit_event(event)));
+    }
+
+    fn handle_user_event(&mut self, message: &mut UserMessage) -> Propagation {
+        self.scheduler.request_redraw();
+        let mut context = WindowContext {
+            window: self.window.as_mut(),
+            id: self.id,
+            renderer: self.renderer.as_mut(),
+            objects: &mut self.shared_objects,
+            commands: &mut self.commands,
+            sink: &self.sink,
+            scheduler: &mut self.scheduler,
+        };
+        if self
+            .events
+            .dispatch_user(&mut self.state, &mut context, message)
+            == Propagation::Stop
+        {
+            return Propagation::Stop;
+        }
+
+        // Core subscribers get the message itself, which is handed back once they are done.
+        let payload = std::mem::replace(message, Box::new(()));
+        let mut core_event = CoreEvent::UserEvent(payload);
+        let propagation = self
+            .events
+            .dispatch_core(&mut self.state, &mut context, &mut core_event);
+        if let CoreEvent::UserEvent(payload) = core_event {
+            *message = payload;
+        }
+        propagation
+    }
+
+    fn prepare_frame(&mut self, now: Instant) {
+        let due = self.scheduler.take_due(now);
+        for mut timer in due {
+            let mut context = WindowContext {
+                window: self.window.as_mut(),
+                id: self.id,
+                renderer: self.renderer.as_mut(),
+                objects: &mut self.shared_objects,
+                commands: &mut self.commands,
+                sink: &self.sink,
+                scheduler: &mut self.scheduler,
+            };
+            timer.fire(&mut context);
+            self.scheduler.reschedule(timer, now);
+        }
+
+        // A headless or hidden window keeps the request until it can draw the frame.
+        match &self.window {
+            Some(window) if !self.occluded && self.scheduler.take_redraw() => {
+                window.request_redraw()
+            }
+            _ => (),
+        }
+    }
+
+    fn next_deadline(&self) -> Option<Instant> {
+        self.scheduler.next_deadline()
+    }
+
+    fn redraw(&mut self) {
+        self.draw_frame(Instant::now());
+    }
+
+    fn take_commands(&mut self) -> WindowCommands {
+        std::mem::take(&mut self.commands)
+    }
+
+    fn set_device(&mut self, device: &RenderDevice) {
+        let Some(window) = &self.window else {
+            return;
+        };
+        match self.renderer.set_device(window, device) {
+            Ok(()) => self.scheduler.request_redraw(),
+            Err(error) => eprintln!("Cannot move the window to the new render device: {error}"),
+        }
+    }
+
+    fn destroy(&self) {
+        // Every way of closing a window ends here, including `close` and `exit` commands.
+        self.save_geometry();
+        self.renderer.destroy();
     }
 }
 
@@ -560,6 +1471,7 @@ fn window_from_winit_event(event: &winit::event::WindowEvent) -> CoreWindowEvent
         WindowEvent::Focused(focused) => CoreWindowEvent::Focused(*focused),
         WindowEvent::ReceivedCharacter(character) => CoreWindowEvent::RecievedChar(*character),
         WindowEvent::KeyboardInput { input, .. } => CoreWindowEvent::KeyboardInput(*input),
+        WindowEvent::Ime(ime) => CoreWindowEvent::Ime(ime.clone()),
         WindowEvent::ModifiersChanged(modifiers) => CoreWindowEvent::ModifierChanged(*modifiers),
         WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
             CoreWindowEvent::DPIChanged(*scale_factor, *scale_factor)
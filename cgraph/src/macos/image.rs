@@ -17,7 +17,8 @@ pub struct Image {
 impl Image {
     /// Creates a new Image from a file path.
     pub fn new(source: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let device = metal::Device::system_default().expect("No Metal device found");
+        let device =
+            metal::Device::system_default().ok_or(crate::renderer::RenderError::NoDevice)?;
 
         let img = image::open(Path::new(source))?;
         let rgba_img = img.to_rgba8();
@@ -1,16 +1,26 @@
+use block::ConcreteBlock;
 use glam::{Mat4, Vec2, Vec4};
 use memoffset::offset_of;
 use metal::*;
+use objc::{msg_send, sel, sel_impl};
+use std::collections::HashMap;
 use std::mem::size_of;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use winit::window::Window;
 
 use crate::{
     macos::{
+        shader_code::SHADER_CODE,
         shaders::{create_library, setup_alpha_blending},
         view::setup_layer,
     },
-    object::{Object, Vertex, primitives::Color},
-    renderer::Renderer,
+    object::{
+        Object, Vertex,
+        material::Material,
+        primitives::{Color, ShapeKind},
+    },
+    renderer::{FrameStats, RenderDevice, RenderError, Renderer},
 };
 
 use core_graphics_types::geometry::CGSize;
@@ -30,141 +40,26 @@ pub struct MetalRenderer {
     depth_texture: Texture,
     /// The background color for the renderer.
     pub background_color: Color,
+    stats: FrameStats,
+    // The GPU time of the last completed frame, written by the command buffer's completion handler.
+    gpu_time: Arc<Mutex<Duration>>,
+    // Drawn above the objects and left out of the stats.
+    overlay: Option<Object>,
+    // The commands of the last frame, checked on the next one to notice a lost device.
+    last_command_buffer: Option<CommandBuffer>,
+    // The source of the built-in shaders, replaced when they are reloaded.
+    shader_source: String,
+    // Pipelines of the materials by function name, or `None` if the material failed to compile.
+    materials: HashMap<String, Option<RenderPipelineState>>,
 }
 
 impl Renderer for MetalRenderer {
-    fn new(window: &Window, background_color: Color) -> Self
+    fn new(window: &Window, background_color: Color) -> Result<Self, RenderError>
     where
         Self: Sized,
     {
-        let device = Device::system_default().expect("No Metal device found");
-        let command_queue = device.new_command_queue();
-
-        let pipeline_descriptor = RenderPipelineDescriptor::new();
-        let library = create_library(&device);
-        let vertex = library
-            .get_function("vertex_main", None)
-            .expect("Failed to get vertex function");
-        let fragment = library
-            .get_function("fragment_main", None)
-            .expect("Failed to get fragment function");
-        pipeline_descriptor.set_vertex_function(Some(&vertex));
-        pipeline_descriptor.set_fragment_function(Some(&fragment));
-        pipeline_descriptor
-            .color_attachments()
-            .object_at(0)
-            .unwrap()
-            .set_pixel_format(MTLPixelFormat::RGBA8Unorm);
-        pipeline_descriptor.set_sample_count(4);
-
-        pipeline_descriptor.set_depth_attachment_pixel_format(MTLPixelFormat::Depth32Float);
-
-        setup_alpha_blending(&pipeline_descriptor);
-
-        // Enable depth testing
-        let depth_stencil_descriptor = DepthStencilDescriptor::new();
-        depth_stencil_descriptor.set_depth_compare_function(MTLCompareFunction::LessEqual);
-        depth_stencil_descriptor.set_depth_write_enabled(true);
-        let depth_stencil_state = device.new_depth_stencil_state(&depth_stencil_descriptor);
-
-        let vertex_descriptor = VertexDescriptor::new();
-
-        set_vertex_descriptor(
-            vertex_descriptor,
-            offset_of!(Vertex, position),
-            0,
-            MTLVertexFormat::Float2,
-        );
-
-        set_vertex_descriptor(
-            vertex_descriptor,
-            offset_of!(Vertex, color),
-            1,
-            MTLVertexFormat::Float4,
-        );
-
-        set_vertex_descriptor(
-            vertex_descriptor,
-            offset_of!(Vertex, z_index),
-            2,
-            MTLVertexFormat::Float,
-        );
-
-        set_vertex_descriptor(
-            vertex_descriptor,
-            offset_of!(Vertex, uv),
-            3,
-            MTLVertexFormat::Float2,
-        );
-
-        vertex_descriptor
-            .layouts()
-            .object_at(0)
-            .unwrap()
-            .set_stride(size_of::<Vertex>() as u64);
-        vertex_descriptor
-            .layouts()
-            .object_at(0)
-            .unwrap()
-            .set_step_function(MTLVertexStepFunction::PerVertex);
-        vertex_descriptor
-            .layouts()
-            .object_at(0)
-            .unwrap()
-            .set_step_rate(1);
-
-        pipeline_descriptor.set_vertex_descriptor(Some(vertex_descriptor));
-
-        let state = match device.new_render_pipeline_state(&pipeline_descriptor) {
-            Ok(state) => state,
-            Err(e) => panic!("Failed to create render pipeline state: {e}"),
-        };
-
-        let layer = setup_layer(device.as_ref(), window);
-
-        // Create a sampler for texture sampling
-        let sampler_descriptor = SamplerDescriptor::new();
-        sampler_descriptor.set_min_filter(MTLSamplerMinMagFilter::Linear);
-        sampler_descriptor.set_mag_filter(MTLSamplerMinMagFilter::Linear);
-        sampler_descriptor.set_mip_filter(MTLSamplerMipFilter::Linear);
-        sampler_descriptor.set_address_mode_s(MTLSamplerAddressMode::ClampToEdge);
-        sampler_descriptor.set_address_mode_t(MTLSamplerAddressMode::ClampToEdge);
-        let sampler = device.new_sampler(&sampler_descriptor);
-
-        let msaa_texture_desc = TextureDescriptor::new();
-        msaa_texture_desc.set_pixel_format(MTLPixelFormat::RGBA8Unorm);
-        msaa_texture_desc.set_width(window.inner_size().width as u64);
-        msaa_texture_desc.set_height(window.inner_size().height as u64);
-        msaa_texture_desc.set_storage_mode(MTLStorageMode::Private);
-        msaa_texture_desc.set_usage(MTLTextureUsage::RenderTarget);
-        msaa_texture_desc.set_texture_type(MTLTextureType::D2Multisample);
-        msaa_texture_desc.set_sample_count(4); // Enable MSAA with 4 samples
-        let msaa_texture = device.new_texture(&msaa_texture_desc);
-
-        let depth_texture = {
-            let depth_desc = TextureDescriptor::new();
-            depth_desc.set_pixel_format(MTLPixelFormat::Depth32Float);
-            depth_desc.set_width(window.inner_size().width as u64);
-            depth_desc.set_height(window.inner_size().height as u64);
-            depth_desc.set_storage_mode(MTLStorageMode::Private);
-            depth_desc.set_usage(MTLTextureUsage::RenderTarget);
-            depth_desc.set_texture_type(MTLTextureType::D2Multisample);
-            depth_desc.set_sample_count(4);
-            device.new_texture(&depth_desc)
-        };
-
-        MetalRenderer {
-            device,
-            command_queue,
-            state,
-            depth_stencil_state,
-            layer,
-            objects: Vec::new(),
-            sampler,
-            msaa_texture,
-            depth_texture,
-            background_color,
-        }
+        let device = Device::system_default().ok_or(RenderError::NoDevice)?;
+        MetalRenderer::with_device(window, background_color, &device)
     }
 
     fn add_object(&mut self, object: crate::object::Object) {
@@ -175,18 +70,71 @@ impl Renderer for MetalRenderer {
         self.objects.clear();
     }
 
+    fn objects_mut(&mut self) -> &mut Vec<Object> {
+        &mut self.objects
+    }
+
     fn destroy(&self) {}
 
     fn set_background_color(&mut self, background_color: Color) {
         self.background_color = background_color;
     }
 
-    fn render(&mut self, _window: &winit::window::Window) {
-        let command_buffer = self.command_queue.new_command_buffer();
-        let drawable = self.layer.next_drawable().expect("Failed to get drawable");
+    fn set_overlay(&mut self, overlay: Option<Object>) {
+        self.overlay = overlay;
+    }
+
+    fn set_device(&mut self, window: &Window, device: &RenderDevice) -> Result<(), RenderError> {
+        self.rebuild(window, &device.device)
+    }
+
+    fn frame_stats(&self) -> FrameStats {
+        FrameStats {
+            gpu_time: self.gpu_time.lock().map(|time| *time).unwrap_or_default(),
+            ..self.stats
+        }
+    }
+
+    fn reload_shaders(&mut self, source: &str) -> Result<(), RenderError> {
+        let library = create_library(&self.device, source)?;
+        self.state = create_pipeline(&self.device, &library, "fragment_main")?;
+        self.shader_source = source.to_string();
+        // Materials are compiled with the built-in shaders, so they are compiled again.
+        self.materials.clear();
+        Ok(())
+    }
+
+    fn render(&mut self, _window: &winit::window::Window) -> Result<(), RenderError> {
+        // Metal fails the command buffers of a device that was removed, such as an unplugged eGPU.
+        if self
+            .last_command_buffer
+            .take()
+            .is_some_and(|buffer| matches!(buffer.status(), MTLCommandBufferStatus::Error))
+        {
+            return Err(RenderError::DeviceLost);
+        }
+
+        // Minimized and occluded windows have no drawable, so the frame is skipped.
+        let Some(drawable) = self.layer.next_drawable().map(ToOwned::to_owned) else {
+            return Ok(());
+        };
+        let width = drawable.texture().width();
+        let height = drawable.texture().height();
+        if self.depth_texture.width() != width || self.depth_texture.height() != height {
+            // The drawable can change size before the resize event is handled.
+            self.update_size_textures(width, height);
+        }
 
-        self.depth_texture =
-            self.create_depth_texture(drawable.texture().width(), drawable.texture().height());
+        self.prepare_materials();
+
+        let start = Instant::now();
+        let allocations = crate::object::buffer::allocation_count();
+        let mut stats = FrameStats {
+            objects: self.objects.len() as u32,
+            ..FrameStats::default()
+        };
+
+        let command_buffer = self.command_queue.new_command_buffer();
 
         let render_pass_descriptor = RenderPassDescriptor::new();
         let color_attachment = render_pass_descriptor
@@ -222,63 +170,75 @@ impl Renderer for MetalRenderer {
         for object in &mut self.objects {
             if object.shadow_on {
                 let shadow_buffer = object.get_shadow_buffer();
-                encoder.set_vertex_buffer(0, Some(&shadow_buffer.buffer), 0);
+                stats.vertices += shadow_buffer.data.len() as u32;
+                encoder.set_vertex_buffer(0, Some(shadow_buffer.upload(&self.device)), 0);
 
-                let shadow_uniform_buffer =
+                let mut shadow_uniform_buffer =
                     object.make_shadow_position_uniforms_expanded(&self.layer);
-                encoder.set_vertex_buffer(1, Some(&shadow_uniform_buffer.buffer), 0);
-                encoder.set_fragment_buffer(0, Some(&shadow_uniform_buffer.buffer), 0);
+                let shadow_uniform_buffer = shadow_uniform_buffer.upload(&self.device);
+                encoder.set_vertex_buffer(1, Some(shadow_uniform_buffer), 0);
+                encoder.set_fragment_buffer(0, Some(shadow_uniform_buffer), 0);
+
+                let mut shadow_uniforms = object.make_shadow_uniforms_enabled();
+                encoder.set_fragment_buffer(2, Some(shadow_uniforms.upload(&self.device)), 0);
 
-                let shadow_uniforms = object.make_shadow_uniforms_enabled();
-                encoder.set_fragment_buffer(2, Some(&shadow_uniforms.buffer), 0);
+                let mut shape_points =
+                    crate::object::buffer::Buffer::new(object.get_shape_points());
+                encoder.set_fragment_buffer(3, Some(shape_points.upload(&self.device)), 0);
 
                 let shadow_index_buffer = object.get_shadow_index_buffer();
+                stats.draw_calls += 1;
                 encoder.draw_indexed_primitives(
                     MTLPrimitiveType::Triangle,
                     shadow_index_buffer.data.len() as u64,
                     MTLIndexType::UInt32,
-                    &shadow_index_buffer.buffer,
+                    shadow_index_buffer.upload(&self.device),
                     0,
                 );
             }
         }
 
         // Second pass: Render main objects
-        for object in &self.objects {
-            let buffer = &object.get_buffer().buffer;
-            encoder.set_vertex_buffer(0, Some(buffer), 0);
-
-            let uniform_buffer = object.make_uniforms(&self.layer);
-            encoder.set_vertex_buffer(1, Some(&uniform_buffer.buffer), 0);
-            encoder.set_fragment_buffer(0, Some(&uniform_buffer.buffer), 0);
+        let mut objects = std::mem::take(&mut self.objects);
+        for object in &mut objects {
+            stats.draw_calls += 1;
+            stats.vertices += object.vertices.len() as u32;
+            self.draw_object(encoder, object);
+        }
+        self.objects = objects;
+        stats.buffer_allocations = (crate::object::buffer::allocation_count() - allocations) as u32;
+        // The overlay is drawn last and left out of the stats.
+        if let Some(mut overlay) = self.overlay.take() {
+            self.draw_object(encoder, &mut overlay);
+            self.overlay = Some(overlay);
+        }
 
-            let shadow_uniforms = object.make_shadow_uniforms_disabled();
-            encoder.set_fragment_buffer(2, Some(&shadow_uniforms.buffer), 0);
+        encoder.end_encoding();
 
-            if object.use_texture {
-                if let Some(ref texture) = object.texture {
-                    encoder.set_fragment_texture(0, Some(&texture.texture));
-                    encoder.set_fragment_sampler_state(0, Some(&self.sampler));
+        let gpu_time = Arc::clone(&self.gpu_time);
+        let completed = ConcreteBlock::new(move |buffer: &CommandBufferRef| {
+            // Both times are in seconds, and zero when the commands failed.
+            let start: f64 = unsafe { msg_send![buffer, GPUStartTime] };
+            let end: f64 = unsafe { msg_send![buffer, GPUEndTime] };
+            if end > start {
+                if let Ok(mut time) = gpu_time.lock() {
+                    *time = Duration::from_secs_f64(end - start);
                 }
             }
-
-            encoder.draw_indexed_primitives(
-                MTLPrimitiveType::Triangle,
-                object.indices.len() as u64,
-                MTLIndexType::UInt32,
-                &object.get_index_buffer().buffer,
-                0,
-            );
-        }
-
-        encoder.end_encoding();
-        command_buffer.present_drawable(drawable);
+        })
+        .copy();
+        command_buffer.add_completed_handler(&completed);
+        command_buffer.present_drawable(&drawable);
         command_buffer.commit();
+        stats.cpu_time = start.elapsed();
+        self.last_command_buffer = Some(command_buffer.to_owned());
+        self.stats = stats;
+        Ok(())
     }
 
     fn resize(&mut self, width: f64, height: f64) {
         self.layer.set_drawable_size(CGSize::new(width, height));
-        self.update_msaa_texture(width as u64, height as u64);
+        self.update_size_textures(width as u64, height as u64);
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
@@ -313,6 +273,94 @@ fn set_vertex_descriptor(
         .set_buffer_index(0);
 }
 
+/// Creates the uniform block of a material, with one value for materials without uniforms.
+fn material_uniform_buffer(material: &Material) -> crate::object::buffer::Buffer<f32> {
+    let mut uniforms = material.uniforms().to_vec();
+    if uniforms.is_empty() {
+        uniforms.push(0.0);
+    }
+    crate::object::buffer::Buffer::new(uniforms)
+}
+
+/// Creates a pipeline drawing with the built-in vertex shader and a fragment function of `library`.
+fn create_pipeline(
+    device: &Device,
+    library: &Library,
+    fragment_function: &str,
+) -> Result<RenderPipelineState, RenderError> {
+    let pipeline_descriptor = RenderPipelineDescriptor::new();
+    let vertex = library
+        .get_function("vertex_main", None)
+        .map_err(RenderError::Shader)?;
+    let fragment = library
+        .get_function(fragment_function, None)
+        .map_err(RenderError::Shader)?;
+    pipeline_descriptor.set_vertex_function(Some(&vertex));
+    pipeline_descriptor.set_fragment_function(Some(&fragment));
+    pipeline_descriptor
+        .color_attachments()
+        .object_at(0)
+        .unwrap()
+        .set_pixel_format(MTLPixelFormat::RGBA8Unorm);
+    pipeline_descriptor.set_sample_count(4);
+
+    pipeline_descriptor.set_depth_attachment_pixel_format(MTLPixelFormat::Depth32Float);
+
+    setup_alpha_blending(&pipeline_descriptor);
+
+    let vertex_descriptor = VertexDescriptor::new();
+
+    set_vertex_descriptor(
+        vertex_descriptor,
+        offset_of!(Vertex, position),
+        0,
+        MTLVertexFormat::Float2,
+    );
+
+    set_vertex_descriptor(
+        vertex_descriptor,
+        offset_of!(Vertex, color),
+        1,
+        MTLVertexFormat::Float4,
+    );
+
+    set_vertex_descriptor(
+        vertex_descriptor,
+        offset_of!(Vertex, z_index),
+        2,
+        MTLVertexFormat::Float,
+    );
+
+    set_vertex_descriptor(
+        vertex_descriptor,
+        offset_of!(Vertex, uv),
+        3,
+        MTLVertexFormat::Float2,
+    );
+
+    vertex_descriptor
+        .layouts()
+        .object_at(0)
+        .unwrap()
+        .set_stride(size_of::<Vertex>() as u64);
+    vertex_descriptor
+        .layouts()
+        .object_at(0)
+        .unwrap()
+        .set_step_function(MTLVertexStepFunction::PerVertex);
+    vertex_descriptor
+        .layouts()
+        .object_at(0)
+        .unwrap()
+        .set_step_rate(1);
+
+    pipeline_descriptor.set_vertex_descriptor(Some(vertex_descriptor));
+
+    device
+        .new_render_pipeline_state(&pipeline_descriptor)
+        .map_err(RenderError::Pipeline)
+}
+
 #[allow(dead_code)]
 #[derive(Debug)]
 #[repr(C)]
@@ -334,6 +382,35 @@ pub struct Uniforms {
     pub shadow_radius: f32,
     /// The color of the shadow.
     pub shadow_color: Vec4,
+    /// The radius of each corner, from the top-left going clockwise.
+    pub corner_radii: Vec4,
+    /// The outline the fragment shader cuts the rectangle into.
+    pub shape: u32,
+    /// The number of polygon points bound for the shape.
+    pub point_count: u32,
+    /// The width of the border.
+    pub border_width: f32,
+    /// How far the border reaches past the edge of the shape.
+    pub border_outset: f32,
+    /// The color of the border.
+    pub border_color: Vec4,
+    /// The dash and gap lengths of the border, zero for a solid border.
+    pub border_dash: Vec2,
+    /// How far each corner of the quad moves outwards to fit the border.
+    pub vertex_outset: Vec2,
+    /// How far the UV coordinates extend past the quad to fit the border.
+    pub uv_outset: Vec2,
+}
+
+impl ShapeKind {
+    /// Returns the identifier of the shape used by the shaders.
+    pub fn shader_id(&self) -> u32 {
+        match self {
+            ShapeKind::Rect => 0,
+            ShapeKind::Ellipse => 1,
+            ShapeKind::Polygon(_) => 2,
+        }
+    }
 }
 
 impl Object {
@@ -370,6 +447,25 @@ impl Object {
             use_texture: if self.use_texture { 1 } else { 0 },
             shadow_radius: self.shadow_radius,
             shadow_color: self.shadow_color,
+            corner_radii: self.get_corner_radii(),
+            shape: self.shape.shader_id(),
+            point_count: self.get_shape_points().len() as u32,
+            border_width: self.border_width,
+            border_outset: self.border_alignment.outset(self.border_width),
+            border_color: self.border_color,
+            border_dash: self
+                .border_dash
+                .map(|dash| Vec2::new(dash.length, dash.gap))
+                .unwrap_or(Vec2::new(0.0, 0.0)),
+            vertex_outset: self.get_vertex_outset(),
+            uv_outset: if rect_size.x > 0.0 && rect_size.y > 0.0 {
+                Vec2::new(
+                    self.get_border_outset() / rect_size.x,
+                    self.get_border_outset() / rect_size.y,
+                )
+            } else {
+                Vec2::new(0.0, 0.0)
+            },
         };
 
         crate::object::buffer::Buffer::new(vec![uniforms])
@@ -377,6 +473,176 @@ impl Object {
 }
 
 impl MetalRenderer {
+    /// Creates a renderer that draws with an existing Metal device, so several windows can share it.
+    pub fn with_device(
+        window: &Window,
+        background_color: Color,
+        device: &Device,
+    ) -> Result<Self, RenderError> {
+        let device = device.clone();
+        let command_queue = device.new_command_queue();
+
+        let library = create_library(&device, SHADER_CODE)?;
+        let state = create_pipeline(&device, &library, "fragment_main")?;
+
+        // Enable depth testing
+        let depth_stencil_descriptor = DepthStencilDescriptor::new();
+        depth_stencil_descriptor.set_depth_compare_function(MTLCompareFunction::LessEqual);
+        depth_stencil_descriptor.set_depth_write_enabled(true);
+        let depth_stencil_state = device.new_depth_stencil_state(&depth_stencil_descriptor);
+
+        let layer = setup_layer(device.as_ref(), window);
+
+        // Create a sampler for texture sampling
+        let sampler_descriptor = SamplerDescriptor::new();
+        sampler_descriptor.set_min_filter(MTLSamplerMinMagFilter::Linear);
+        sampler_descriptor.set_mag_filter(MTLSamplerMinMagFilter::Linear);
+        sampler_descriptor.set_mip_filter(MTLSamplerMipFilter::Linear);
+        sampler_descriptor.set_address_mode_s(MTLSamplerAddressMode::ClampToEdge);
+        sampler_descriptor.set_address_mode_t(MTLSamplerAddressMode::ClampToEdge);
+        let sampler = device.new_sampler(&sampler_descriptor);
+
+        let msaa_texture_desc = TextureDescriptor::new();
+        msaa_texture_desc.set_pixel_format(MTLPixelFormat::RGBA8Unorm);
+        msaa_texture_desc.set_width(window.inner_size().width as u64);
+        msaa_texture_desc.set_height(window.inner_size().height as u64);
+        msaa_texture_desc.set_storage_mode(MTLStorageMode::Private);
+        msaa_texture_desc.set_usage(MTLTextureUsage::RenderTarget);
+        msaa_texture_desc.set_texture_type(MTLTextureType::D2Multisample);
+        msaa_texture_desc.set_sample_count(4); // Enable MSAA with 4 samples
+        let msaa_texture = device.new_texture(&msaa_texture_desc);
+
+        let depth_texture = {
+            let depth_desc = TextureDescriptor::new();
+            depth_desc.set_pixel_format(MTLPixelFormat::Depth32Float);
+            depth_desc.set_width(window.inner_size().width as u64);
+            depth_desc.set_height(window.inner_size().height as u64);
+            depth_desc.set_storage_mode(MTLStorageMode::Private);
+            depth_desc.set_usage(MTLTextureUsage::RenderTarget);
+            depth_desc.set_texture_type(MTLTextureType::D2Multisample);
+            depth_desc.set_sample_count(4);
+            device.new_texture(&depth_desc)
+        };
+
+        Ok(MetalRenderer {
+            device,
+            command_queue,
+            state,
+            depth_stencil_state,
+            layer,
+            objects: Vec::new(),
+            sampler,
+            msaa_texture,
+            depth_texture,
+            background_color,
+            stats: FrameStats::default(),
+            gpu_time: Arc::new(Mutex::new(Duration::ZERO)),
+            overlay: None,
+            last_command_buffer: None,
+            shader_source: SHADER_CODE.to_string(),
+            materials: HashMap::new(),
+        })
+    }
+
+    /// Encodes the draw call of an object.
+    fn draw_object(&self, encoder: &RenderCommandEncoderRef, object: &mut Object) {
+        let (state, material_uniforms) = match &object.material {
+            Some(material) => (
+                self.materials
+                    .get(material.function())
+                    .and_then(Option::as_ref)
+                    .unwrap_or(&self.state),
+                Some(material_uniform_buffer(material)),
+            ),
+            None => (&self.state, None),
+        };
+        encoder.set_render_pipeline_state(state);
+        if let Some(mut uniforms) = material_uniforms {
+            encoder.set_fragment_buffer(4, Some(uniforms.upload(&self.device)), 0);
+        }
+
+        encoder.set_vertex_buffer(0, Some(object.buffer.upload(&self.device)), 0);
+
+        let mut uniform_buffer = object.make_uniforms(&self.layer);
+        let uniform_buffer = uniform_buffer.upload(&self.device);
+        encoder.set_vertex_buffer(1, Some(uniform_buffer), 0);
+        encoder.set_fragment_buffer(0, Some(uniform_buffer), 0);
+
+        let mut shadow_uniforms = object.make_shadow_uniforms_disabled();
+        encoder.set_fragment_buffer(2, Some(shadow_uniforms.upload(&self.device)), 0);
+
+        let mut shape_points = crate::object::buffer::Buffer::new(object.get_shape_points());
+        encoder.set_fragment_buffer(3, Some(shape_points.upload(&self.device)), 0);
+
+        if object.use_texture {
+            if let Some(ref texture) = object.texture {
+                encoder.set_fragment_texture(0, Some(&texture.texture));
+                encoder.set_fragment_sampler_state(0, Some(&self.sampler));
+            }
+        }
+
+        encoder.draw_indexed_primitives(
+            MTLPrimitiveType::Triangle,
+            object.indices.len() as u64,
+            MTLIndexType::UInt32,
+            object.index_buffer.upload(&self.device),
+            0,
+        );
+    }
+
+    /// Compiles the materials of the objects that were not compiled yet.
+    fn prepare_materials(&mut self) {
+        for object in &self.objects {
+            let Some(material) = &object.material else {
+                continue;
+            };
+            if self.materials.contains_key(material.function()) {
+                continue;
+            }
+            let source = format!("{}\n{}", self.shader_source, material.source());
+            let state = create_library(&self.device, &source)
+                .and_then(|library| create_pipeline(&self.device, &library, material.function()));
+            let state = match state {
+                Ok(state) => Some(state),
+                Err(error) => {
+                    eprintln!("Cannot compile material `{}`: {error}", material.function());
+                    None
+                }
+            };
+            self.materials
+                .insert(material.function().to_string(), state);
+        }
+    }
+
+    /// Recreates the GPU resources on `device` and uploads the scene to it.
+    fn rebuild(&mut self, window: &Window, device: &Device) -> Result<(), RenderError> {
+        let mut renderer = MetalRenderer::with_device(window, self.background_color, device)?;
+        let size = self.layer.drawable_size();
+        renderer.resize(size.width, size.height);
+        if self.shader_source != SHADER_CODE {
+            renderer.reload_shaders(&self.shader_source)?;
+        }
+        for mut object in std::mem::take(&mut self.objects) {
+            // Buffers are uploaded to the new device the next time they are drawn.
+            object.buffer.release();
+            object.index_buffer.release();
+            object.shadow_buffer = None;
+            object.shadow_index_buffer = None;
+            if let Some(image) = object.texture.take() {
+                object.texture =
+                    crate::macos::image::Image::new_from_device(&image.source, device).ok();
+            }
+            renderer.objects.push(object);
+        }
+        *self = renderer;
+        Ok(())
+    }
+
+    fn update_size_textures(&mut self, width: u64, height: u64) {
+        self.update_msaa_texture(width, height);
+        self.depth_texture = self.create_depth_texture(width, height);
+    }
+
     fn create_depth_texture(&self, width: u64, height: u64) -> Texture {
         let depth_desc = TextureDescriptor::new();
         depth_desc.set_pixel_format(MTLPixelFormat::Depth32Float);
@@ -1,2 +1,2 @@
 // Auto-generated from 'metal/' directory
-pub const SHADER_CODE: &str = "// File: metal/main.metal\n\n#include <metal_stdlib>\nusing namespace metal;\n\nstruct VertexIn {\n    float2 position [[attribute(0)]];\n    float4 color [[attribute(1)]];\n    float zIndex [[attribute(2)]];\n    float2 uv [[attribute(3)]];\n};\n\nstruct VertexOut {\n    float4 position [[position]];\n    float4 color;\n    float2 uv;\n};\n\nstruct Uniforms {\n    float2 rect_position;\n    float2 rect_size;\n    float corner_radius;\n    float4x4 model_matrix;\n    float4x4 projection_matrix;\n    bool use_texture;\n};\n\nstruct ShadowUniforms {\n    float offset_x;\n    float offset_y;\n    float radius;\n    float4 color;\n    bool enabled;\n};\n\nvertex VertexOut vertex_main(VertexIn in [[stage_in]], constant Uniforms& uniforms [[buffer(1)]]) {\n    VertexOut out;\n    float depth = (0 + in.zIndex) / 50;\n    out.position = uniforms.projection_matrix * uniforms.model_matrix * float4(in.position, depth, 1.0);\n    out.color = in.color;\n    out.uv = in.uv;\n    return out;\n}\n\nfloat rounded_rect_sdf(float2 p, float2 size, float corner_radius) {\n    float2 d = abs(p) - size + corner_radius;\n    return length(max(d, 0.0)) + min(max(d.x, d.y), 0.0) - corner_radius;\n}\n\nfloat calculate_shadow_expanded(float2 uv, float2 original_rect_size, float corner_radius, float blur_radius) {\n    float expansion = blur_radius;\n    \n    float2 expanded_size = original_rect_size + float2(expansion * 2.0, expansion * 2.0);\n    \n    float2 expanded_pos = (uv - 0.5) * expanded_size;\n    \n    float2 half_original_size = original_rect_size * 0.5;\n    float dist = rounded_rect_sdf(expanded_pos, half_original_size, corner_radius);\n    \n    float shadow_alpha = 1.0 - smoothstep(-blur_radius, blur_radius, dist);\n    \n    return clamp(shadow_alpha, 0.0, 1.0);\n}\n\nfragment float4 fragment_main(VertexOut in [[stage_in]], \n                             constant Uniforms& uniforms [[buffer(0)]],\n                             texture2d<float> tex [[texture(0)]],\n                             sampler texSampler [[sampler(0)]],\n                             constant ShadowUniforms& shadowUniforms [[buffer(2)]]) {\n    \n    if (shadowUniforms.enabled) {\n        float shadow_alpha = calculate_shadow_expanded(\n            in.uv, \n            uniforms.rect_size, \n            uniforms.corner_radius, \n            shadowUniforms.radius\n        );\n        \n        if (shadow_alpha <= 0.01) {\n            discard_fragment();\n        }\n        \n        float4 shadow_color = shadowUniforms.color;\n        shadow_color.a *= shadow_alpha;\n        return shadow_color;\n    }\n    \n    float4 final_color;\n    \n    if (uniforms.use_texture) {\n        float4 tex_color = tex.sample(texSampler, in.uv);\n        final_color = tex_color;\n    } else {\n        final_color = in.color;\n    }\n    \n    if (uniforms.corner_radius > 0.0) {\n        float2 local_pos = (in.uv - 0.5) * uniforms.rect_size;\n        float2 half_size = uniforms.rect_size * 0.5;\n        \n        float dist = rounded_rect_sdf(local_pos, half_size, uniforms.corner_radius);\n        \n        float alpha = 1.0 - smoothstep(-1.0, 1.0, dist);\n        if (alpha <= 0.0) {\n            discard_fragment();\n        }\n        final_color.a *= alpha;\n    }\n    \n    return final_color;\n}\n\n";
+pub const SHADER_CODE: &str = "// File: metal/main.metal\n\n#include <metal_stdlib>\nusing namespace metal;\n\nstruct VertexIn {\n    float2 position [[attribute(0)]];\n    float4 color [[attribute(1)]];\n    float zIndex [[attribute(2)]];\n    float2 uv [[attribute(3)]];\n};\n\nstruct VertexOut {\n    float4 position [[position]];\n    float4 color;\n    float2 uv;\n};\n\nstruct Uniforms {\n    float2 rect_position;\n    float2 rect_size;\n    float corner_radius;\n    float4x4 model_matrix;\n    float4x4 projection_matrix;\n    bool use_texture;\n    float shadow_radius;\n    float4 shadow_color;\n    float4 corner_radii;\n    uint shape;\n    uint point_count;\n    float border_width;\n    float border_outset;\n    float4 border_color;\n    float2 border_dash;\n    float2 vertex_outset;\n    float2 uv_outset;\n};\n\nconstant uint SHAPE_RECT = 0;\nconstant uint SHAPE_ELLIPSE = 1;\nconstant uint SHAPE_POLYGON = 2;\n\nstruct ShadowUniforms {\n    float offset_x;\n    float offset_y;\n    float radius;\n    float4 color;\n    bool enabled;\n};\n\nvertex VertexOut vertex_main(VertexIn in [[stage_in]], constant Uniforms& uniforms [[buffer(1)]]) {\n    VertexOut out;\n    float depth = (0 + in.zIndex) / 50;\n    // Grow quads outwards so borders drawn outside the edge have room; UVs grow upwards.\n    float2 direction = sign(in.uv - 0.5);\n    float2 position = in.position + float2(direction.x, -direction.y) * uniforms.vertex_outset;\n    out.position = uniforms.projection_matrix * uniforms.model_matrix * float4(position, depth, 1.0);\n    out.color = in.color;\n    out.uv = in.uv + direction * uniforms.uv_outset;\n    return out;\n}\n\nfloat rounded_rect_sdf(float2 p, float2 size, float corner_radius) {\n    float2 d = abs(p) - size + corner_radius;\n    return length(max(d, 0.0)) + min(max(d.x, d.y), 0.0) - corner_radius;\n}\n\n// Radii are ordered top-left, top-right, bottom-right, bottom-left; y points up.\nfloat rounded_rect_corners_sdf(float2 p, float2 size, float4 radii) {\n    float r = p.y > 0.0 ? (p.x > 0.0 ? radii.y : radii.x) : (p.x > 0.0 ? radii.z : radii.w);\n    r = min(r, min(size.x, size.y));\n    return rounded_rect_sdf(p, size, r);\n}\n\nfloat ellipse_sdf(float2 p, float2 size) {\n    float k1 = length(p / size);\n    float k2 = length(p / (size * size));\n    return k2 > 0.0 ? k1 * (k1 - 1.0) / k2 : -min(size.x, size.y);\n}\n\nfloat polygon_sdf(float2 p, constant float2* points, uint count) {\n    float d = dot(p - points[0], p - points[0]);\n    float s = 1.0;\n    for (uint i = 0, j = count - 1; i < count; j = i, i++) {\n        float2 e = points[j] - points[i];\n        float2 w = p - points[i];\n        float2 b = w - e * clamp(dot(w, e) / dot(e, e), 0.0, 1.0);\n        d = min(d, dot(b, b));\n        bool above = p.y >= points[i].y;\n        bool below = p.y < points[j].y;\n        bool left = e.x * w.y > e.y * w.x;\n        if ((above && below && left) || (!above && !below && !left)) {\n            s = -s;\n        }\n    }\n    return s * sqrt(d);\n}\n\nfloat shape_sdf(float2 p, float2 size, constant Uniforms& uniforms, constant float2* points) {\n    float2 half_size = size * 0.5;\n    if (uniforms.shape == SHAPE_ELLIPSE) {\n        return ellipse_sdf(p, half_size);\n    }\n    if (uniforms.shape == SHAPE_POLYGON && uniforms.point_count >= 3) {\n        return polygon_sdf(p / size, points, uniforms.point_count) * min(size.x, size.y) - uniforms.corner_radius;\n    }\n    return rounded_rect_corners_sdf(p, half_size, uniforms.corner_radii);\n}\n\nbool has_shape(constant Uniforms& uniforms) {\n    return uniforms.shape != SHAPE_RECT || any(uniforms.corner_radii > 0.0) || uniforms.border_width > 0.0;\n}\n\n// Distance travelled along the outline up to the point closest to p, used to place dashes.\nfloat outline_position(float2 p, float2 size, constant Uniforms& uniforms, constant float2* points) {\n    float2 h = size * 0.5;\n    if (uniforms.shape == SHAPE_ELLIPSE) {\n        float perimeter = M_PI_F * (3.0 * (h.x + h.y) - sqrt((3.0 * h.x + h.y) * (h.x + 3.0 * h.y)));\n        float angle = atan2(p.y / h.y, p.x / h.x);\n        return (angle + M_PI_F) / (2.0 * M_PI_F) * perimeter;\n    }\n    if (uniforms.shape == SHAPE_POLYGON && uniforms.point_count >= 3) {\n        float best = 1e20;\n        float position = 0.0;\n        float travelled = 0.0;\n        for (uint i = 0; i < uniforms.point_count; i++) {\n            float2 a = points[i] * size;\n            float2 e = points[(i + 1) % uniforms.point_count] * size - a;\n            float t = clamp(dot(p - a, e) / max(dot(e, e), 1e-6), 0.0, 1.0);\n            float d = length(p - a - e * t);\n            if (d < best) {\n                best = d;\n                position = travelled + t * length(e);\n            }\n            travelled += length(e);\n        }\n        return position;\n    }\n    // Walk the rectangle clockwise from its top-left corner.\n    float2 edge = h - abs(p);\n    if (edge.y < edge.x) {\n        return p.y > 0.0 ? p.x + h.x : 3.0 * h.x + 2.0 * h.y - p.x;\n    }\n    return p.x > 0.0 ? 2.0 * h.x + h.y - p.y : 4.0 * h.x + 3.0 * h.y + p.y;\n}\n\nfloat dash_mask(float position, float2 dash) {\n    float period = dash.x + dash.y;\n    if (dash.x <= 0.0 || dash.y <= 0.0) {\n        return 1.0;\n    }\n    return fmod(position, period) < dash.x ? 1.0 : 0.0;\n}\n\nfloat calculate_shadow_expanded(float2 uv, float2 original_rect_size, constant Uniforms& uniforms, constant float2* points, float blur_radius) {\n    float expansion = blur_radius;\n    \n    float2 expanded_size = original_rect_size + float2(expansion * 2.0, expansion * 2.0);\n    \n    float2 expanded_pos = (uv - 0.5) * expanded_size;\n    \n    float dist = shape_sdf(expanded_pos, original_rect_size, uniforms, points);\n    \n    float shadow_alpha = 1.0 - smoothstep(-blur_radius, blur_radius, dist);\n    \n    return clamp(shadow_alpha, 0.0, 1.0);\n}\n\nfragment float4 fragment_main(VertexOut in [[stage_in]], \n                             constant Uniforms& uniforms [[buffer(0)]],\n                             texture2d<float> tex [[texture(0)]],\n                             sampler texSampler [[sampler(0)]],\n                             constant ShadowUniforms& shadowUniforms [[buffer(2)]],\n                             constant float2* shapePoints [[buffer(3)]]) {\n    \n    if (shadowUniforms.enabled) {\n        float shadow_alpha = calculate_shadow_expanded(\n            in.uv, \n            uniforms.rect_size, \n            uniforms, \n            shapePoints, \n            shadowUniforms.radius\n        );\n        \n        if (shadow_alpha <= 0.01) {\n            discard_fragment();\n        }\n        \n        float4 shadow_color = shadowUniforms.color;\n        shadow_color.a *= shadow_alpha;\n        return shadow_color;\n    }\n    \n    float4 final_color;\n    \n    if (uniforms.use_texture) {\n        float4 tex_color = tex.sample(texSampler, in.uv);\n        final_color = tex_color;\n    } else {\n        final_color = in.color;\n    }\n    \n    if (has_shape(uniforms)) {\n        float2 local_pos = (in.uv - 0.5) * uniforms.rect_size;\n        \n        float dist = shape_sdf(local_pos, uniforms.rect_size, uniforms, shapePoints);\n        \n        float alpha = 1.0 - smoothstep(-1.0, 1.0, dist);\n        \n        if (uniforms.border_width > 0.0) {\n            float outer = uniforms.border_outset;\n            float inner = outer - uniforms.border_width;\n            float band = smoothstep(-0.5, 0.5, dist - inner) * (1.0 - smoothstep(-0.5, 0.5, dist - outer));\n            band *= dash_mask(outline_position(local_pos, uniforms.rect_size, uniforms, shapePoints), uniforms.border_dash);\n            \n            float border_alpha = uniforms.border_color.a * band;\n            float fill_alpha = final_color.a * alpha;\n            float out_alpha = border_alpha + fill_alpha * (1.0 - border_alpha);\n            if (out_alpha <= 0.0) {\n                discard_fragment();\n            }\n            float3 rgb = (uniforms.border_color.rgb * border_alpha + final_color.rgb * fill_alpha * (1.0 - border_alpha)) / out_alpha;\n            return float4(rgb, out_alpha);\n        }\n        \n        if (alpha <= 0.0) {\n            discard_fragment();\n        }\n        final_color.a *= alpha;\n    }\n    \n    return final_color;\n}\n\n";
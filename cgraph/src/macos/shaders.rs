@@ -1,12 +1,11 @@
-use crate::macos::shader_code::SHADER_CODE;
+use crate::renderer::RenderError;
 use metal::*;
 
-/// Creates a Metal library from the shader source code.
-pub fn create_library(device: &Device) -> Library {
-    match device.new_library_with_source(SHADER_CODE, &CompileOptions::new()) {
-        Ok(library) => library,
-        Err(e) => panic!("Failed to create shader library: {e}"),
-    }
+/// Creates a Metal library from shader source code.
+pub fn create_library(device: &Device, source: &str) -> Result<Library, RenderError> {
+    device
+        .new_library_with_source(source, &CompileOptions::new())
+        .map_err(RenderError::Shader)
 }
 
 /// Sets up alpha blending for the given render pipeline descriptor.
@@ -80,6 +80,15 @@ impl Object {
             use_texture: false as u32, // Shadows don't use textures
             shadow_radius: 0.0,        // Not used for shadow objects
             shadow_color: Vec4::new(0.0, 0.0, 0.0, 0.0), // Not used for shadow objects
+            corner_radii: self.get_corner_radii(),
+            shape: self.shape.shader_id(),
+            point_count: self.get_shape_points().len() as u32,
+            border_width: 0.0,
+            border_outset: 0.0,
+            border_color: Vec4::new(0.0, 0.0, 0.0, 0.0),
+            border_dash: Vec2::new(0.0, 0.0),
+            vertex_outset: Vec2::new(0.0, 0.0),
+            uv_outset: Vec2::new(0.0, 0.0),
         };
 
         crate::object::buffer::Buffer::new(vec![uniforms])
@@ -140,22 +149,22 @@ impl Object {
     }
 
     /// Returns the shadow buffer, creating it if necessary.
-    pub fn get_shadow_buffer(&mut self) -> &crate::object::buffer::Buffer<Vertex> {
+    pub fn get_shadow_buffer(&mut self) -> &mut crate::object::buffer::Buffer<Vertex> {
         if self.shadow_buffer.is_none() || self.shadow_dirty {
             let (vertices, _) = self.create_shadow_geometry();
             self.shadow_buffer = Some(crate::object::buffer::Buffer::new(vertices));
             self.shadow_dirty = false;
         }
-        self.shadow_buffer.as_ref().unwrap()
+        self.shadow_buffer.as_mut().unwrap()
     }
 
     /// Returns the shadow index buffer, creating it if necessary.
-    pub fn get_shadow_index_buffer(&mut self) -> &crate::object::buffer::Buffer<u32> {
+    pub fn get_shadow_index_buffer(&mut self) -> &mut crate::object::buffer::Buffer<u32> {
         if self.shadow_index_buffer.is_none() {
             let (_, indices) = self.create_shadow_geometry();
             self.shadow_index_buffer = Some(crate::object::buffer::Buffer::new(indices));
         }
-        self.shadow_index_buffer.as_ref().unwrap()
+        self.shadow_index_buffer.as_mut().unwrap()
     }
 
     /// Creates the shadow position uniforms for rendering.
@@ -192,6 +201,15 @@ impl Object {
             use_texture: false as u32,
             shadow_radius: 0.0,
             shadow_color: Vec4::new(0.0, 0.0, 0.0, 0.0),
+            corner_radii: self.get_corner_radii(),
+            shape: self.shape.shader_id(),
+            point_count: self.get_shape_points().len() as u32,
+            border_width: 0.0,
+            border_outset: 0.0,
+            border_color: Vec4::new(0.0, 0.0, 0.0, 0.0),
+            border_dash: Vec2::new(0.0, 0.0),
+            vertex_outset: Vec2::new(0.0, 0.0),
+            uv_outset: Vec2::new(0.0, 0.0),
         };
 
         crate::object::buffer::Buffer::new(vec![uniforms])
@@ -1,6 +1,9 @@
 use glam::{Vec2, Vec4};
 
+use crate::app::CursorIcon;
 use crate::object::buffer::Buffer;
+use crate::object::material::Material;
+use crate::object::primitives::{BorderAlignment, BorderDash, CornerRadii, ShapeKind};
 
 #[cfg(target_os = "macos")]
 use crate::macos::image::Image;
@@ -75,6 +78,24 @@ pub struct Object {
     pub rotation: f32,
     /// The corner radius for rounded corners.
     pub corner_radius: f32,
+    /// Per-corner radii, overriding `corner_radius` for rectangles when set.
+    pub corner_radii: Option<CornerRadii>,
+    /// The outline the object is cut into when rendered.
+    pub shape: ShapeKind,
+    /// The width of the border, or zero for no border.
+    pub border_width: f32,
+    /// The color of the border.
+    pub border_color: Vec4,
+    /// Where the border is drawn relative to the edge.
+    pub border_alignment: BorderAlignment,
+    /// The dash pattern of the border, or `None` for a solid border.
+    pub border_dash: Option<BorderDash>,
+    /// The mouse cursor shown while the cursor is over the object, or `None` for the default.
+    pub cursor: Option<CursorIcon>,
+    /// The custom fragment shader the object is drawn with, or `None` for the default one.
+    pub material: Option<Material>,
+    /// A name to find the object again among the renderer's objects, or `None`.
+    pub tag: Option<&'static str>,
     /// The radius of the shadow.
     pub shadow_radius: f32,
     /// The color of the shadow.
@@ -110,6 +131,15 @@ impl Clone for Object {
             original_pixel_size: self.original_pixel_size,
             rotation: self.rotation,
             corner_radius: self.corner_radius,
+            corner_radii: self.corner_radii,
+            shape: self.shape.clone(),
+            border_width: self.border_width,
+            border_color: self.border_color,
+            border_alignment: self.border_alignment,
+            border_dash: self.border_dash,
+            cursor: self.cursor,
+            material: self.material.clone(),
+            tag: self.tag,
             shadow_radius: self.shadow_radius,
             shadow_color: self.shadow_color,
             shadow_offset: self.shadow_offset,
@@ -140,6 +170,66 @@ impl Object {
     pub fn get_index_buffer(&self) -> &Buffer<u32> {
         &self.index_buffer
     }
+
+    /// Returns the radius of each corner as top-left, top-right, bottom-right and bottom-left.
+    pub fn get_corner_radii(&self) -> Vec4 {
+        match self.corner_radii {
+            Some(radii) => Vec4::new(
+                radii.top_left,
+                radii.top_right,
+                radii.bottom_right,
+                radii.bottom_left,
+            ),
+            None => Vec4::new(
+                self.corner_radius,
+                self.corner_radius,
+                self.corner_radius,
+                self.corner_radius,
+            ),
+        }
+    }
+
+    /// Returns how far the quad grows past the edge of the object to fit its border, in pixels.
+    pub fn get_border_outset(&self) -> f32 {
+        if self.border_width <= 0.0 {
+            return 0.0;
+        }
+        // Leave one extra pixel for the antialiased outer edge.
+        self.border_alignment.outset(self.border_width) + 1.0
+    }
+
+    /// Returns how far each quad corner moves outwards to fit the border, in vertex units.
+    pub fn get_vertex_outset(&self) -> Vec2 {
+        let outset = self.get_border_outset();
+        let rect_size = self.original_pixel_size * self.scale;
+        if outset == 0.0 || self.vertices.is_empty() || rect_size.x == 0.0 || rect_size.y == 0.0 {
+            return Vec2::new(0.0, 0.0);
+        }
+
+        let mut min = self.vertices[0].position;
+        let mut max = self.vertices[0].position;
+        for vertex in &self.vertices {
+            min = min.min(vertex.position);
+            max = max.max(vertex.position);
+        }
+        let extent = max - min;
+        Vec2::new(
+            outset * extent.x / rect_size.x,
+            outset * extent.y / rect_size.y,
+        )
+    }
+
+    /// Returns the polygon points centered on the quad, with y pointing up as in its UV space.
+    pub fn get_shape_points(&self) -> Vec<Vec2> {
+        match &self.shape {
+            ShapeKind::Polygon(points) if !points.is_empty() => points
+                .iter()
+                .map(|p| Vec2::new(p.x - 0.5, 0.5 - p.y))
+                .collect(),
+            // Metal needs a non-empty buffer even when the shape has no points.
+            _ => vec![Vec2::new(0.0, 0.0)],
+        }
+    }
 }
 
 /// Buffer module for managing vertex and index data.
@@ -148,5 +238,7 @@ pub mod buffer;
 pub mod curve;
 /// Primitives module for creating advanced shapes.
 pub mod curve_primitives;
+/// Custom fragment shaders for objects.
+pub mod material;
 /// Primitives module for creating common shapes.
 pub mod primitives;
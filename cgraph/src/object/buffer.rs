@@ -1,35 +1,52 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Returns how many GPU buffers were allocated since the program started.
+pub fn allocation_count() -> u64 {
+    ALLOCATIONS.load(Ordering::Relaxed)
+}
+
 #[cfg(target_os = "macos")]
 #[derive(Debug)]
 /// Represents a buffer for storing vertex data in Metal.
+///
+/// The data is uploaded to the device of the renderer that first draws it.
 pub struct Buffer<T> {
     /// The data stored in the buffer.
     pub data: Vec<T>,
-    /// The Metal buffer object.
-    pub buffer: metal::Buffer,
+    // The Metal buffer holding the data, or `None` until it is uploaded.
+    buffer: Option<metal::Buffer>,
 }
 
 #[cfg(target_os = "macos")]
 impl<T> Buffer<T> {
     /// Creates a new buffer with the given data.
     pub fn new(data: Vec<T>) -> Self {
-        let device = metal::Device::system_default().expect("No Metal device found");
-        let buffer = device.new_buffer_with_data(
-            data.as_ptr() as *const std::ffi::c_void,
-            (data.len() * std::mem::size_of::<T>()) as u64,
-            metal::MTLResourceOptions::CPUCacheModeDefaultCache,
-        );
-        Buffer { data, buffer }
+        Buffer { data, buffer: None }
     }
 
     /// Updates the buffer with new data.
     pub fn update(&mut self, data: Vec<T>) {
         self.data = data;
-        let device = metal::Device::system_default().expect("No Metal device found");
-        self.buffer = device.new_buffer_with_data(
-            self.data.as_ptr() as *const std::ffi::c_void,
-            (self.data.len() * std::mem::size_of::<T>()) as u64,
-            metal::MTLResourceOptions::CPUCacheModeDefaultCache,
-        );
+        self.buffer = None;
+    }
+
+    /// Returns the Metal buffer holding the data, uploading it to `device` if needed.
+    pub fn upload(&mut self, device: &metal::DeviceRef) -> &metal::Buffer {
+        self.buffer.get_or_insert_with(|| {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            device.new_buffer_with_data(
+                self.data.as_ptr() as *const std::ffi::c_void,
+                std::mem::size_of_val(self.data.as_slice()) as u64,
+                metal::MTLResourceOptions::CPUCacheModeDefaultCache,
+            )
+        })
+    }
+
+    /// Drops the uploaded copy of the data, so it is uploaded again the next time it is drawn.
+    pub fn release(&mut self) {
+        self.buffer = None;
     }
 }
 
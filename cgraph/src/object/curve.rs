@@ -1,6 +1,19 @@
 use crate::object::{Object, Vertex};
 use glam::{Vec2, Vec4};
 
+/// Boolean operations between closed paths.
+mod boolean;
+/// Filling of closed paths.
+mod fill;
+/// SVG path-data parsing and serialization.
+mod svg;
+/// Affine transforms and reversal of curves and paths.
+mod transform;
+
+pub use boolean::BooleanOp;
+pub use fill::FillRule;
+pub use svg::SvgPathError;
+
 #[derive(Clone, Debug, Copy)]
 /// Represents a point in 2D space.
 pub struct Point {
@@ -103,7 +116,7 @@ impl CurveEval for Curve {
                 }
 
                 let t_scaled = t * (points.len() - 3) as f32;
-                let i = t_scaled.floor() as usize;
+                let i = (t_scaled.floor() as usize).min(points.len() - 4);
                 let t = t_scaled - i as f32;
 
                 let p0 = points[i];
@@ -134,6 +147,198 @@ impl CurveEval for Curve {
     }
 }
 
+/// The default maximum distance, in pixels, between a flattened curve and the real curve.
+pub const DEFAULT_TOLERANCE: f32 = 0.25;
+
+/// The maximum number of segments a single curve is flattened into.
+const MAX_SEGMENTS: usize = 1024;
+
+#[derive(Clone, Debug, Copy)]
+/// Controls how curves are flattened into line segments.
+pub struct Flattening {
+    /// The maximum distance, in pixels, between the flattened curve and the real curve.
+    pub tolerance: f32,
+    /// The scale the curve will be drawn at, so zoomed-in curves get more segments.
+    pub scale: f32,
+    /// A fixed number of steps that overrides the adaptive subdivision.
+    pub steps: Option<usize>,
+}
+
+impl Default for Flattening {
+    fn default() -> Self {
+        Flattening {
+            tolerance: DEFAULT_TOLERANCE,
+            scale: 1.0,
+            steps: None,
+        }
+    }
+}
+
+impl Flattening {
+    /// Creates an adaptive flattening with the given tolerance in pixels.
+    pub fn new(tolerance: f32) -> Self {
+        Flattening {
+            tolerance,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a flattening that always uses a fixed number of steps.
+    pub fn fixed(steps: usize) -> Self {
+        Flattening {
+            steps: Some(steps),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the scale the curve will be drawn at.
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Multiplies the scale by the largest axis of an object's transform scale.
+    pub fn with_transform_scale(mut self, scale: Vec2) -> Self {
+        self.scale *= scale.x.abs().max(scale.y.abs());
+        self
+    }
+
+    /// Sets an optional fixed number of steps that overrides the tolerance.
+    pub fn with_steps(mut self, steps: Option<usize>) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    /// Returns the tolerance in curve units, taking the scale into account.
+    pub fn effective_tolerance(&self) -> f32 {
+        let scale = self.scale.abs();
+        let tolerance = if self.tolerance > 0.0 {
+            self.tolerance
+        } else {
+            DEFAULT_TOLERANCE
+        };
+        if scale > 0.0 {
+            tolerance / scale
+        } else {
+            tolerance
+        }
+    }
+}
+
+impl From<usize> for Flattening {
+    fn from(steps: usize) -> Self {
+        Flattening::fixed(steps)
+    }
+}
+
+impl From<Option<usize>> for Flattening {
+    fn from(steps: Option<usize>) -> Self {
+        Flattening::default().with_steps(steps)
+    }
+}
+
+/// Returns the length of the second difference `a - 2b + c`.
+fn second_difference(a: Point, b: Point, c: Point) -> f32 {
+    let x = a.x - 2.0 * b.x + c.x;
+    let y = a.y - 2.0 * b.y + c.y;
+    (x * x + y * y).sqrt()
+}
+
+/// Returns the number of uniform segments needed to keep a cubic within the tolerance.
+fn cubic_segments(p0: Point, p1: Point, p2: Point, p3: Point, tolerance: f32) -> usize {
+    let dd = second_difference(p0, p1, p2).max(second_difference(p1, p2, p3));
+    (0.75 * dd / tolerance).sqrt().ceil() as usize
+}
+
+/// Creates a straight line as a quadratic curve, the same way `PathBuilder::line_to` does.
+pub(crate) fn line(start: Point, end: Point) -> Curve {
+    Curve::Quadratic {
+        p0: start,
+        p1: Point {
+            x: (start.x + end.x) / 2.0,
+            y: (start.y + end.y) / 2.0,
+        },
+        p2: end,
+    }
+}
+
+/// Converts each span of a Catmull-Rom spline into the control points of an equivalent cubic Bézier.
+pub(crate) fn catmull_rom_to_cubics(points: &[Point], tension: f32) -> Vec<[Point; 4]> {
+    points
+        .windows(4)
+        .map(|w| {
+            let b1 = Point {
+                x: w[1].x + tension * (w[2].x - w[0].x) / 3.0,
+                y: w[1].y + tension * (w[2].y - w[0].y) / 3.0,
+            };
+            let b2 = Point {
+                x: w[2].x - tension * (w[3].x - w[1].x) / 3.0,
+                y: w[2].y - tension * (w[3].y - w[1].y) / 3.0,
+            };
+            [w[1], b1, b2, w[2]]
+        })
+        .collect()
+}
+
+impl Curve {
+    /// Returns the number of segments needed to flatten the curve within the given tolerance.
+    pub fn segment_count(&self, tolerance: f32) -> usize {
+        let count = match self {
+            Curve::Quadratic { p0, p1, p2 } => (second_difference(*p0, *p1, *p2)
+                / (4.0 * tolerance))
+                .sqrt()
+                .ceil() as usize,
+            Curve::Cubic { p0, p1, p2, p3 } => cubic_segments(*p0, *p1, *p2, *p3, tolerance),
+            Curve::Arc {
+                radius,
+                start_angle,
+                end_angle,
+                ..
+            } => {
+                let radius = radius.abs();
+                if radius <= tolerance {
+                    1
+                } else {
+                    let step = 2.0 * (1.0 - tolerance / radius).acos();
+                    ((end_angle - start_angle).abs() / step).ceil() as usize
+                }
+            }
+            Curve::CatmullRom { points, tension } => {
+                let spans = catmull_rom_to_cubics(points, *tension);
+                if spans.is_empty() {
+                    return 1;
+                }
+                let per_span = spans
+                    .iter()
+                    .map(|[p0, p1, p2, p3]| cubic_segments(*p0, *p1, *p2, *p3, tolerance))
+                    .max()
+                    .unwrap_or(1)
+                    .max(1);
+                spans.len() * per_span
+            }
+        };
+        count.clamp(1, MAX_SEGMENTS)
+    }
+
+    /// Returns the point where the curve starts.
+    pub fn start_point(&self) -> Point {
+        self.evaluate(0.0)
+    }
+
+    /// Returns the point where the curve ends.
+    pub fn end_point(&self) -> Point {
+        self.evaluate(1.0)
+    }
+
+    /// Flattens the curve into points, using the fixed steps if set or the tolerance otherwise.
+    pub fn flatten(&self, flattening: &Flattening) -> Vec<Point> {
+        let steps = flattening
+            .steps
+            .unwrap_or_else(|| self.segment_count(flattening.effective_tolerance()));
+        self.points(steps.max(1))
+    }
+}
+
 /// Represents a path made up of multiple curves.
 pub struct Path {
     /// The curves that make up the path.
@@ -145,30 +350,38 @@ impl Path {
     pub fn points(&self, steps: usize) -> Vec<Point> {
         self.curves.iter().flat_map(|c| c.points(steps)).collect()
     }
+
+    /// Flattens every curve of the path into points.
+    pub fn flatten(&self, flattening: &Flattening) -> Vec<Point> {
+        self.curves
+            .iter()
+            .flat_map(|c| c.flatten(flattening))
+            .collect()
+    }
 }
 
 impl Curve {
     /// Converts the curve to a vector of vertices for rendering.
     pub fn to_vertices(
         &self,
-        steps: usize,
+        flattening: Flattening,
         color: Vec4,
         z_index: f32,
         line_width: f32,
     ) -> Vec<Vertex> {
-        let points = self.points(steps);
+        let points = self.flatten(&flattening);
         self.points_to_vertices(points, color, z_index, line_width)
     }
 
     /// Converts the curve to a vector of vertices and indices for rendering as a line strip.
     pub fn to_line_vertices(
         &self,
-        steps: usize,
+        flattening: Flattening,
         color: Vec4,
         z_index: f32,
         line_width: f32,
     ) -> (Vec<Vertex>, Vec<u32>) {
-        let points = self.points(steps);
+        let points = self.flatten(&flattening);
         self.points_to_line_strip(points, color, z_index, line_width)
     }
 
@@ -309,21 +522,21 @@ impl Path {
     /// Converts the path to a vector of vertices for rendering.
     pub fn to_vertices(
         &self,
-        steps: usize,
+        flattening: Flattening,
         color: Vec4,
         z_index: f32,
         line_width: f32,
     ) -> Vec<Vertex> {
         self.curves
             .iter()
-            .flat_map(|curve| curve.to_vertices(steps, color, z_index, line_width))
+            .flat_map(|curve| curve.to_vertices(flattening, color, z_index, line_width))
             .collect()
     }
 
     /// Converts the path to a vector of vertices and indices for rendering as a line strip.
     pub fn to_line_vertices(
         &self,
-        steps: usize,
+        flattening: Flattening,
         color: Vec4,
         z_index: f32,
         line_width: f32,
@@ -333,7 +546,7 @@ impl Path {
 
         for curve in &self.curves {
             let (mut vertices, mut indices) =
-                curve.to_line_vertices(steps, color, z_index, line_width);
+                curve.to_line_vertices(flattening, color, z_index, line_width);
 
             let vertex_offset = all_vertices.len() as u32;
             for index in &mut indices {
@@ -348,8 +561,14 @@ impl Path {
     }
 
     /// Converts the path to an `Object` for rendering.
-    pub fn to_object(&self, steps: usize, color: Vec4, z_index: f32, line_width: f32) -> Object {
-        let (vertices, indices) = self.to_line_vertices(steps, color, z_index, line_width);
+    pub fn to_object(
+        &self,
+        flattening: Flattening,
+        color: Vec4,
+        z_index: f32,
+        line_width: f32,
+    ) -> Object {
+        let (vertices, indices) = self.to_line_vertices(flattening, color, z_index, line_width);
         let mut object = Object::new(vertices, indices);
         object.position = Vec2::new(0.0, 0.0);
         object.scale = Vec2::new(1.0, 1.0);
@@ -358,4 +577,22 @@ impl Path {
         object.update_buffer();
         object
     }
+
+    /// Replaces the geometry of an object with the path, flattened at the object's scale.
+    /// Call it again after changing the scale so the curves stay within the tolerance.
+    pub fn update_object(
+        &self,
+        object: &mut Object,
+        flattening: Flattening,
+        color: Vec4,
+        z_index: f32,
+        line_width: f32,
+    ) {
+        let flattening = flattening.with_transform_scale(object.scale);
+        let (vertices, indices) = self.to_line_vertices(flattening, color, z_index, line_width);
+        object.vertices = vertices;
+        object.indices = indices;
+        object.shadow_dirty = true;
+        object.update_buffer();
+    }
 }
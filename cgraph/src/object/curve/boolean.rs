@@ -0,0 +1,330 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::object::curve::{FillRule, Flattening, Path, Point, fill::winding_number, line};
+
+/// Parametric tolerance used when splitting edges at intersections.
+const EPSILON: f64 = 1e-9;
+
+/// Grid used to merge vertices that only differ by rounding errors.
+const SNAP: f64 = 1e-6;
+
+/// A vertex position rounded to the snapping grid.
+type Key = (i64, i64);
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+/// A boolean operation between two closed paths.
+pub enum BooleanOp {
+    /// Keeps the area covered by either path.
+    Union,
+    /// Keeps the area covered by both paths.
+    Intersection,
+    /// Keeps the area covered by the first path but not by the second.
+    Difference,
+    /// Keeps the area covered by exactly one of the paths.
+    Xor,
+}
+
+impl BooleanOp {
+    fn apply(&self, a: bool, b: bool) -> bool {
+        match self {
+            BooleanOp::Union => a || b,
+            BooleanOp::Intersection => a && b,
+            BooleanOp::Difference => a && !b,
+            BooleanOp::Xor => a != b,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct P {
+    x: f64,
+    y: f64,
+}
+
+impl P {
+    fn sub(self, other: P) -> P {
+        P {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+
+    fn cross(self, other: P) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    fn dot(self, other: P) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    fn lerp(self, other: P, t: f64) -> P {
+        P {
+            x: self.x + (other.x - self.x) * t,
+            y: self.y + (other.y - self.y) * t,
+        }
+    }
+
+    fn point(self) -> Point {
+        Point {
+            x: self.x as f32,
+            y: self.y as f32,
+        }
+    }
+
+    fn key(self) -> Key {
+        (
+            (self.x / SNAP).round() as i64,
+            (self.y / SNAP).round() as i64,
+        )
+    }
+}
+
+fn to_p(contours: &[Vec<Point>]) -> Vec<Vec<P>> {
+    contours
+        .iter()
+        .map(|contour| {
+            contour
+                .iter()
+                .map(|p| P {
+                    x: p.x as f64,
+                    y: p.y as f64,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Collects the points where each edge must be split so no two edges cross or overlap.
+fn split_points(edges: &[(P, P)]) -> Vec<Vec<(f64, P)>> {
+    let mut splits: Vec<Vec<(f64, P)>> = vec![Vec::new(); edges.len()];
+
+    for i in 0..edges.len() {
+        let (a, b) = edges[i];
+        let r = b.sub(a);
+        let r_len2 = r.dot(r);
+        for j in (i + 1)..edges.len() {
+            let (c, d) = edges[j];
+            let s = d.sub(c);
+            let s_len2 = s.dot(s);
+            let denominator = r.cross(s);
+
+            if denominator.abs() > EPSILON * (r_len2 * s_len2).sqrt() {
+                let t = c.sub(a).cross(s) / denominator;
+                let u = c.sub(a).cross(r) / denominator;
+                if (-EPSILON..=1.0 + EPSILON).contains(&t)
+                    && (-EPSILON..=1.0 + EPSILON).contains(&u)
+                {
+                    // Compute the point once so both edges are split at exactly the same place.
+                    let point = a.lerp(b, t);
+                    splits[i].push((t, point));
+                    splits[j].push((u, point));
+                }
+            } else if c.sub(a).cross(r).abs() <= EPSILON * r_len2.sqrt().max(1.0) {
+                // Collinear edges: split each one at the other's endpoints that fall inside it.
+                for point in [c, d] {
+                    let t = point.sub(a).dot(r) / r_len2;
+                    splits[i].push((t, point));
+                }
+                for point in [a, b] {
+                    let u = point.sub(c).dot(s) / s_len2;
+                    splits[j].push((u, point));
+                }
+            }
+        }
+    }
+
+    splits
+}
+
+/// Removes vertices that lie on the straight line between their neighbours.
+fn remove_collinear(points: Vec<P>) -> Vec<P> {
+    let mut points = points;
+    let mut changed = true;
+    while changed && points.len() > 3 {
+        changed = false;
+        let mut i = 0;
+        while i < points.len() && points.len() > 3 {
+            let prev = points[(i + points.len() - 1) % points.len()];
+            let next = points[(i + 1) % points.len()];
+            let incoming = points[i].sub(prev);
+            let outgoing = next.sub(points[i]);
+            let scale = (incoming.dot(incoming) * outgoing.dot(outgoing)).sqrt();
+            if incoming.cross(outgoing).abs() <= EPSILON * scale.max(EPSILON)
+                && incoming.dot(outgoing) > 0.0
+            {
+                points.remove(i);
+                changed = true;
+            } else {
+                i += 1;
+            }
+        }
+    }
+    points
+}
+
+impl Path {
+    /// Combines two closed paths with a boolean operation.
+    ///
+    /// Curves are flattened first, so the result is made of straight lines. Every contour of the
+    /// result keeps the filled area on the same side, so it can be filled with either fill rule.
+    pub fn boolean(
+        &self,
+        other: &Path,
+        op: BooleanOp,
+        fill_rule: FillRule,
+        flattening: Flattening,
+    ) -> Path {
+        let a = self.contours(&flattening);
+        let b = other.contours(&flattening);
+
+        let edges: Vec<(P, P)> = to_p(&a)
+            .iter()
+            .chain(to_p(&b).iter())
+            .flat_map(|contour| {
+                (0..contour.len()).map(move |i| (contour[i], contour[(i + 1) % contour.len()]))
+            })
+            .filter(|(start, end)| start.key() != end.key())
+            .collect();
+
+        let splits = split_points(&edges);
+
+        // Break every edge into pieces that only touch other pieces at their endpoints.
+        // The pieces are ordered by position so the result doesn't depend on hashing.
+        let mut pieces: BTreeMap<(Key, Key), (P, P)> = BTreeMap::new();
+        for (edge, mut points) in edges.iter().zip(splits) {
+            points.retain(|(t, _)| *t > EPSILON && *t < 1.0 - EPSILON);
+            points.sort_by(|x, y| x.0.total_cmp(&y.0));
+            let mut previous = edge.0;
+            for point in points.into_iter().map(|(_, p)| p).chain([edge.1]) {
+                let (start, end) = (previous.key(), point.key());
+                if start != end {
+                    let undirected = if start < end {
+                        (start, end)
+                    } else {
+                        (end, start)
+                    };
+                    pieces.entry(undirected).or_insert((previous, point));
+                }
+                previous = point;
+            }
+        }
+
+        // Keep the pieces that separate the inside of the result from its outside.
+        let inside = |point: P| {
+            op.apply(
+                fill_rule.is_inside(winding_number(&a, point.point())),
+                fill_rule.is_inside(winding_number(&b, point.point())),
+            )
+        };
+        let mut boundary: Vec<(P, P)> = Vec::new();
+        for (start, end) in pieces.into_values() {
+            let direction = end.sub(start);
+            let length = direction.dot(direction).sqrt();
+            let offset = (length * 1e-3).min(1e-3);
+            let normal = P {
+                x: -direction.y / length * offset,
+                y: direction.x / length * offset,
+            };
+            let middle = start.lerp(end, 0.5);
+            let left = inside(P {
+                x: middle.x + normal.x,
+                y: middle.y + normal.y,
+            });
+            let right = inside(P {
+                x: middle.x - normal.x,
+                y: middle.y - normal.y,
+            });
+            if left && !right {
+                boundary.push((start, end));
+            } else if right && !left {
+                boundary.push((end, start));
+            }
+        }
+
+        // Chain the boundary pieces into closed contours.
+        let mut outgoing: HashMap<Key, Vec<usize>> = HashMap::new();
+        for (index, (start, _)) in boundary.iter().enumerate() {
+            outgoing.entry(start.key()).or_default().push(index);
+        }
+        let mut used = vec![false; boundary.len()];
+        let mut curves = Vec::new();
+        for first in 0..boundary.len() {
+            if used[first] {
+                continue;
+            }
+            let start_key = boundary[first].0.key();
+            let mut contour = Vec::new();
+            let mut current = first;
+            let closed = loop {
+                used[current] = true;
+                contour.push(boundary[current].0);
+                let end_key = boundary[current].1.key();
+                if end_key == start_key {
+                    break true;
+                }
+                let next = outgoing
+                    .get(&end_key)
+                    .and_then(|candidates| candidates.iter().copied().find(|&i| !used[i]));
+                match next {
+                    Some(next) => current = next,
+                    None => break false,
+                }
+            };
+
+            // A chain that doesn't get back to its start would need a made-up closing edge.
+            if !closed {
+                continue;
+            }
+            let contour = remove_collinear(contour);
+            if contour.len() < 3 {
+                continue;
+            }
+            for i in 0..contour.len() {
+                let end = contour[(i + 1) % contour.len()];
+                curves.push(line(contour[i].point(), end.point()));
+            }
+        }
+
+        Path { curves }
+    }
+
+    /// Returns the area covered by either path.
+    pub fn union(&self, other: &Path) -> Path {
+        self.boolean(
+            other,
+            BooleanOp::Union,
+            FillRule::NonZero,
+            Flattening::default(),
+        )
+    }
+
+    /// Returns the area covered by both paths.
+    pub fn intersection(&self, other: &Path) -> Path {
+        self.boolean(
+            other,
+            BooleanOp::Intersection,
+            FillRule::NonZero,
+            Flattening::default(),
+        )
+    }
+
+    /// Returns the area covered by this path but not by the other one.
+    pub fn difference(&self, other: &Path) -> Path {
+        self.boolean(
+            other,
+            BooleanOp::Difference,
+            FillRule::NonZero,
+            Flattening::default(),
+        )
+    }
+
+    /// Returns the area covered by exactly one of the paths.
+    pub fn xor(&self, other: &Path) -> Path {
+        self.boolean(
+            other,
+            BooleanOp::Xor,
+            FillRule::NonZero,
+            Flattening::default(),
+        )
+    }
+}
@@ -0,0 +1,167 @@
+use glam::{Vec2, Vec4};
+use lyon::path::Path as LyonPath;
+use lyon::tessellation::{BuffersBuilder, FillOptions, FillTessellator, FillVertex, VertexBuffers};
+
+use crate::object::curve::{Flattening, Path, Point};
+use crate::object::{Object, Vertex};
+
+/// Distance under which two consecutive points of a contour are merged.
+const EPSILON: f32 = 1e-4;
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+/// Decides which areas enclosed by a path are considered inside it.
+pub enum FillRule {
+    /// A point is inside when the contours wind around it a non-zero number of times.
+    #[default]
+    NonZero,
+    /// A point is inside when a ray from it crosses the contours an odd number of times.
+    EvenOdd,
+}
+
+impl FillRule {
+    /// Returns whether a winding number counts as inside for this rule.
+    pub fn is_inside(&self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+/// Returns the winding number of the closed contours around a point.
+pub(crate) fn winding_number(contours: &[Vec<Point>], point: Point) -> i32 {
+    let mut winding = 0;
+    for contour in contours {
+        for (i, a) in contour.iter().enumerate() {
+            let b = contour[(i + 1) % contour.len()];
+            let side = (b.x - a.x) * (point.y - a.y) - (point.x - a.x) * (b.y - a.y);
+            if a.y <= point.y {
+                if b.y > point.y && side > 0.0 {
+                    winding += 1;
+                }
+            } else if b.y <= point.y && side < 0.0 {
+                winding -= 1;
+            }
+        }
+    }
+    winding
+}
+
+impl Path {
+    /// Flattens the path into closed contours, starting a new contour wherever curves are disconnected.
+    pub fn contours(&self, flattening: &Flattening) -> Vec<Vec<Point>> {
+        let mut contours: Vec<Vec<Point>> = Vec::new();
+        let mut current: Vec<Point> = Vec::new();
+
+        for curve in &self.curves {
+            let points = curve.flatten(flattening);
+            let Some(first) = points.first() else {
+                continue;
+            };
+            let connected = current.last().is_some_and(|last| {
+                (last.x - first.x).abs() <= EPSILON && (last.y - first.y).abs() <= EPSILON
+            });
+            if !connected && !current.is_empty() {
+                contours.push(std::mem::take(&mut current));
+            }
+            for point in points {
+                let duplicate = current.last().is_some_and(|last| {
+                    (last.x - point.x).abs() <= EPSILON && (last.y - point.y).abs() <= EPSILON
+                });
+                if !duplicate {
+                    current.push(point);
+                }
+            }
+        }
+        if !current.is_empty() {
+            contours.push(current);
+        }
+
+        for contour in &mut contours {
+            if contour.len() > 1 {
+                let first = contour[0];
+                let last = contour[contour.len() - 1];
+                if (last.x - first.x).abs() <= EPSILON && (last.y - first.y).abs() <= EPSILON {
+                    contour.pop();
+                }
+            }
+        }
+        contours.retain(|contour| contour.len() >= 3);
+        contours
+    }
+
+    /// Returns whether a point lies inside the closed path.
+    pub fn contains(&self, point: Point, fill_rule: FillRule, flattening: &Flattening) -> bool {
+        fill_rule.is_inside(winding_number(&self.contours(flattening), point))
+    }
+
+    /// Converts the path to a filled `Object` for rendering, treating every contour as closed.
+    pub fn to_filled_object(
+        &self,
+        flattening: Flattening,
+        color: Vec4,
+        z_index: f32,
+        fill_rule: FillRule,
+    ) -> Object {
+        let contours = self.contours(&flattening);
+
+        let mut builder = LyonPath::builder();
+        let mut min = Vec2::new(f32::INFINITY, f32::INFINITY);
+        let mut max = Vec2::new(f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for contour in &contours {
+            builder.begin(lyon::math::point(contour[0].x, contour[0].y));
+            for point in &contour[1..] {
+                builder.line_to(lyon::math::point(point.x, point.y));
+            }
+            builder.end(true);
+            for point in contour {
+                min = min.min(Vec2::new(point.x, point.y));
+                max = max.max(Vec2::new(point.x, point.y));
+            }
+        }
+        let path = builder.build();
+
+        let options = FillOptions::default().with_fill_rule(match fill_rule {
+            FillRule::NonZero => lyon::tessellation::FillRule::NonZero,
+            FillRule::EvenOdd => lyon::tessellation::FillRule::EvenOdd,
+        });
+
+        let size = max - min;
+        let mut geometry: VertexBuffers<Vertex, u32> = VertexBuffers::new();
+        let result = FillTessellator::new().tessellate_path(
+            &path,
+            &options,
+            &mut BuffersBuilder::new(&mut geometry, |v: FillVertex| {
+                let position = v.position();
+                let uv = Vec2::new(
+                    if size.x > 0.0 {
+                        (position.x - min.x) / size.x
+                    } else {
+                        0.0
+                    },
+                    if size.y > 0.0 {
+                        (position.y - min.y) / size.y
+                    } else {
+                        0.0
+                    },
+                );
+                Vertex::new(position.x, position.y, z_index, color, uv)
+            }),
+        );
+        if let Err(e) = result {
+            eprintln!("Failed to tessellate path: {e:?}");
+            geometry = VertexBuffers::new();
+        }
+
+        let mut object = Object::new(geometry.vertices, geometry.indices);
+        object.position = Vec2::new(0.0, 0.0);
+        object.scale = Vec2::new(1.0, 1.0);
+        if !contours.is_empty() {
+            object.original_pixel_size = size;
+        }
+        object.rotation = 0.0;
+        object.corner_radius = 0.0;
+        object.update_buffer();
+        object
+    }
+}
@@ -0,0 +1,545 @@
+use std::f32::consts::{PI, TAU};
+use std::fmt::Write;
+
+use thiserror::Error;
+
+use crate::object::curve::{
+    Curve, Path, Point, catmull_rom_to_cubics, line, transform::arc_to_cubics,
+};
+
+/// Errors returned when parsing SVG path data.
+///
+/// Each error has the byte offset of the unexpected token and the token itself, or `None` at the
+/// end of the data.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SvgPathError {
+    /// A coordinate or another number was expected.
+    #[error("expected a number at {offset}, found {}", describe(*found))]
+    ExpectedNumber {
+        /// The byte offset of the unexpected token.
+        offset: usize,
+        /// The unexpected token.
+        found: Option<char>,
+    },
+    /// An arc flag, `0` or `1`, was expected.
+    #[error("expected an arc flag at {offset}, found {}", describe(*found))]
+    ExpectedFlag {
+        /// The byte offset of the unexpected token.
+        offset: usize,
+        /// The unexpected token.
+        found: Option<char>,
+    },
+    /// A command letter was expected, such as at the start of the data or after `Z`.
+    #[error("expected a command at {offset}, found {}", describe(*found))]
+    ExpectedCommand {
+        /// The byte offset of the unexpected token.
+        offset: usize,
+        /// The unexpected token.
+        found: Option<char>,
+    },
+    /// The command letter isn't an SVG path command.
+    #[error("unsupported command `{command}` at {offset}")]
+    UnsupportedCommand {
+        /// The byte offset of the command.
+        offset: usize,
+        /// The command letter.
+        command: char,
+    },
+}
+
+fn describe(found: Option<char>) -> String {
+    match found {
+        Some(c) => format!("`{c}`"),
+        None => "the end of the data".to_string(),
+    }
+}
+
+/// Distance under which two points are considered the same.
+const EPSILON: f32 = 1e-4;
+
+fn same_point(a: Point, b: Point) -> bool {
+    (a.x - b.x).abs() <= EPSILON && (a.y - b.y).abs() <= EPSILON
+}
+
+fn midpoint(a: Point, b: Point) -> Point {
+    Point {
+        x: (a.x + b.x) / 2.0,
+        y: (a.y + b.y) / 2.0,
+    }
+}
+
+/// Reflects `control` around `origin`, as used by the `S` and `T` commands.
+fn reflect(control: Point, origin: Point) -> Point {
+    Point {
+        x: 2.0 * origin.x - control.x,
+        y: 2.0 * origin.y - control.y,
+    }
+}
+
+/// Tokenizer over the characters of an SVG `d` attribute.
+struct Parser<'a> {
+    data: &'a str,
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(data: &'a str) -> Self {
+        Parser {
+            data,
+            bytes: data.as_bytes(),
+            position: 0,
+        }
+    }
+
+    /// Returns the offset and the character of the next token, for errors.
+    fn found(&self) -> (usize, Option<char>) {
+        (self.position, self.data[self.position..].chars().next())
+    }
+
+    fn expected_number(&self) -> SvgPathError {
+        let (offset, found) = self.found();
+        SvgPathError::ExpectedNumber { offset, found }
+    }
+
+    fn skip_separators(&mut self) {
+        while let Some(&c) = self.bytes.get(self.position) {
+            if c.is_ascii_whitespace() || c == b',' {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_separators();
+        self.position >= self.bytes.len()
+    }
+
+    fn next_command(&mut self) -> Result<Option<u8>, SvgPathError> {
+        self.skip_separators();
+        match self.bytes.get(self.position) {
+            Some(&c) if b"MmLlHhVvCcSsQqTtAaZz".contains(&c) => {
+                self.position += 1;
+                Ok(Some(c))
+            }
+            Some(&c) if c.is_ascii_alphabetic() && c != b'e' && c != b'E' => {
+                Err(SvgPathError::UnsupportedCommand {
+                    offset: self.position,
+                    command: c as char,
+                })
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns true when the next token is a number rather than a command.
+    fn has_number(&mut self) -> bool {
+        self.skip_separators();
+        matches!(
+            self.bytes.get(self.position),
+            Some(c) if c.is_ascii_digit() || matches!(c, b'-' | b'+' | b'.')
+        )
+    }
+
+    fn number(&mut self) -> Result<f32, SvgPathError> {
+        self.skip_separators();
+        let start = self.position;
+        let mut end = start;
+        if matches!(self.bytes.get(end), Some(b'-' | b'+')) {
+            end += 1;
+        }
+        let mut seen_dot = false;
+        let mut seen_digit = false;
+        while let Some(&c) = self.bytes.get(end) {
+            if c.is_ascii_digit() {
+                seen_digit = true;
+            } else if c == b'.' && !seen_dot {
+                seen_dot = true;
+            } else {
+                break;
+            }
+            end += 1;
+        }
+        if !seen_digit {
+            return Err(self.expected_number());
+        }
+        if matches!(self.bytes.get(end), Some(b'e' | b'E')) {
+            let mut exponent = end + 1;
+            if matches!(self.bytes.get(exponent), Some(b'-' | b'+')) {
+                exponent += 1;
+            }
+            if matches!(self.bytes.get(exponent), Some(c) if c.is_ascii_digit()) {
+                while matches!(self.bytes.get(exponent), Some(c) if c.is_ascii_digit()) {
+                    exponent += 1;
+                }
+                end = exponent;
+            }
+        }
+        let number = self.data[start..end]
+            .parse::<f32>()
+            .map_err(|_| self.expected_number())?;
+        self.position = end;
+        Ok(number)
+    }
+
+    /// Parses an arc flag, which may be written without a separator (`a1 1 0 00 10 10`).
+    fn flag(&mut self) -> Result<bool, SvgPathError> {
+        self.skip_separators();
+        match self.bytes.get(self.position) {
+            Some(b'0') => {
+                self.position += 1;
+                Ok(false)
+            }
+            Some(b'1') => {
+                self.position += 1;
+                Ok(true)
+            }
+            _ => {
+                let (offset, found) = self.found();
+                Err(SvgPathError::ExpectedFlag { offset, found })
+            }
+        }
+    }
+
+    fn point(&mut self, relative: bool, current: Point) -> Result<Point, SvgPathError> {
+        let x = self.number()?;
+        let y = self.number()?;
+        Ok(if relative {
+            Point {
+                x: current.x + x,
+                y: current.y + y,
+            }
+        } else {
+            Point { x, y }
+        })
+    }
+}
+
+/// The center parameterization of an SVG elliptical arc.
+struct CenterArc {
+    center: Point,
+    rx: f32,
+    ry: f32,
+    rotation: f32,
+    start_angle: f32,
+    sweep: f32,
+}
+
+/// Converts an SVG endpoint arc into its center parameterization (SVG 1.1, appendix F.6.5).
+fn endpoint_to_center(
+    from: Point,
+    to: Point,
+    radii: (f32, f32),
+    rotation_degrees: f32,
+    large_arc: bool,
+    sweep: bool,
+) -> CenterArc {
+    let rotation = rotation_degrees.to_radians();
+    let (sin, cos) = rotation.sin_cos();
+    let dx = (from.x - to.x) / 2.0;
+    let dy = (from.y - to.y) / 2.0;
+    let x1 = cos * dx + sin * dy;
+    let y1 = -sin * dx + cos * dy;
+
+    let mut rx = radii.0.abs();
+    let mut ry = radii.1.abs();
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+
+    let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let mut coefficient = (numerator / denominator).max(0.0).sqrt();
+    if large_arc == sweep {
+        coefficient = -coefficient;
+    }
+    let cx1 = coefficient * rx * y1 / ry;
+    let cy1 = -coefficient * ry * x1 / rx;
+
+    let center = Point {
+        x: cos * cx1 - sin * cy1 + (from.x + to.x) / 2.0,
+        y: sin * cx1 + cos * cy1 + (from.y + to.y) / 2.0,
+    };
+
+    let ux = (x1 - cx1) / rx;
+    let uy = (y1 - cy1) / ry;
+    let vx = (-x1 - cx1) / rx;
+    let vy = (-y1 - cy1) / ry;
+    let start_angle = uy.atan2(ux);
+    let mut delta = (ux * vy - uy * vx).atan2(ux * vx + uy * vy);
+    if !sweep && delta > 0.0 {
+        delta -= TAU;
+    } else if sweep && delta < 0.0 {
+        delta += TAU;
+    }
+
+    CenterArc {
+        center,
+        rx,
+        ry,
+        rotation,
+        start_angle,
+        sweep: delta,
+    }
+}
+
+impl CenterArc {
+    /// Maps the arc onto a `Curve::Arc` when it is circular, or onto cubic approximations otherwise.
+    fn to_curves(&self) -> Vec<Curve> {
+        if (self.rx - self.ry).abs() <= EPSILON * self.rx.max(1.0) {
+            let start_angle = self.start_angle + self.rotation;
+            return vec![Curve::Arc {
+                center: self.center,
+                radius: self.rx,
+                start_angle,
+                end_angle: start_angle + self.sweep,
+            }];
+        }
+
+        arc_to_cubics(
+            self.center,
+            (self.rx, self.ry),
+            self.rotation,
+            self.start_angle,
+            self.sweep,
+        )
+    }
+}
+
+/// Closes the current subpath with `Z` when it ends where it started.
+fn close_subpath(data: &mut String, current: Option<Point>, start: Point, len: usize) {
+    match current {
+        Some(end) if len > 1 && same_point(end, start) => data.push_str(" Z"),
+        _ => {}
+    }
+}
+
+impl Path {
+    /// Parses SVG path data (the `d` attribute) into a path.
+    ///
+    /// Supports the `M`, `L`, `H`, `V`, `C`, `S`, `Q`, `T`, `A` and `Z` commands in both
+    /// absolute and relative forms. Lines are stored as quadratic curves, circular arcs as
+    /// `Curve::Arc` and elliptical arcs as cubic approximations.
+    pub fn from_svg_d(data: &str) -> Result<Path, SvgPathError> {
+        let mut parser = Parser::new(data);
+        let mut curves = Vec::new();
+        let mut current = Point { x: 0.0, y: 0.0 };
+        let mut subpath_start = current;
+        let mut last_cubic_control: Option<Point> = None;
+        let mut last_quadratic_control: Option<Point> = None;
+        let mut command: Option<u8> = None;
+
+        while !parser.at_end() {
+            let next = match parser.next_command()? {
+                Some(c) => c,
+                None => match command {
+                    // Extra coordinates after a move are implicit line commands.
+                    Some(b'M') => b'L',
+                    Some(b'm') => b'l',
+                    Some(c) if !matches!(c, b'Z' | b'z') => c,
+                    _ => {
+                        let (offset, found) = parser.found();
+                        return Err(SvgPathError::ExpectedCommand { offset, found });
+                    }
+                },
+            };
+            command = Some(next);
+            let relative = next.is_ascii_lowercase();
+            let mut cubic_control = None;
+            let mut quadratic_control = None;
+
+            match next.to_ascii_uppercase() {
+                b'M' => {
+                    current = parser.point(relative, current)?;
+                    subpath_start = current;
+                }
+                b'L' => {
+                    let end = parser.point(relative, current)?;
+                    curves.push(line(current, end));
+                    current = end;
+                }
+                b'H' => {
+                    let x = parser.number()?;
+                    let end = Point {
+                        x: if relative { current.x + x } else { x },
+                        y: current.y,
+                    };
+                    curves.push(line(current, end));
+                    current = end;
+                }
+                b'V' => {
+                    let y = parser.number()?;
+                    let end = Point {
+                        x: current.x,
+                        y: if relative { current.y + y } else { y },
+                    };
+                    curves.push(line(current, end));
+                    current = end;
+                }
+                b'C' | b'S' => {
+                    let p1 = if next.eq_ignore_ascii_case(&b'C') {
+                        parser.point(relative, current)?
+                    } else {
+                        last_cubic_control
+                            .map(|c| reflect(c, current))
+                            .unwrap_or(current)
+                    };
+                    let p2 = parser.point(relative, current)?;
+                    let p3 = parser.point(relative, current)?;
+                    curves.push(Curve::Cubic {
+                        p0: current,
+                        p1,
+                        p2,
+                        p3,
+                    });
+                    cubic_control = Some(p2);
+                    current = p3;
+                }
+                b'Q' | b'T' => {
+                    let p1 = if next.eq_ignore_ascii_case(&b'Q') {
+                        parser.point(relative, current)?
+                    } else {
+                        last_quadratic_control
+                            .map(|c| reflect(c, current))
+                            .unwrap_or(current)
+                    };
+                    let p2 = parser.point(relative, current)?;
+                    curves.push(Curve::Quadratic {
+                        p0: current,
+                        p1,
+                        p2,
+                    });
+                    quadratic_control = Some(p1);
+                    current = p2;
+                }
+                b'A' => {
+                    let rx = parser.number()?;
+                    let ry = parser.number()?;
+                    let rotation = parser.number()?;
+                    let large_arc = parser.flag()?;
+                    let sweep = parser.flag()?;
+                    let end = parser.point(relative, current)?;
+                    if same_point(current, end) {
+                        // An arc to the same point is omitted, as per the specification.
+                    } else if rx.abs() <= EPSILON || ry.abs() <= EPSILON {
+                        curves.push(line(current, end));
+                    } else {
+                        let arc =
+                            endpoint_to_center(current, end, (rx, ry), rotation, large_arc, sweep);
+                        curves.extend(arc.to_curves());
+                    }
+                    current = end;
+                }
+                b'Z' => {
+                    if !same_point(current, subpath_start) {
+                        curves.push(line(current, subpath_start));
+                    }
+                    current = subpath_start;
+                }
+                command => {
+                    return Err(SvgPathError::UnsupportedCommand {
+                        offset: parser.position,
+                        command: command as char,
+                    });
+                }
+            }
+
+            last_cubic_control = cubic_control;
+            last_quadratic_control = quadratic_control;
+
+            if matches!(next, b'Z' | b'z') && parser.has_number() {
+                let (offset, found) = parser.found();
+                return Err(SvgPathError::ExpectedCommand { offset, found });
+            }
+        }
+
+        Ok(Path { curves })
+    }
+
+    /// Serializes the path into SVG path data (the `d` attribute).
+    ///
+    /// Disconnected curves start a new subpath, and subpaths that end where they started
+    /// are closed with `Z`.
+    pub fn to_svg_d(&self) -> String {
+        let mut data = String::new();
+        let mut current: Option<Point> = None;
+        let mut subpath_start = Point { x: 0.0, y: 0.0 };
+        let mut subpath_len = 0;
+
+        for curve in &self.curves {
+            if matches!(curve, Curve::CatmullRom { points, .. } if points.len() < 4) {
+                continue;
+            }
+
+            let start = curve.start_point();
+            if current.is_none_or(|point| !same_point(point, start)) {
+                close_subpath(&mut data, current, subpath_start, subpath_len);
+                if !data.is_empty() {
+                    data.push(' ');
+                }
+                let _ = write!(data, "M{} {}", start.x, start.y);
+                subpath_start = start;
+                subpath_len = 0;
+            }
+
+            match curve {
+                Curve::Quadratic { p0, p1, p2 } => {
+                    if same_point(*p1, midpoint(*p0, *p2)) {
+                        let _ = write!(data, " L{} {}", p2.x, p2.y);
+                    } else {
+                        let _ = write!(data, " Q{} {} {} {}", p1.x, p1.y, p2.x, p2.y);
+                    }
+                }
+                Curve::Cubic { p1, p2, p3, .. } => {
+                    let _ = write!(
+                        data,
+                        " C{} {} {} {} {} {}",
+                        p1.x, p1.y, p2.x, p2.y, p3.x, p3.y
+                    );
+                }
+                Curve::Arc {
+                    center,
+                    radius,
+                    start_angle,
+                    end_angle,
+                } => {
+                    // SVG arcs cannot describe a full turn, so split into pieces below one.
+                    let sweep = end_angle - start_angle;
+                    let pieces = (sweep.abs() / PI).ceil().max(1.0) as usize;
+                    let step = sweep / pieces as f32;
+                    for i in 1..=pieces {
+                        let angle = start_angle + step * i as f32;
+                        let _ = write!(
+                            data,
+                            " A{} {} 0 0 {} {} {}",
+                            radius,
+                            radius,
+                            if step > 0.0 { 1 } else { 0 },
+                            center.x + radius * angle.cos(),
+                            center.y + radius * angle.sin()
+                        );
+                    }
+                }
+                Curve::CatmullRom { points, tension } => {
+                    for [_, p1, p2, p3] in catmull_rom_to_cubics(points, *tension) {
+                        let _ = write!(
+                            data,
+                            " C{} {} {} {} {} {}",
+                            p1.x, p1.y, p2.x, p2.y, p3.x, p3.y
+                        );
+                    }
+                }
+            }
+
+            current = Some(curve.end_point());
+            subpath_len += 1;
+        }
+
+        close_subpath(&mut data, current, subpath_start, subpath_len);
+        data
+    }
+}
@@ -0,0 +1,207 @@
+use std::f32::consts::FRAC_PI_2;
+
+use glam::{Mat3, Vec2, Vec3};
+
+use crate::object::curve::{Curve, Path, Point};
+
+/// Relative tolerance used to decide whether a matrix keeps circles circular.
+const EPSILON: f32 = 1e-4;
+
+fn apply(matrix: &Mat3, point: Point) -> Point {
+    let result = matrix.transform_point2(Vec2::new(point.x, point.y));
+    Point {
+        x: result.x,
+        y: result.y,
+    }
+}
+
+/// Approximates an elliptical arc, whose axes are rotated by `rotation` radians, with cubic Bézier
+/// curves of at most a quarter turn each.
+pub(crate) fn arc_to_cubics(
+    center: Point,
+    radii: (f32, f32),
+    rotation: f32,
+    start_angle: f32,
+    sweep: f32,
+) -> Vec<Curve> {
+    let (sin, cos) = rotation.sin_cos();
+    let map = |x: f32, y: f32| {
+        let x = x * radii.0;
+        let y = y * radii.1;
+        Point {
+            x: center.x + cos * x - sin * y,
+            y: center.y + sin * x + cos * y,
+        }
+    };
+
+    let segments = (sweep.abs() / FRAC_PI_2).ceil().max(1.0) as usize;
+    let step = sweep / segments as f32;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    (0..segments)
+        .map(|i| {
+            let a = start_angle + step * i as f32;
+            let b = a + step;
+            let (sa, ca) = a.sin_cos();
+            let (sb, cb) = b.sin_cos();
+            Curve::Cubic {
+                p0: map(ca, sa),
+                p1: map(ca - k * sa, sa + k * ca),
+                p2: map(cb + k * sb, sb - k * cb),
+                p3: map(cb, sb),
+            }
+        })
+        .collect()
+}
+
+impl Curve {
+    /// Applies an affine transform to the curve.
+    ///
+    /// Arcs stay arcs under rotations, translations, reflections and uniform scales; any other
+    /// transform turns them into cubic approximations, so more than one curve may be returned.
+    pub fn transform(&self, matrix: &Mat3) -> Vec<Curve> {
+        match self {
+            Curve::Quadratic { p0, p1, p2 } => vec![Curve::Quadratic {
+                p0: apply(matrix, *p0),
+                p1: apply(matrix, *p1),
+                p2: apply(matrix, *p2),
+            }],
+            Curve::Cubic { p0, p1, p2, p3 } => vec![Curve::Cubic {
+                p0: apply(matrix, *p0),
+                p1: apply(matrix, *p1),
+                p2: apply(matrix, *p2),
+                p3: apply(matrix, *p3),
+            }],
+            Curve::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+            } => {
+                let x = matrix.transform_vector2(Vec2::new(1.0, 0.0));
+                let y = matrix.transform_vector2(Vec2::new(0.0, 1.0));
+                let scale = x.length();
+                let similar = (scale - y.length()).abs() <= EPSILON * scale.max(1.0)
+                    && x.dot(y).abs() <= EPSILON * scale.max(1.0) * scale.max(1.0);
+
+                if !similar {
+                    let sweep = end_angle - start_angle;
+                    return arc_to_cubics(*center, (*radius, *radius), 0.0, *start_angle, sweep)
+                        .iter()
+                        .flat_map(|curve| curve.transform(matrix))
+                        .collect();
+                }
+
+                let rotation = x.y.atan2(x.x);
+                let reflected = x.x * y.y - x.y * y.x < 0.0;
+                let (start_angle, end_angle) = if reflected {
+                    (rotation - start_angle, rotation - end_angle)
+                } else {
+                    (rotation + start_angle, rotation + end_angle)
+                };
+                vec![Curve::Arc {
+                    center: apply(matrix, *center),
+                    radius: radius * scale,
+                    start_angle,
+                    end_angle,
+                }]
+            }
+            Curve::CatmullRom { points, tension } => vec![Curve::CatmullRom {
+                points: points.iter().map(|point| apply(matrix, *point)).collect(),
+                tension: *tension,
+            }],
+        }
+    }
+
+    /// Returns the same curve traversed in the opposite direction.
+    pub fn reversed(&self) -> Curve {
+        match self {
+            Curve::Quadratic { p0, p1, p2 } => Curve::Quadratic {
+                p0: *p2,
+                p1: *p1,
+                p2: *p0,
+            },
+            Curve::Cubic { p0, p1, p2, p3 } => Curve::Cubic {
+                p0: *p3,
+                p1: *p2,
+                p2: *p1,
+                p3: *p0,
+            },
+            Curve::Arc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+            } => Curve::Arc {
+                center: *center,
+                radius: *radius,
+                start_angle: *end_angle,
+                end_angle: *start_angle,
+            },
+            Curve::CatmullRom { points, tension } => Curve::CatmullRom {
+                points: points.iter().rev().copied().collect(),
+                tension: *tension,
+            },
+        }
+    }
+}
+
+impl Path {
+    /// Applies an affine transform to every curve of the path.
+    pub fn transform(&self, matrix: &Mat3) -> Path {
+        Path {
+            curves: self
+                .curves
+                .iter()
+                .flat_map(|curve| curve.transform(matrix))
+                .collect(),
+        }
+    }
+
+    /// Moves the path by the given offset.
+    pub fn translate(&self, x: f32, y: f32) -> Path {
+        self.transform(&Mat3::from_scale_angle_translation(
+            Vec2::new(1.0, 1.0),
+            0.0,
+            Vec2::new(x, y),
+        ))
+    }
+
+    /// Rotates the path by an angle in radians around the given origin.
+    pub fn rotate(&self, angle: f32, origin: Point) -> Path {
+        self.transform(&around(Mat3::from_rotation_z(angle), origin))
+    }
+
+    /// Scales the path by the given factors around the given origin.
+    pub fn scale(&self, x: f32, y: f32, origin: Point) -> Path {
+        self.transform(&around(Mat3::from_scale(Vec3::new(x, y, 1.0)), origin))
+    }
+
+    /// Skews the path by the given angles in radians around the given origin.
+    pub fn skew(&self, x_angle: f32, y_angle: f32, origin: Point) -> Path {
+        let skew = Mat3::from_cols(
+            Vec3::new(1.0, y_angle.tan(), 0.0),
+            Vec3::new(x_angle.tan(), 1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        );
+        self.transform(&around(skew, origin))
+    }
+
+    /// Returns the same path traversed in the opposite direction.
+    pub fn reversed(&self) -> Path {
+        Path {
+            curves: self.curves.iter().rev().map(Curve::reversed).collect(),
+        }
+    }
+}
+
+/// Wraps a linear transform so it is applied around `origin` instead of the coordinate origin.
+fn around(matrix: Mat3, origin: Point) -> Mat3 {
+    let to_origin = Mat3::from_scale_angle_translation(
+        Vec2::new(1.0, 1.0),
+        0.0,
+        Vec2::new(-origin.x, -origin.y),
+    );
+    let back =
+        Mat3::from_scale_angle_translation(Vec2::new(1.0, 1.0), 0.0, Vec2::new(origin.x, origin.y));
+    back * matrix * to_origin
+}
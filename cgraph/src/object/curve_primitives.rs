@@ -1,7 +1,7 @@
 use crate::object::primitives::{Color, Position};
 use crate::object::{
     Object,
-    curve::{Curve, Path, Point},
+    curve::{Curve, Flattening, Path, Point},
 };
 
 /// Creates a quadratic Bezier curve object.
@@ -12,7 +12,7 @@ pub fn create_quadratic_bezier(
     color: Color,
     z_index: f32,
     line_width: f32,
-    steps: usize,
+    steps: Option<usize>,
 ) -> Object {
     let curve = Curve::Quadratic {
         p0: Point {
@@ -29,7 +29,7 @@ pub fn create_quadratic_bezier(
     let path = Path {
         curves: vec![curve],
     };
-    path.to_object(steps, color, z_index, line_width)
+    path.to_object(Flattening::from(steps), color, z_index, line_width)
 }
 
 /// Represents a control path for cubic Bezier curves.
@@ -50,7 +50,7 @@ pub fn create_cubic_bezier(
     color: Color,
     z_index: f32,
     line_width: f32,
-    steps: usize,
+    steps: Option<usize>,
 ) -> Object {
     let curve = Curve::Cubic {
         p0: Point {
@@ -74,7 +74,7 @@ pub fn create_cubic_bezier(
     let path = Path {
         curves: vec![curve],
     };
-    path.to_object(steps, color, z_index, line_width)
+    path.to_object(Flattening::from(steps), color, z_index, line_width)
 }
 
 /// Represents an arc defined by its center, radius, and angles.
@@ -95,7 +95,7 @@ pub fn create_arc(
     color: Color,
     z_index: f32,
     line_width: f32,
-    steps: usize,
+    steps: Option<usize>,
 ) -> Object {
     let curve = Curve::Arc {
         center: Point {
@@ -110,7 +110,7 @@ pub fn create_arc(
     let path = Path {
         curves: vec![curve],
     };
-    path.to_object(steps, color, z_index, line_width)
+    path.to_object(Flattening::from(steps), color, z_index, line_width)
 }
 
 /// Creates a full circle arc object.
@@ -120,7 +120,7 @@ pub fn create_circle_arc(
     color: Color,
     z_index: f32,
     line_width: f32,
-    steps: usize,
+    steps: Option<usize>,
 ) -> Object {
     create_arc(
         ArcAngle {
@@ -143,7 +143,7 @@ pub fn create_catmull_rom_spline(
     color: Color,
     z_index: f32,
     line_width: f32,
-    steps: usize,
+    steps: Option<usize>,
 ) -> Object {
     if points.len() < 4 {
         // Not enough points for Catmull-Rom, return empty object
@@ -163,7 +163,7 @@ pub fn create_catmull_rom_spline(
     let path = Path {
         curves: vec![curve],
     };
-    path.to_object(steps, color, z_index, line_width)
+    path.to_object(Flattening::from(steps), color, z_index, line_width)
 }
 
 /// Creates a smooth path object from a series of points.
@@ -172,7 +172,7 @@ pub fn create_smooth_path(
     color: Color,
     z_index: f32,
     line_width: f32,
-    steps: usize,
+    steps: Option<usize>,
 ) -> Object {
     if points.len() < 2 {
         return Object::new(vec![], vec![]);
@@ -206,7 +206,7 @@ pub fn create_smooth_path(
     }
 
     let path = Path { curves };
-    path.to_object(steps, color, z_index, line_width)
+    path.to_object(Flattening::from(steps), color, z_index, line_width)
 }
 
 /// Creates a path object from a series of points.
@@ -215,7 +215,7 @@ pub fn create_path_from_points(
     color: Color,
     z_index: f32,
     line_width: f32,
-    steps: usize,
+    steps: Option<usize>,
 ) -> Object {
     if points.len() < 2 {
         return Object::new(vec![], vec![]);
@@ -250,7 +250,7 @@ pub fn create_path_from_points(
     }
 
     let path = Path { curves };
-    path.to_object(steps, color, z_index, line_width)
+    path.to_object(Flattening::from(steps), color, z_index, line_width)
 }
 
 /// Represents a point in 2D space.
@@ -369,12 +369,18 @@ impl PathBuilder {
         self
     }
 
-    /// Builds the path into an Object with specified color, z_index, line_width, and steps.
-    pub fn build(self, color: Color, z_index: f32, line_width: f32, steps: usize) -> Object {
+    /// Builds the path into an Object with specified color, z_index, line_width, and flattening.
+    pub fn build(
+        self,
+        color: Color,
+        z_index: f32,
+        line_width: f32,
+        flattening: Flattening,
+    ) -> Object {
         let path = Path {
             curves: self.curves,
         };
-        path.to_object(steps, color, z_index, line_width)
+        path.to_object(flattening, color, z_index, line_width)
     }
 
     /// Builds the path into a Path object.
@@ -398,7 +404,7 @@ pub fn create_heart_shape(
     color: Color,
     z_index: f32,
     line_width: f32,
-    steps: usize,
+    steps: Option<usize>,
 ) -> Object {
     let scale = size / 100.0; // Normalize to size
 
@@ -424,7 +430,7 @@ pub fn create_heart_shape(
             Position::new(center.x, center.y + 5.0 * scale),
             Position::new(center.x, center.y + 25.0 * scale),
         )
-        .build(color, z_index, line_width, steps)
+        .build(color, z_index, line_width, Flattening::from(steps))
 }
 
 /// Represents a star shape with its center, outer radius, inner radius, and number of points.
@@ -445,7 +451,7 @@ pub fn create_star_shape(
     color: Color,
     z_index: f32,
     line_width: f32,
-    steps: usize,
+    steps: Option<usize>,
 ) -> Object {
     if shape.points < 3 {
         return Object::new(vec![], vec![]);
@@ -474,5 +480,5 @@ pub fn create_star_shape(
 
     builder = builder.line_to(Position::new(start_x, start_y));
 
-    builder.build(color, z_index, line_width, steps)
+    builder.build(color, z_index, line_width, Flattening::from(steps))
 }
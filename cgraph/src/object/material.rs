@@ -0,0 +1,56 @@
+/// A custom fragment shader that replaces the default shading of an object.
+///
+/// The source is compiled together with the built-in shaders, so the fragment function can use
+/// `VertexOut`, `Uniforms` and the shape helpers. It receives the same arguments as the built-in
+/// `fragment_main`, plus the material's uniform block as `constant float*` at `[[buffer(4)]]`:
+///
+/// ```metal
+/// fragment float4 tint(VertexOut in [[stage_in]], constant float* params [[buffer(4)]]) {
+///     return float4(params[0], params[1], params[2], in.color.a);
+/// }
+/// ```
+///
+/// Materials are identified by their function name, which must be unique.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Material {
+    function: String,
+    source: String,
+    uniforms: Vec<f32>,
+}
+
+impl Material {
+    /// Creates a material from Metal source code defining the fragment function `function`.
+    pub fn new(function: &str, source: &str) -> Self {
+        Material {
+            function: function.to_string(),
+            source: source.to_string(),
+            uniforms: Vec::new(),
+        }
+    }
+
+    /// Sets the values of the material's uniform block.
+    pub fn with_uniforms(mut self, uniforms: &[f32]) -> Self {
+        self.set_uniforms(uniforms);
+        self
+    }
+
+    /// Replaces the values of the material's uniform block.
+    pub fn set_uniforms(&mut self, uniforms: &[f32]) {
+        self.uniforms = uniforms.to_vec();
+    }
+
+    /// Returns the name of the fragment function.
+    pub fn function(&self) -> &str {
+        &self.function
+    }
+
+    /// Returns the Metal source code of the material.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the values of the material's uniform block.
+    pub fn uniforms(&self) -> &[f32] {
+        &self.uniforms
+    }
+}
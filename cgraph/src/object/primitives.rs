@@ -342,3 +342,403 @@ pub fn create_circle_with_shadow(
     object.set_shadow(shadow_data.radius, shadow_data.color, shadow_data.offset);
     object
 }
+
+/// Radii for each corner of a rounded quad, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CornerRadii {
+    /// The radius of the top-left corner.
+    pub top_left: f32,
+    /// The radius of the top-right corner.
+    pub top_right: f32,
+    /// The radius of the bottom-right corner.
+    pub bottom_right: f32,
+    /// The radius of the bottom-left corner.
+    pub bottom_left: f32,
+}
+
+impl CornerRadii {
+    /// Creates corner radii from the top-left corner going clockwise.
+    pub fn new(top_left: f32, top_right: f32, bottom_right: f32, bottom_left: f32) -> Self {
+        CornerRadii {
+            top_left,
+            top_right,
+            bottom_right,
+            bottom_left,
+        }
+    }
+
+    /// Uses the same radius for every corner.
+    pub fn uniform(radius: f32) -> Self {
+        CornerRadii::new(radius, radius, radius, radius)
+    }
+
+    /// Rounds only the top corners, as used by tabs.
+    pub fn top(radius: f32) -> Self {
+        CornerRadii::new(radius, radius, 0.0, 0.0)
+    }
+
+    /// Rounds only the bottom corners.
+    pub fn bottom(radius: f32) -> Self {
+        CornerRadii::new(0.0, 0.0, radius, radius)
+    }
+
+    /// Rounds only the left corners.
+    pub fn left(radius: f32) -> Self {
+        CornerRadii::new(radius, 0.0, 0.0, radius)
+    }
+
+    /// Rounds only the right corners.
+    pub fn right(radius: f32) -> Self {
+        CornerRadii::new(0.0, radius, radius, 0.0)
+    }
+}
+
+/// Where a border is drawn relative to the edge of its object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BorderAlignment {
+    /// The border is drawn inside the edge, keeping the object's size.
+    #[default]
+    Inside,
+    /// The border is centered on the edge.
+    Center,
+    /// The border is drawn outside the edge, around the object.
+    Outside,
+}
+
+impl BorderAlignment {
+    /// Returns how far past the edge a border of the given width reaches.
+    pub fn outset(&self, width: f32) -> f32 {
+        match self {
+            BorderAlignment::Inside => 0.0,
+            BorderAlignment::Center => width / 2.0,
+            BorderAlignment::Outside => width,
+        }
+    }
+}
+
+/// A dash pattern for borders, in pixels along the outline.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BorderDash {
+    /// The length of each dash.
+    pub length: f32,
+    /// The space between two dashes.
+    pub gap: f32,
+}
+
+impl BorderDash {
+    /// Creates a dash pattern with the given dash and gap lengths.
+    pub fn new(length: f32, gap: f32) -> Self {
+        BorderDash { length, gap }
+    }
+}
+
+/// The outline used by the fragment shader to cut a quad into its final shape.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum ShapeKind {
+    /// A rectangle, optionally with rounded corners.
+    #[default]
+    Rect,
+    /// An ellipse filling the quad.
+    Ellipse,
+    /// A polygon with points relative to the quad, from (0, 0) at the top-left to (1, 1) at the
+    /// bottom-right. The object's corner radius rounds its corners.
+    Polygon(Vec<Vec2>),
+}
+
+/// The direction a triangle, arrow or chevron points to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Points to the top of the screen.
+    Up,
+    /// Points to the bottom of the screen.
+    Down,
+    /// Points to the left of the screen.
+    Left,
+    /// Points to the right of the screen.
+    Right,
+}
+
+impl Direction {
+    /// Turns relative points of a right-pointing shape so they point in this direction.
+    fn orient(&self, points: Vec<Vec2>) -> Vec<Vec2> {
+        points
+            .into_iter()
+            .map(|p| match self {
+                Direction::Right => p,
+                Direction::Left => Vec2::new(1.0 - p.x, p.y),
+                Direction::Down => Vec2::new(p.y, p.x),
+                Direction::Up => Vec2::new(p.y, 1.0 - p.x),
+            })
+            .collect()
+    }
+}
+
+/// Creates a new `Object` representing a quad with a different radius for each corner.
+pub fn create_quad_with_corner_radii(
+    size: Size,
+    color: Color,
+    z_index: f32,
+    position: Position,
+    corner_radii: CornerRadii,
+) -> Object {
+    let mut object = create_quad(size, color, z_index, position);
+    object.corner_radii = Some(corner_radii);
+    object
+}
+
+/// Creates a new `Object` representing a tab, a quad with only its top corners rounded.
+pub fn create_tab(
+    size: Size,
+    color: Color,
+    z_index: f32,
+    position: Position,
+    corner_radius: f32,
+) -> Object {
+    create_quad_with_corner_radii(
+        size,
+        color,
+        z_index,
+        position,
+        CornerRadii::top(corner_radius),
+    )
+}
+
+/// Creates a new `Object` representing a capsule, a quad whose shorter sides are fully rounded.
+pub fn create_capsule(size: Size, color: Color, z_index: f32, position: Position) -> Object {
+    let corner_radius = size.width.min(size.height) / 2.0;
+    create_rounded_quad(size, color, z_index, position, corner_radius)
+}
+
+/// Creates a new `Object` representing an ellipse filling the given size.
+pub fn create_ellipse(size: Size, color: Color, z_index: f32, position: Position) -> Object {
+    let mut object = create_quad(size, color, z_index, position);
+    object.shape = ShapeKind::Ellipse;
+    object
+}
+
+/// Creates a new `Object` representing a polygon from points relative to its size.
+///
+/// Points go from (0, 0) at the top-left to (1, 1) at the bottom-right of the quad.
+pub fn create_shape(
+    size: Size,
+    color: Color,
+    z_index: f32,
+    position: Position,
+    points: Vec<Vec2>,
+) -> Object {
+    let mut object = create_quad(size, color, z_index, position);
+    object.shape = ShapeKind::Polygon(points);
+    object
+}
+
+/// Creates a new `Object` representing an isosceles triangle pointing in the given direction.
+pub fn create_triangle(
+    size: Size,
+    color: Color,
+    z_index: f32,
+    position: Position,
+    direction: Direction,
+) -> Object {
+    let points = direction.orient(vec![
+        Vec2::new(0.0, 0.0),
+        Vec2::new(1.0, 0.5),
+        Vec2::new(0.0, 1.0),
+    ]);
+    create_shape(size, color, z_index, position, points)
+}
+
+/// Creates a new `Object` representing an arrow pointing in the given direction.
+pub fn create_arrow(
+    size: Size,
+    color: Color,
+    z_index: f32,
+    position: Position,
+    direction: Direction,
+) -> Object {
+    let points = direction.orient(vec![
+        Vec2::new(0.0, 0.3),
+        Vec2::new(0.55, 0.3),
+        Vec2::new(0.55, 0.0),
+        Vec2::new(1.0, 0.5),
+        Vec2::new(0.55, 1.0),
+        Vec2::new(0.55, 0.7),
+        Vec2::new(0.0, 0.7),
+    ]);
+    create_shape(size, color, z_index, position, points)
+}
+
+/// Creates a new `Object` representing a chevron pointing in the given direction, with strokes
+/// of the given thickness in pixels.
+pub fn create_chevron(
+    size: Size,
+    color: Color,
+    z_index: f32,
+    position: Position,
+    direction: Direction,
+    thickness: f32,
+) -> Object {
+    let length = match direction {
+        Direction::Left | Direction::Right => size.width,
+        Direction::Up | Direction::Down => size.height,
+    };
+    let t = (thickness / length).clamp(0.0, 1.0);
+    let points = direction.orient(vec![
+        Vec2::new(0.0, 0.0),
+        Vec2::new(t, 0.0),
+        Vec2::new(1.0, 0.5),
+        Vec2::new(t, 1.0),
+        Vec2::new(0.0, 1.0),
+        Vec2::new(1.0 - t, 0.5),
+    ]);
+    create_shape(size, color, z_index, position, points)
+}
+
+/// Creates a new `Object` representing a regular polygon with rounded corners.
+pub fn create_rounded_polygon(
+    size: Size,
+    color: Color,
+    z_index: f32,
+    position: Position,
+    faces: usize,
+    corner_radius: f32,
+) -> Object {
+    if faces < 3 {
+        // Return a default empty object for invalid polygon
+        return Object::new(vec![], vec![]);
+    }
+
+    // Pull the corners in so the rounded outline still touches the bounding circle.
+    let radius = size.width.min(size.height) / 2.0;
+    let corner_radius = corner_radius.clamp(0.0, radius);
+    let inset = radius - corner_radius / (std::f32::consts::PI / faces as f32).cos();
+
+    let points = (0..faces)
+        .map(|i| {
+            let angle = 2.0 * std::f32::consts::PI * i as f32 / faces as f32;
+            Vec2::new(
+                0.5 + inset * angle.cos() / size.width,
+                0.5 + inset * angle.sin() / size.height,
+            )
+        })
+        .collect();
+
+    let mut object = create_shape(size, color, z_index, position, points);
+    object.corner_radius = corner_radius;
+    object
+}
+
+#[cfg(target_os = "macos")]
+/// Creates a new `Object` representing a textured ellipse with the given size, z-index, position, and image path.
+pub fn create_textured_ellipse(
+    size: Size,
+    z_index: f32,
+    position: Position,
+    image_path: &str,
+) -> Result<Object, Box<dyn std::error::Error>> {
+    use crate::macos::image::Image;
+
+    let image = Image::new(image_path)?;
+    let mut object = create_ellipse(size, Vec4::new(1.0, 1.0, 1.0, 1.0), z_index, position);
+    object = object.with_texture(image);
+    Ok(object)
+}
+
+#[cfg(target_os = "macos")]
+/// Creates a new `Object` representing a textured quad with a different radius for each corner.
+pub fn create_textured_quad_with_corner_radii(
+    size: Size,
+    z_index: f32,
+    position: Position,
+    corner_radii: CornerRadii,
+    image_path: &str,
+) -> Result<Object, Box<dyn std::error::Error>> {
+    use crate::macos::image::Image;
+
+    let image = Image::new(image_path)?;
+    let mut object = create_quad_with_corner_radii(
+        size,
+        Vec4::new(1.0, 1.0, 1.0, 1.0),
+        z_index,
+        position,
+        corner_radii,
+    );
+    object = object.with_texture(image);
+    Ok(object)
+}
+
+#[cfg(target_os = "macos")]
+/// Creates a new `Object` representing a textured polygon from points relative to its size.
+pub fn create_textured_shape(
+    size: Size,
+    z_index: f32,
+    position: Position,
+    points: Vec<Vec2>,
+    image_path: &str,
+) -> Result<Object, Box<dyn std::error::Error>> {
+    use crate::macos::image::Image;
+
+    let image = Image::new(image_path)?;
+    let mut object = create_shape(
+        size,
+        Vec4::new(1.0, 1.0, 1.0, 1.0),
+        z_index,
+        position,
+        points,
+    );
+    object = object.with_texture(image);
+    Ok(object)
+}
+
+/// Creates a new `Object` representing a quad with per-corner radii and shadow properties.
+pub fn create_quad_with_corner_radii_and_shadow(
+    size: Size,
+    color: Color,
+    z_index: f32,
+    position: Position,
+    corner_radii: CornerRadii,
+    shadow_data: ShadowData,
+) -> Object {
+    let mut object = create_quad_with_corner_radii(size, color, z_index, position, corner_radii);
+    object.set_shadow(shadow_data.radius, shadow_data.color, shadow_data.offset);
+    object
+}
+
+/// Creates a new `Object` representing a capsule with shadow properties.
+pub fn create_capsule_with_shadow(
+    size: Size,
+    color: Color,
+    z_index: f32,
+    position: Position,
+    shadow_data: ShadowData,
+) -> Object {
+    let mut object = create_capsule(size, color, z_index, position);
+    object.set_shadow(shadow_data.radius, shadow_data.color, shadow_data.offset);
+    object
+}
+
+/// Creates a new `Object` representing an ellipse with shadow properties.
+pub fn create_ellipse_with_shadow(
+    size: Size,
+    color: Color,
+    z_index: f32,
+    position: Position,
+    shadow_data: ShadowData,
+) -> Object {
+    let mut object = create_ellipse(size, color, z_index, position);
+    object.set_shadow(shadow_data.radius, shadow_data.color, shadow_data.offset);
+    object
+}
+
+/// Creates a new `Object` representing a polygon from relative points with shadow properties.
+pub fn create_shape_with_shadow(
+    size: Size,
+    color: Color,
+    z_index: f32,
+    position: Position,
+    points: Vec<Vec2>,
+    shadow_data: ShadowData,
+) -> Object {
+    let mut object = create_shape(size, color, z_index, position, points);
+    object.set_shadow(shadow_data.radius, shadow_data.color, shadow_data.offset);
+    object
+}
@@ -1,17 +1,22 @@
+use std::time::Duration;
+
 use glam::{Vec2, Vec4};
+use thiserror::Error;
 
+use crate::app::CursorIcon;
 use crate::object::buffer::Buffer;
-use crate::object::primitives::Color;
+use crate::object::material::Material;
+use crate::object::primitives::{BorderAlignment, BorderDash, Color, CornerRadii, ShapeKind};
 use crate::object::{Object, Vertex};
 
 /// Renderer trait for rendering graphics objects.
 pub trait Renderer {
     /// Creates a new renderer instance.
-    fn new(window: &winit::window::Window, background_color: Color) -> Self
+    fn new(window: &winit::window::Window, background_color: Color) -> Result<Self, RenderError>
     where
         Self: Sized;
     /// Initializes the renderer.
-    fn render(&mut self, window: &winit::window::Window);
+    fn render(&mut self, window: &winit::window::Window) -> Result<(), RenderError>;
     /// Resizes the renderer to the specified width and height.
     fn resize(&mut self, width: f64, height: f64);
     /// Destroys the renderer and releases resources.
@@ -20,6 +25,17 @@ pub trait Renderer {
     fn add_object(&mut self, object: Object);
     /// Clears all objects from the renderer.
     fn clear(&mut self);
+    /// Returns the objects drawn by the renderer, in drawing order.
+    fn objects_mut(&mut self) -> &mut Vec<Object>;
+    /// Removes the object at the given index and returns it.
+    fn remove_object(&mut self, index: usize) -> Option<Object> {
+        let objects = self.objects_mut();
+        if index < objects.len() {
+            Some(objects.remove(index))
+        } else {
+            None
+        }
+    }
     /// Renders all objects in the renderer.
     fn as_any(&self) -> &dyn std::any::Any;
     #[allow(dead_code)]
@@ -27,15 +43,161 @@ pub trait Renderer {
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any;
     /// Sets the background color of the renderer.
     fn set_background_color(&mut self, background_color: Color);
+    /// Recompiles the built-in shaders from `source`, keeping the current ones if it fails.
+    fn reload_shaders(&mut self, _source: &str) -> Result<(), RenderError> {
+        Ok(())
+    }
+    /// Sets an object drawn above all the others and left out of the frame stats.
+    fn set_overlay(&mut self, _overlay: Option<Object>) {}
+    /// Moves the renderer and its objects to another device.
+    fn set_device(
+        &mut self,
+        _window: &winit::window::Window,
+        _device: &RenderDevice,
+    ) -> Result<(), RenderError> {
+        Ok(())
+    }
+    /// Returns the timings and counters of the last frame drawn.
+    fn frame_stats(&self) -> FrameStats {
+        FrameStats::default()
+    }
+}
+
+/// Timings and counters of a frame drawn by a renderer.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameStats {
+    /// The time spent on the CPU encoding and submitting the frame.
+    pub cpu_time: Duration,
+    /// The time the GPU spent on the last frame it finished, which may be an earlier one.
+    pub gpu_time: Duration,
+    /// The number of draw calls, counting shadows but not the performance overlay.
+    pub draw_calls: u32,
+    /// The number of objects drawn.
+    pub objects: u32,
+    /// The number of vertices drawn, counting shadows.
+    pub vertices: u32,
+    /// The number of GPU buffers allocated while drawing the frame.
+    pub buffer_allocations: u32,
+}
+
+/// Errors returned when creating a renderer or drawing a frame.
+#[derive(Debug, Error)]
+pub enum RenderError {
+    /// The system has no graphics device to draw with.
+    #[error("no graphics device found")]
+    NoDevice,
+    /// The platform has no renderer.
+    #[error("unsupported platform")]
+    UnsupportedPlatform,
+    /// The shaders could not be compiled.
+    #[error("cannot compile shaders: {0}")]
+    Shader(String),
+    /// The render pipeline could not be created.
+    #[error("cannot create render pipeline: {0}")]
+    Pipeline(String),
+    /// The graphics device was removed, so the renderer must move to another one.
+    #[error("the graphics device was lost")]
+    DeviceLost,
 }
 
 /// Creates a new renderer based on the platform.
-pub fn create_renderer(window: &winit::window::Window, color: Color) -> Box<dyn Renderer> {
+pub fn create_renderer(
+    window: &winit::window::Window,
+    color: Color,
+) -> Result<Box<dyn Renderer>, RenderError> {
     if cfg!(target_os = "macos") {
         use crate::macos::metal::MetalRenderer;
-        Box::new(MetalRenderer::new(window, color))
+        Ok(Box::new(MetalRenderer::new(window, color)?))
     } else {
-        panic!("Unsupported platform");
+        Err(RenderError::UnsupportedPlatform)
+    }
+}
+
+/// A renderer that keeps objects without drawing them, used by headless windows.
+pub(crate) struct HeadlessRenderer {
+    objects: Vec<Object>,
+    background_color: Color,
+}
+
+impl HeadlessRenderer {
+    pub(crate) fn with_color(background_color: Color) -> Self {
+        HeadlessRenderer {
+            objects: Vec::new(),
+            background_color,
+        }
+    }
+}
+
+impl Renderer for HeadlessRenderer {
+    fn new(_window: &winit::window::Window, background_color: Color) -> Result<Self, RenderError> {
+        Ok(HeadlessRenderer::with_color(background_color))
+    }
+
+    fn render(&mut self, _window: &winit::window::Window) -> Result<(), RenderError> {
+        Ok(())
+    }
+
+    fn resize(&mut self, _width: f64, _height: f64) {}
+
+    fn destroy(&self) {}
+
+    fn add_object(&mut self, object: Object) {
+        self.objects.push(object);
+    }
+
+    fn clear(&mut self) {
+        self.objects.clear();
+    }
+
+    fn objects_mut(&mut self) -> &mut Vec<Object> {
+        &mut self.objects
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn set_background_color(&mut self, background_color: Color) {
+        self.background_color = background_color;
+    }
+}
+
+/// A graphics device that can be shared by the renderers of several windows.
+#[derive(Clone)]
+pub struct RenderDevice {
+    #[cfg(target_os = "macos")]
+    pub(crate) device: metal::Device,
+}
+
+impl RenderDevice {
+    /// Returns the default graphics device of the system.
+    pub fn system_default() -> Result<Self, RenderError> {
+        Ok(RenderDevice {
+            #[cfg(target_os = "macos")]
+            device: metal::Device::system_default().ok_or(RenderError::NoDevice)?,
+        })
+    }
+}
+
+/// Creates a new renderer that draws with a device shared with other renderers.
+pub fn create_renderer_with_device(
+    window: &winit::window::Window,
+    color: Color,
+    device: &RenderDevice,
+) -> Result<Box<dyn Renderer>, RenderError> {
+    if cfg!(target_os = "macos") {
+        use crate::macos::metal::MetalRenderer;
+        Ok(Box::new(MetalRenderer::with_device(
+            window,
+            color,
+            &device.device,
+        )?))
+    } else {
+        Err(RenderError::UnsupportedPlatform)
     }
 }
 
@@ -54,6 +216,15 @@ impl Object {
             original_pixel_size: Vec2::new(1.0, 1.0),
             rotation: 0.0,
             corner_radius: 0.0,
+            corner_radii: None,
+            shape: ShapeKind::Rect,
+            border_width: 0.0,
+            border_color: Vec4::new(0.0, 0.0, 0.0, 0.0),
+            border_alignment: BorderAlignment::Inside,
+            border_dash: None,
+            cursor: None,
+            material: None,
+            tag: None,
             #[cfg(target_os = "macos")]
             texture: None,
             use_texture: false,
@@ -144,4 +315,107 @@ impl Object {
         self.shadow_on = !self.shadow_on;
         self.shadow_dirty = true;
     }
+
+    /// Gives each corner of the object its own radius.
+    pub fn with_corner_radii(mut self, corner_radii: CornerRadii) -> Self {
+        self.corner_radii = Some(corner_radii);
+        self
+    }
+
+    /// Sets a different radius for each corner of the object.
+    pub fn set_corner_radii(&mut self, corner_radii: CornerRadii) {
+        self.corner_radii = Some(corner_radii);
+    }
+
+    /// Sets the outline the object is cut into when rendered.
+    pub fn set_shape(&mut self, shape: ShapeKind) {
+        self.shape = shape;
+    }
+
+    /// Draws a border along the edge of the object.
+    ///
+    /// Borders follow the outline computed in the fragment shader, so they apply to quads built by
+    /// the primitives module, including rounded corners, ellipses and polygon shapes.
+    pub fn with_border(mut self, width: f32, color: Vec4, alignment: BorderAlignment) -> Self {
+        self.set_border(width, color, alignment);
+        self
+    }
+
+    /// Updates the border properties of the object.
+    pub fn set_border(&mut self, width: f32, color: Vec4, alignment: BorderAlignment) {
+        self.border_width = width;
+        self.border_color = color;
+        self.border_alignment = alignment;
+    }
+
+    /// Dashes the border with the given dash and gap lengths.
+    pub fn with_border_dash(mut self, length: f32, gap: f32) -> Self {
+        self.border_dash = Some(BorderDash::new(length, gap));
+        self
+    }
+
+    /// Sets the dash pattern of the border, or makes it solid with `None`.
+    pub fn set_border_dash(&mut self, dash: Option<BorderDash>) {
+        self.border_dash = dash;
+    }
+
+    /// Removes the border from the object.
+    pub fn remove_border(&mut self) {
+        self.border_width = 0.0;
+        self.border_dash = None;
+    }
+
+    /// Shows the given cursor while the mouse is over the object.
+    pub fn with_cursor(mut self, cursor: CursorIcon) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /// Sets the cursor shown over the object, or the default cursor with `None`.
+    pub fn set_cursor(&mut self, cursor: Option<CursorIcon>) {
+        self.cursor = cursor;
+    }
+
+    /// Draws the object with a custom fragment shader.
+    pub fn with_material(mut self, material: Material) -> Self {
+        self.material = Some(material);
+        self
+    }
+
+    /// Sets the fragment shader of the object, or the default one with `None`.
+    pub fn set_material(&mut self, material: Option<Material>) {
+        self.material = material;
+    }
+
+    /// Tags the object, so it can be found among the renderer's objects.
+    pub fn with_tag(mut self, tag: &'static str) -> Self {
+        self.tag = Some(tag);
+        self
+    }
+
+    /// Sets the tag of the object, or removes it with `None`.
+    pub fn set_tag(&mut self, tag: Option<&'static str>) {
+        self.tag = tag;
+    }
+
+    /// Returns whether a point in window pixels falls inside the bounds of the object.
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        if self.vertices.is_empty() || self.scale.x == 0.0 || self.scale.y == 0.0 {
+            return false;
+        }
+        // Undo the model transform so the point can be compared to the vertices.
+        let (sin, cos) = (-self.rotation).sin_cos();
+        let offset = point - self.position;
+        let local = Vec2::new(
+            (offset.x * cos - offset.y * sin) / self.scale.x,
+            (offset.x * sin + offset.y * cos) / self.scale.y,
+        );
+
+        let (mut min, mut max) = (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY));
+        for vertex in &self.vertices {
+            min = min.min(vertex.position);
+            max = max.max(vertex.position);
+        }
+        local.x >= min.x && local.x <= max.x && local.y >= min.y && local.y <= max.y
+    }
 }
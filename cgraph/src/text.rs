@@ -1,6 +1,5 @@
-use std::error::Error;
-
 use cfont::font::{
+    error::FontError,
     load::get_system_font_with_style,
     shape::{TextTransform, produce_styled_text_with_family_name, produce_text},
     style::{FontWeight, TextStyle},
@@ -111,7 +110,7 @@ impl TextStyleBuilder {
     }
 
     /// Builds the styled font for use with the text rendering system.
-    pub fn build_font(self, window: &Window) -> Result<StyledFont, Box<dyn Error>> {
+    pub fn build_font(self, window: &Window) -> Result<StyledFont, FontError> {
         let core_font = get_system_font_with_style(&self.font_family, &self.style)?;
         let transform = TextTransform {
             font_size: self.font_size,
@@ -139,7 +138,7 @@ pub struct StyledFont {
 }
 
 /// Retrieves a system font and creates a `Font` instance with the specified name and size.
-pub fn get_font(window: &Window, name: &str, size: f32) -> Result<Font, Box<dyn Error>> {
+pub fn get_font(window: &Window, name: &str, size: f32) -> Result<Font, FontError> {
     let font = cfont::font::load::get_system_font(name)?;
     let transform = TextTransform {
         font_size: size,
@@ -160,11 +159,10 @@ pub fn make_text(
     color: Color,
     z_index: f32,
     position: Vec2,
-) -> Result<Object, Box<dyn Error>> {
+) -> Result<Object, FontError> {
     let mut result = produce_text(font.core_font, text)?;
 
-    let font_units_per_em = 1000.0;
-    result.transform_to_canvas(font.transform, font_units_per_em);
+    result.transform_to_canvas(font.transform);
 
     let mut indices: Vec<u32> = vec![];
     for index in result.indices {
@@ -201,7 +199,7 @@ pub fn make_styled_text(
     color: Color,
     z_index: f32,
     position: Vec2,
-) -> Result<Object, Box<dyn Error>> {
+) -> Result<Object, FontError> {
     let mut result = produce_styled_text_with_family_name(
         styled_font.core_font,
         text,
@@ -209,8 +207,7 @@ pub fn make_styled_text(
         &styled_font.font_family,
     )?;
 
-    let font_units_per_em = 1000.0;
-    result.transform_to_canvas(styled_font.transform, font_units_per_em);
+    result.transform_to_canvas(styled_font.transform);
 
     let mut indices: Vec<u32> = vec![];
     for index in result.indices {
@@ -251,7 +248,7 @@ pub fn make_bold_text(
     color: Color,
     z_index: f32,
     position: Vec2,
-) -> Result<Object, Box<dyn Error>> {
+) -> Result<Object, FontError> {
     let styled_font = TextStyleBuilder::new(font_family, font_size)
         .bold()
         .build_font(window)?;
@@ -268,7 +265,7 @@ pub fn make_italic_text(
     color: Color,
     z_index: f32,
     position: Vec2,
-) -> Result<Object, Box<dyn Error>> {
+) -> Result<Object, FontError> {
     let styled_font = TextStyleBuilder::new(font_family, font_size)
         .italic()
         .build_font(window)?;
@@ -285,7 +282,7 @@ pub fn make_underlined_text(
     color: Color,
     z_index: f32,
     position: Vec2,
-) -> Result<Object, Box<dyn Error>> {
+) -> Result<Object, FontError> {
     let styled_font = TextStyleBuilder::new(font_family, font_size)
         .underlined()
         .build_font(window)?;
@@ -302,7 +299,7 @@ pub fn make_bold_italic_text(
     color: Color,
     z_index: f32,
     position: Vec2,
-) -> Result<Object, Box<dyn Error>> {
+) -> Result<Object, FontError> {
     let styled_font = TextStyleBuilder::new(font_family, font_size)
         .bold()
         .italic()
@@ -326,7 +323,7 @@ pub fn make_weighted_text(
     color: Color,
     z_index: f32,
     position: Vec2,
-) -> Result<Object, Box<dyn Error>> {
+) -> Result<Object, FontError> {
     let styled_font = TextStyleBuilder::new(font.family, font.size)
         .weight(weight)
         .build_font(window)?;
@@ -357,7 +354,7 @@ pub fn make_custom_text(
     color: Color,
     z_index: f32,
     position: Vec2,
-) -> Result<Object, Box<dyn Error>> {
+) -> Result<Object, FontError> {
     let styled_font = style_builder.build_font(window)?;
     make_styled_text(styled_font, text, color, z_index, position)
 }
@@ -0,0 +1,220 @@
+use cgraph::object::{
+    Object,
+    curve::{Curve, CurveEval, DEFAULT_TOLERANCE, Flattening, Path, Point},
+};
+use glam::{Vec2, Vec4};
+
+fn point(x: f32, y: f32) -> Point {
+    Point { x, y }
+}
+
+fn curves() -> Vec<Curve> {
+    vec![
+        Curve::Quadratic {
+            p0: point(0.0, 0.0),
+            p1: point(50.0, 120.0),
+            p2: point(100.0, 0.0),
+        },
+        Curve::Cubic {
+            p0: point(0.0, 0.0),
+            p1: point(100.0, 200.0),
+            p2: point(200.0, -200.0),
+            p3: point(300.0, 0.0),
+        },
+        Curve::Arc {
+            center: point(10.0, -20.0),
+            radius: 80.0,
+            start_angle: 0.0,
+            end_angle: 5.0,
+        },
+        Curve::CatmullRom {
+            points: vec![
+                point(0.0, 0.0),
+                point(40.0, 60.0),
+                point(90.0, -30.0),
+                point(150.0, 50.0),
+                point(200.0, 0.0),
+            ],
+            tension: 0.5,
+        },
+    ]
+}
+
+fn distance_to_segment(p: Point, a: Point, b: Point) -> f32 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length = dx * dx + dy * dy;
+    let t = if length > 0.0 {
+        (((p.x - a.x) * dx + (p.y - a.y) * dy) / length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (x, y) = (a.x + t * dx - p.x, a.y + t * dy - p.y);
+    (x * x + y * y).sqrt()
+}
+
+/// Returns the largest distance between the curve and its flattened points.
+fn max_deviation(curve: &Curve, points: &[Point]) -> f32 {
+    // The points are spaced evenly in `t`, so each segment covers an equal part of the curve.
+    let segments = points.len() - 1;
+    let mut deviation = 0.0f32;
+    for (index, segment) in points.windows(2).enumerate() {
+        for sample in 1..16 {
+            let t = (index as f32 + sample as f32 / 16.0) / segments as f32;
+            let distance = distance_to_segment(curve.evaluate(t), segment[0], segment[1]);
+            deviation = deviation.max(distance);
+        }
+    }
+    deviation
+}
+
+#[test]
+fn segments_grow_as_tolerance_shrinks() {
+    for (index, curve) in curves().iter().enumerate() {
+        let counts: Vec<usize> = [4.0, 1.0, 0.25, 0.05, 0.01]
+            .into_iter()
+            .map(|tolerance| curve.segment_count(tolerance))
+            .collect();
+        assert!(
+            counts.windows(2).all(|pair| pair[0] <= pair[1]),
+            "curve {index}: {counts:?}"
+        );
+        assert!(counts[0] < counts[4], "curve {index}: {counts:?}");
+    }
+}
+
+#[test]
+fn segments_grow_with_scale() {
+    for (index, curve) in curves().iter().enumerate() {
+        let counts: Vec<usize> = [0.5, 1.0, 4.0, 16.0]
+            .into_iter()
+            .map(|scale| {
+                curve
+                    .flatten(&Flattening::default().with_scale(scale))
+                    .len()
+            })
+            .collect();
+        assert!(
+            counts.windows(2).all(|pair| pair[0] <= pair[1]),
+            "curve {index}: {counts:?}"
+        );
+        assert!(counts[0] < counts[3], "curve {index}: {counts:?}");
+
+        // A transform scale counts its largest axis, whatever its sign.
+        let transformed = Flattening::default().with_transform_scale(Vec2::new(1.0, -4.0));
+        assert_eq!(
+            curve.flatten(&transformed).len(),
+            counts[2],
+            "curve {index}"
+        );
+    }
+
+    let flattening = Flattening::new(0.5).with_scale(2.0);
+    assert_eq!(flattening.effective_tolerance(), 0.25);
+    // A tolerance or scale that isn't positive falls back to the defaults.
+    assert_eq!(
+        Flattening::new(0.0).effective_tolerance(),
+        DEFAULT_TOLERANCE
+    );
+    assert_eq!(
+        Flattening::new(0.5).with_scale(0.0).effective_tolerance(),
+        0.5
+    );
+}
+
+#[test]
+fn small_curves_use_few_segments() {
+    let line = Curve::Quadratic {
+        p0: point(0.0, 0.0),
+        p1: point(50.0, 0.0),
+        p2: point(100.0, 0.0),
+    };
+    assert_eq!(line.flatten(&Flattening::default()).len(), 2);
+
+    let dot = Curve::Arc {
+        center: point(0.0, 0.0),
+        radius: 0.1,
+        start_angle: 0.0,
+        end_angle: 6.0,
+    };
+    assert_eq!(dot.segment_count(DEFAULT_TOLERANCE), 1);
+
+    // Huge curves are capped rather than flattened into millions of segments.
+    let huge = Curve::Arc {
+        center: point(0.0, 0.0),
+        radius: 1.0e6,
+        start_angle: 0.0,
+        end_angle: 6.0,
+    };
+    assert_eq!(huge.segment_count(0.001), 1024);
+}
+
+#[test]
+fn explicit_steps_override_tolerance() {
+    for curve in curves() {
+        let points = curve.flatten(&Flattening::new(0.001).with_steps(Some(3)));
+        assert_eq!(points.len(), 4);
+        assert_eq!(curve.flatten(&Flattening::fixed(7)).len(), 8);
+        assert_eq!(curve.flatten(&Flattening::from(5)).len(), 6);
+        // Scaling doesn't change a fixed number of steps.
+        assert_eq!(
+            curve.flatten(&Flattening::fixed(7).with_scale(100.0)).len(),
+            8
+        );
+        // Without steps, the tolerance decides.
+        assert_eq!(
+            curve.flatten(&Flattening::from(None)).len(),
+            curve.flatten(&Flattening::default()).len()
+        );
+    }
+}
+
+#[test]
+fn flattened_points_stay_within_tolerance() {
+    for (index, curve) in curves().iter().enumerate() {
+        for tolerance in [1.0, 0.25, 0.05] {
+            for scale in [1.0, 3.0] {
+                let flattening = Flattening::new(tolerance).with_scale(scale);
+                let points = curve.flatten(&flattening);
+                let start = curve.start_point();
+                let end = curve.end_point();
+                assert_eq!((points[0].x, points[0].y), (start.x, start.y));
+                let last = points[points.len() - 1];
+                assert_eq!((last.x, last.y), (end.x, end.y));
+
+                let deviation = max_deviation(curve, &points);
+                let allowed = flattening.effective_tolerance() * 1.01 + 1.0e-4;
+                assert!(
+                    deviation <= allowed,
+                    "curve {index} at tolerance {tolerance} and scale {scale}: \
+                     {deviation} > {allowed} with {} points",
+                    points.len()
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn update_object_follows_the_object_scale() {
+    let path = Path {
+        curves: curves().into_iter().take(2).collect(),
+    };
+    let color = Vec4::new(1.0, 1.0, 1.0, 1.0);
+    let mut object = path.to_object(Flattening::default(), color, 0.0, 2.0);
+    let unscaled = object.vertices.len();
+
+    object.scale = Vec2::new(8.0, 8.0);
+    path.update_object(&mut object, Flattening::default(), color, 0.0, 2.0);
+    let scaled = object.vertices.len();
+    assert!(scaled > unscaled, "{scaled} <= {unscaled}");
+    assert_eq!(object.buffer.data.len(), scaled);
+    assert_eq!(object.index_buffer.data.len(), object.indices.len());
+
+    // Fixed steps ignore the scale.
+    let mut fixed = Object::new(Vec::new(), Vec::new());
+    path.update_object(&mut fixed, Flattening::fixed(4), color, 0.0, 2.0);
+    let unscaled = fixed.vertices.len();
+    fixed.scale = Vec2::new(8.0, 8.0);
+    path.update_object(&mut fixed, Flattening::fixed(4), color, 0.0, 2.0);
+    assert_eq!(fixed.vertices.len(), unscaled);
+}
@@ -0,0 +1,154 @@
+use cgraph::app::{ChordParseError, KeyChord, primary_modifier};
+use winit::event::{ModifiersState, VirtualKeyCode};
+
+fn chord(text: &str) -> KeyChord {
+    text.parse()
+        .unwrap_or_else(|error| panic!("Cannot parse `{text}`: {error}"))
+}
+
+fn logo_name() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "Cmd"
+    } else {
+        "Super"
+    }
+}
+
+#[test]
+fn parse_chords() {
+    assert_eq!(
+        chord("Cmd+Shift+P"),
+        KeyChord::new(
+            ModifiersState::LOGO | ModifiersState::SHIFT,
+            VirtualKeyCode::P
+        )
+    );
+    assert_eq!(
+        chord("Escape"),
+        KeyChord::new(ModifiersState::empty(), VirtualKeyCode::Escape)
+    );
+    assert_eq!(
+        chord(" Ctrl + Alt + Delete "),
+        KeyChord::new(
+            ModifiersState::CTRL | ModifiersState::ALT,
+            VirtualKeyCode::Delete
+        )
+    );
+}
+
+#[test]
+fn parse_is_case_insensitive() {
+    assert_eq!(chord("cmd+shift+p"), chord("Cmd+Shift+P"));
+    assert_eq!(chord("CTRL+PAGEDOWN"), chord("Ctrl+PageDown"));
+}
+
+#[test]
+fn parse_aliases() {
+    assert_eq!(chord("Command+K"), chord("Cmd+K"));
+    assert_eq!(chord("Super+K"), chord("Cmd+K"));
+    assert_eq!(chord("Meta+K"), chord("Cmd+K"));
+    assert_eq!(chord("Control+K"), chord("Ctrl+K"));
+    assert_eq!(chord("Option+K"), chord("Alt+K"));
+    assert_eq!(chord("Opt+K"), chord("Alt+K"));
+    assert_eq!(chord("Esc"), chord("Escape"));
+    assert_eq!(chord("Return"), chord("Enter"));
+    assert_eq!(chord("Del"), chord("Delete"));
+    assert_eq!(chord("Ctrl+Minus"), chord("Ctrl+-"));
+    assert_eq!(chord("Quote"), chord("'"));
+    // Keys can also be named by their `VirtualKeyCode` variant.
+    assert_eq!(chord("Numpad5").key, VirtualKeyCode::Numpad5);
+    assert_eq!(chord("Back"), chord("Backspace"));
+
+    let primary = primary_modifier();
+    for name in ["CmdOrCtrl", "Primary", "Mod"] {
+        assert_eq!(chord(&format!("{name}+S")).modifiers, primary);
+    }
+}
+
+#[test]
+fn parse_plus_key() {
+    let plus = KeyChord::new(ModifiersState::CTRL, VirtualKeyCode::Plus);
+    assert_eq!(chord("Ctrl++"), plus);
+    assert_eq!(chord("Ctrl+Plus"), plus);
+    assert_eq!(chord("+").key, VirtualKeyCode::Plus);
+}
+
+#[test]
+fn modifier_order_does_not_matter() {
+    assert_eq!(chord("Shift+Alt+Ctrl+X"), chord("Ctrl+Alt+Shift+X"));
+    assert_eq!(chord("X+Shift"), chord("Shift+X"));
+    // Repeating a modifier is harmless.
+    assert_eq!(chord("Shift+Shift+X"), chord("Shift+X"));
+}
+
+#[test]
+fn display_chords() {
+    // Modifiers are written in the order macOS menus use, and keys by their first name.
+    assert_eq!(
+        chord("shift+cmd+alt+ctrl+p").to_string(),
+        format!("Ctrl+Alt+Shift+{}+P", logo_name())
+    );
+    assert_eq!(chord("esc").to_string(), "Escape");
+    assert_eq!(chord("Ctrl+Return").to_string(), "Ctrl+Enter");
+    assert_eq!(chord("Alt+Minus").to_string(), "Alt+-");
+    assert_eq!(chord("Numpad5").to_string(), "Numpad5");
+}
+
+#[test]
+fn display_round_trips() {
+    for text in [
+        "Ctrl+Alt+Shift+Cmd+P",
+        "Cmd+Shift+P",
+        "Ctrl+Plus",
+        "Ctrl+=",
+        "Shift+/",
+        "Alt+`",
+        "F12",
+        "Ctrl+Backspace",
+        "Shift+Tab",
+        "Ctrl+[",
+        "Numpad5",
+        "MediaSelect",
+    ] {
+        let parsed = chord(text);
+        assert_eq!(chord(&parsed.to_string()), parsed, "`{text}`");
+    }
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!("".parse::<KeyChord>(), Err(ChordParseError::MissingKey));
+    assert_eq!(
+        "Ctrl+".parse::<KeyChord>(),
+        Err(ChordParseError::MissingKey)
+    );
+    assert_eq!(
+        "Ctrl+Shift".parse::<KeyChord>(),
+        Err(ChordParseError::MissingKey)
+    );
+    assert_eq!(
+        "Ctrl++Shift".parse::<KeyChord>(),
+        Err(ChordParseError::MissingKey)
+    );
+    assert_eq!(
+        "Ctrl+Hyper".parse::<KeyChord>(),
+        Err(ChordParseError::UnknownKey("Hyper".to_string()))
+    );
+    assert_eq!(
+        "Ctrl+A+B".parse::<KeyChord>(),
+        Err(ChordParseError::MultipleKeys("Ctrl+A+B".to_string()))
+    );
+
+    assert_eq!(
+        ChordParseError::MissingKey.to_string(),
+        "Key chord has no key"
+    );
+    assert_eq!(
+        ChordParseError::UnknownKey("Hyper".to_string()).to_string(),
+        "Unknown key `Hyper`"
+    );
+    assert_eq!(
+        ChordParseError::MultipleKeys("A+B".to_string()).to_string(),
+        "Key chord `A+B` has more than one key"
+    );
+}
@@ -0,0 +1,372 @@
+use cgraph::app::{
+    BindingSource, ChordParseError, ConflictKind, KeyChord, KeyContext, KeyResolution, KeyResolver,
+    Keymap, KeymapError, When,
+};
+
+fn when(condition: &str) -> When {
+    condition
+        .parse()
+        .unwrap_or_else(|error| panic!("Cannot parse `{condition}`: {error}"))
+}
+
+fn key(name: &str) -> When {
+    When::Key(name.to_string())
+}
+
+fn chord(text: &str) -> KeyChord {
+    text.parse().unwrap()
+}
+
+fn context(keys: &[&str]) -> KeyContext {
+    let mut context = KeyContext::new();
+    for key in keys {
+        context.set(key, true);
+    }
+    context
+}
+
+fn commands(keymap: &Keymap) -> Vec<(String, &str)> {
+    keymap
+        .bindings()
+        .iter()
+        .map(|binding| (binding.sequence.to_string(), binding.command.as_str()))
+        .collect()
+}
+
+#[test]
+fn condition_precedence() {
+    // `!` binds tighter than `&&`, which binds tighter than `||`.
+    assert_eq!(
+        when("a || b && !c"),
+        When::Or(
+            Box::new(key("a")),
+            Box::new(When::And(
+                Box::new(key("b")),
+                Box::new(When::Not(Box::new(key("c"))))
+            ))
+        )
+    );
+    assert_eq!(
+        when("!a && b || c"),
+        When::Or(
+            Box::new(When::And(
+                Box::new(When::Not(Box::new(key("a")))),
+                Box::new(key("b"))
+            )),
+            Box::new(key("c"))
+        )
+    );
+    assert_eq!(
+        when("(a || b) && c"),
+        When::And(
+            Box::new(When::Or(Box::new(key("a")), Box::new(key("b")))),
+            Box::new(key("c"))
+        )
+    );
+
+    let condition = when("editorFocus && !readOnly || inputFocus");
+    assert!(condition.eval(&context(&["editorFocus"])));
+    assert!(!condition.eval(&context(&["editorFocus", "readOnly"])));
+    assert!(condition.eval(&context(&["readOnly", "inputFocus"])));
+}
+
+#[test]
+fn condition_display() {
+    // Parentheses are only written where the precedence needs them.
+    for (condition, written) in [
+        ("a", "a"),
+        ("a&&b", "a && b"),
+        ("(a && b) || c", "a && b || c"),
+        ("(a || b) && c", "(a || b) && c"),
+        ("a && (b || c)", "a && (b || c)"),
+        ("!(a || b)", "!(a || b)"),
+        ("!(a && b)", "!(a && b)"),
+        ("!!a", "!!a"),
+        ("(!a)", "!a"),
+        ("editor.focus && view:main-1", "editor.focus && view:main-1"),
+    ] {
+        let parsed = when(condition);
+        assert_eq!(parsed.to_string(), written, "`{condition}`");
+        assert_eq!(when(written), parsed, "`{written}`");
+    }
+}
+
+#[test]
+fn condition_errors() {
+    for (condition, message) in [
+        ("", "missing context key"),
+        ("a &&", "missing context key"),
+        ("a & b", "expected `&&` in `a & b`"),
+        ("a | b", "expected `||` in `a | b`"),
+        ("(a || b", "missing `)`"),
+        ("a b", "unexpected `b` in `a b`"),
+        ("a && || b", "unexpected `||`"),
+        ("a == b", "unexpected `=` in `a == b`"),
+    ] {
+        assert_eq!(
+            condition.parse::<When>(),
+            Err(KeymapError::Condition(message.to_string())),
+            "`{condition}`"
+        );
+    }
+}
+
+#[test]
+fn parse_keymap() {
+    let keymap = Keymap::parse(
+        "# Comments and blank lines are skipped
+
+        ctrl+s = editor.save when editorFocus && !readOnly
+        ctrl+k ctrl+s=keymap.open
+        ",
+        BindingSource::Default,
+    )
+    .unwrap();
+    let bindings = keymap.bindings();
+    assert_eq!(bindings.len(), 2);
+    assert_eq!(bindings[0].sequence.to_string(), "Ctrl+S");
+    assert_eq!(bindings[0].command, "editor.save");
+    assert_eq!(bindings[0].when, Some(when("editorFocus && !readOnly")));
+    assert_eq!(bindings[0].source, BindingSource::Default);
+    assert_eq!(bindings[1].sequence.to_string(), "Ctrl+K Ctrl+S");
+    assert_eq!(bindings[1].command, "keymap.open");
+    assert_eq!(bindings[1].when, None);
+}
+
+#[test]
+fn platform_sections() {
+    let keymap = Keymap::parse(
+        "ctrl+a = everywhere
+        [macos]
+        cmd+b = macos
+        [linux]
+        ctrl+b = linux
+        [windows]
+        ctrl+b = windows
+        [all]
+        ctrl+c = everywhere.again
+        [plan9]
+        ctrl+d = plan9",
+        BindingSource::Default,
+    )
+    .unwrap();
+    let names: Vec<&str> = keymap
+        .bindings()
+        .iter()
+        .map(|binding| binding.command.as_str())
+        .collect();
+    let mut expected = vec!["everywhere"];
+    if ["macos", "linux", "windows"].contains(&std::env::consts::OS) {
+        expected.push(std::env::consts::OS);
+    }
+    expected.push("everywhere.again");
+    assert_eq!(names, expected);
+}
+
+#[test]
+fn parse_errors_report_the_line() {
+    let text = "# A comment
+        ctrl+s = editor.save
+
+        ctrl+q";
+    assert_eq!(
+        Keymap::parse(text, BindingSource::User).unwrap_err(),
+        KeymapError::Line(4, Box::new(KeymapError::Syntax("ctrl+q".to_string())))
+    );
+
+    assert_eq!(
+        Keymap::parse("ctrl+hyper = editor.save", BindingSource::User).unwrap_err(),
+        KeymapError::Line(
+            1,
+            Box::new(KeymapError::Chord(ChordParseError::UnknownKey(
+                "hyper".to_string()
+            )))
+        )
+    );
+    assert_eq!(
+        Keymap::parse("ctrl+s =", BindingSource::User).unwrap_err(),
+        KeymapError::Line(1, Box::new(KeymapError::Syntax("ctrl+s =".to_string())))
+    );
+    // Lines of other platforms are skipped before they are parsed.
+    let error = Keymap::parse("[plan9]\nnonsense\n[all]\n= run", BindingSource::User).unwrap_err();
+    assert_eq!(
+        error,
+        KeymapError::Line(4, Box::new(KeymapError::Chord(ChordParseError::MissingKey)))
+    );
+    assert_eq!(error.to_string(), "Line 4: Key chord has no key");
+}
+
+#[test]
+fn user_keymap_overrides_defaults() {
+    let mut keymap = Keymap::parse(
+        "ctrl+s = editor.save
+        ctrl+shift+s = editor.save
+        ctrl+w = tab.close
+        ctrl+p = palette.open
+        ctrl+n = file.new",
+        BindingSource::Default,
+    )
+    .unwrap();
+    let user = Keymap::parse(
+        "# Unbind one sequence of a command, then every sequence of another
+        ctrl+shift+s = -editor.save
+        = -tab.close
+        ctrl+p = file.open",
+        BindingSource::User,
+    )
+    .unwrap();
+    // Removals are kept aside until the keymap is applied over the defaults.
+    assert_eq!(user.bindings().len(), 1);
+
+    keymap.extend(user);
+    assert_eq!(
+        commands(&keymap),
+        vec![
+            ("Ctrl+S".to_string(), "editor.save"),
+            ("Ctrl+P".to_string(), "palette.open"),
+            ("Ctrl+N".to_string(), "file.new"),
+            ("Ctrl+P".to_string(), "file.open"),
+        ]
+    );
+    assert_eq!(keymap.bindings()[3].source, BindingSource::User);
+    assert_eq!(keymap.sequence_for("tab.close"), None);
+
+    // The later binding wins.
+    let mut resolver = KeyResolver::new();
+    assert_eq!(
+        resolver.feed(&keymap, chord("ctrl+p"), &KeyContext::new()),
+        KeyResolution::Command("file.open".to_string())
+    );
+}
+
+#[test]
+fn bind_and_unbind() {
+    let mut keymap = Keymap::new();
+    keymap.bind("ctrl+s", "editor.save", None).unwrap();
+    keymap
+        .bind("ctrl+shift+s", "editor.save", Some("editorFocus"))
+        .unwrap();
+    keymap.bind("ctrl+w", "tab.close", None).unwrap();
+    assert_eq!(
+        keymap.sequence_for("editor.save").unwrap().to_string(),
+        "Ctrl+Shift+S"
+    );
+    assert_eq!(
+        keymap.bind("ctrl+x", "cut", Some("a &&")),
+        Err(KeymapError::Condition("missing context key".to_string()))
+    );
+
+    keymap.unbind("editor.save");
+    assert_eq!(commands(&keymap), vec![("Ctrl+W".to_string(), "tab.close")]);
+}
+
+#[test]
+fn conflicts() {
+    let keymap = Keymap::parse(
+        "ctrl+s = editor.save
+        ctrl+s = file.save
+        ctrl+s = file.save
+        ctrl+k = view.split
+        ctrl+k ctrl+s = keymap.open
+        ctrl+s = editor.save when editorFocus
+        ctrl+k ctrl+z = zen.toggle when editorFocus",
+        BindingSource::Default,
+    )
+    .unwrap();
+    let conflicts = keymap.conflicts();
+    let found: Vec<(ConflictKind, &str, &str)> = conflicts
+        .iter()
+        .map(|conflict| {
+            (
+                conflict.kind,
+                conflict.winner.command.as_str(),
+                conflict.shadowed.command.as_str(),
+            )
+        })
+        .collect();
+    // Rebinding the same command isn't a conflict, nor are bindings with different conditions.
+    assert_eq!(
+        found,
+        vec![
+            (ConflictKind::Duplicate, "file.save", "editor.save"),
+            (ConflictKind::Duplicate, "file.save", "editor.save"),
+            (ConflictKind::Prefix, "view.split", "keymap.open"),
+        ]
+    );
+    assert_eq!(
+        conflicts[0].to_string(),
+        "`Ctrl+S` runs `file.save`, shadowing `editor.save`"
+    );
+    assert_eq!(
+        conflicts[2].to_string(),
+        "`Ctrl+K` runs `view.split`, so `Ctrl+K Ctrl+S` for `keymap.open` is unreachable"
+    );
+
+    // A prefix defined after the longer binding is reported the same way.
+    let keymap = Keymap::parse(
+        "ctrl+k ctrl+s = keymap.open\nctrl+k = view.split",
+        BindingSource::Default,
+    )
+    .unwrap();
+    let conflicts = keymap.conflicts();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].kind, ConflictKind::Prefix);
+    assert_eq!(conflicts[0].winner.command, "view.split");
+    assert_eq!(conflicts[0].shadowed.command, "keymap.open");
+}
+
+#[test]
+fn resolve_sequences() {
+    let keymap = Keymap::parse(
+        "ctrl+k ctrl+s = keymap.open
+        ctrl+k ctrl+t = theme.pick
+        ctrl+s = editor.save when editorFocus",
+        BindingSource::Default,
+    )
+    .unwrap();
+    let mut resolver = KeyResolver::new();
+    let focused = context(&["editorFocus"]);
+
+    assert_eq!(
+        resolver.feed(&keymap, chord("ctrl+k"), &focused),
+        KeyResolution::Pending
+    );
+    assert_eq!(resolver.pending(), &[chord("ctrl+k")]);
+    assert_eq!(
+        resolver.feed(&keymap, chord("ctrl+t"), &focused),
+        KeyResolution::Command("theme.pick".to_string())
+    );
+    assert!(resolver.pending().is_empty());
+
+    // An unknown chord drops the sequence, so the next chord starts over.
+    assert_eq!(
+        resolver.feed(&keymap, chord("ctrl+k"), &focused),
+        KeyResolution::Pending
+    );
+    assert_eq!(
+        resolver.feed(&keymap, chord("x"), &focused),
+        KeyResolution::Unmatched
+    );
+    assert!(resolver.pending().is_empty());
+    assert_eq!(
+        resolver.feed(&keymap, chord("ctrl+s"), &focused),
+        KeyResolution::Command("editor.save".to_string())
+    );
+
+    // Bindings whose condition is false are ignored.
+    assert_eq!(
+        resolver.feed(&keymap, chord("ctrl+s"), &KeyContext::new()),
+        KeyResolution::Unmatched
+    );
+
+    assert_eq!(
+        resolver.feed(&keymap, chord("ctrl+k"), &focused),
+        KeyResolution::Pending
+    );
+    resolver.reset();
+    assert!(resolver.pending().is_empty());
+    assert_eq!(
+        resolver.feed(&keymap, chord("ctrl+s"), &focused),
+        KeyResolution::Command("editor.save".to_string())
+    );
+}
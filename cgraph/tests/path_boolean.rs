@@ -0,0 +1,142 @@
+use cgraph::object::curve::{FillRule, Flattening, Path, Point};
+
+fn square(x: f32, y: f32, size: f32) -> Path {
+    Path::from_svg_d(&format!("M{x} {y} h{size} v{size} h-{size} Z")).unwrap()
+}
+
+fn contour_count(path: &Path) -> usize {
+    path.contours(&Flattening::default()).len()
+}
+
+/// The area enclosed by the path, with holes subtracted.
+fn area(path: &Path) -> f32 {
+    let signed: f32 = path
+        .contours(&Flattening::default())
+        .iter()
+        .map(|contour| {
+            let mut sum = 0.0;
+            for (i, a) in contour.iter().enumerate() {
+                let b = contour[(i + 1) % contour.len()];
+                sum += a.x * b.y - b.x * a.y;
+            }
+            sum / 2.0
+        })
+        .sum();
+    signed.abs()
+}
+
+fn assert_area(path: &Path, expected: f32) {
+    let actual = area(path);
+    assert!(
+        (actual - expected).abs() < 1e-2,
+        "expected an area of {expected}, got {actual}"
+    );
+}
+
+fn contains(path: &Path, x: f32, y: f32) -> bool {
+    path.contains(Point { x, y }, FillRule::NonZero, &Flattening::default())
+}
+
+#[test]
+fn overlapping_squares() {
+    let a = square(0.0, 0.0, 10.0);
+    let b = square(5.0, 5.0, 10.0);
+
+    let union = a.union(&b);
+    assert_area(&union, 175.0);
+    assert_eq!(contour_count(&union), 1);
+    assert_eq!(union.curves.len(), 8);
+
+    let intersection = a.intersection(&b);
+    assert_area(&intersection, 25.0);
+    assert!(contains(&intersection, 7.5, 7.5));
+    assert!(!contains(&intersection, 2.5, 2.5));
+
+    let difference = a.difference(&b);
+    assert_area(&difference, 75.0);
+    assert!(contains(&difference, 2.5, 2.5));
+    assert!(!contains(&difference, 7.5, 7.5));
+
+    let xor = a.xor(&b);
+    assert_area(&xor, 150.0);
+    assert!(!contains(&xor, 7.5, 7.5));
+    assert!(contains(&xor, 12.5, 12.5));
+}
+
+#[test]
+fn disjoint_shapes() {
+    let a = square(0.0, 0.0, 10.0);
+    let b = square(20.0, 0.0, 10.0);
+
+    let union = a.union(&b);
+    assert_area(&union, 200.0);
+    assert_eq!(contour_count(&union), 2);
+
+    assert!(a.intersection(&b).curves.is_empty());
+    assert_area(&a.difference(&b), 100.0);
+    assert_eq!(contour_count(&a.difference(&b)), 1);
+    assert_area(&a.xor(&b), 200.0);
+}
+
+#[test]
+fn nested_shapes() {
+    let outer = square(0.0, 0.0, 10.0);
+    let inner = square(2.0, 2.0, 2.0);
+
+    let union = outer.union(&inner);
+    assert_area(&union, 100.0);
+    assert_eq!(contour_count(&union), 1);
+
+    let intersection = outer.intersection(&inner);
+    assert_area(&intersection, 4.0);
+    assert_eq!(contour_count(&intersection), 1);
+
+    // The inner square becomes a hole, wound the other way round.
+    let difference = outer.difference(&inner);
+    assert_area(&difference, 96.0);
+    assert_eq!(contour_count(&difference), 2);
+    assert!(!contains(&difference, 3.0, 3.0));
+    assert!(contains(&difference, 8.0, 8.0));
+
+    assert!(inner.difference(&outer).curves.is_empty());
+    assert_area(&outer.xor(&inner), 96.0);
+}
+
+#[test]
+fn shapes_sharing_an_edge() {
+    let a = square(0.0, 0.0, 10.0);
+    let b = square(10.0, 0.0, 10.0);
+
+    // The shared edge disappears and the collinear edges merge into a rectangle.
+    let union = a.union(&b);
+    assert_area(&union, 200.0);
+    assert_eq!(contour_count(&union), 1);
+    assert_eq!(union.curves.len(), 4);
+
+    assert!(a.intersection(&b).curves.is_empty());
+    assert_area(&a.difference(&b), 100.0);
+    assert_area(&a.xor(&b), 200.0);
+}
+
+#[test]
+fn shapes_sharing_part_of_an_edge() {
+    let a = square(0.0, 0.0, 10.0);
+    let b = square(10.0, 5.0, 10.0);
+
+    let union = a.union(&b);
+    assert_area(&union, 200.0);
+    assert_eq!(contour_count(&union), 1);
+    assert!(a.intersection(&b).curves.is_empty());
+}
+
+#[test]
+fn results_are_deterministic() {
+    let a = square(0.0, 0.0, 10.0);
+    let b = Path::from_svg_d("M5 -5 L15 5 L5 15 L-5 5 Z").unwrap();
+
+    let expected = a.union(&b).to_svg_d();
+    for _ in 0..10 {
+        assert_eq!(a.union(&b).to_svg_d(), expected);
+        assert_eq!(a.xor(&b).to_svg_d(), a.xor(&b).to_svg_d());
+    }
+}
@@ -0,0 +1,264 @@
+use std::time::Duration;
+
+use cgraph::app::{
+    CoreDeviceEvent, CoreEvent, CoreEventReference, CoreWindowEvent, Propagation,
+    RecordedDeviceEvent, RecordedEvent, RecordedWindowEvent, Recording, RecordingError, ReplayEnd,
+    Window,
+};
+use winit::{
+    dpi::PhysicalPosition,
+    event::{
+        ElementState, Ime, ModifiersState, MouseButton, MouseScrollDelta, TouchPhase,
+        VirtualKeyCode,
+    },
+    window::Theme,
+};
+
+fn recording(events: Vec<RecordedEvent>) -> Recording {
+    let mut recording = Recording::new();
+    for (index, event) in events.into_iter().enumerate() {
+        recording.push(Duration::from_millis(index as u64 * 16), event);
+    }
+    recording
+}
+
+fn assert_round_trip(recording: &Recording) {
+    let text = recording.to_string();
+    let parsed = Recording::parse(&text).unwrap_or_else(|error| panic!("{error}\n{text}"));
+    assert_eq!(&parsed, recording, "{text}");
+}
+
+#[test]
+fn window_events_round_trip() {
+    let events = vec![
+        RecordedWindowEvent::Resized(800, 600),
+        RecordedWindowEvent::Moved(-20, 40),
+        RecordedWindowEvent::Closing,
+        RecordedWindowEvent::Destroyed,
+        RecordedWindowEvent::DroppedFile("/tmp/a file \"quoted\".txt".into()),
+        RecordedWindowEvent::HoveredFile("/tmp/tab\there".into()),
+        RecordedWindowEvent::HoveredFileCancelled,
+        RecordedWindowEvent::Focused(true),
+        RecordedWindowEvent::ReceivedChar(' '),
+        RecordedWindowEvent::ReceivedChar('é'),
+        RecordedWindowEvent::KeyboardInput {
+            scancode: 30,
+            state: ElementState::Pressed,
+            key: Some(VirtualKeyCode::Key1),
+        },
+        RecordedWindowEvent::KeyboardInput {
+            scancode: 74,
+            state: ElementState::Released,
+            key: Some(VirtualKeyCode::NumpadSubtract),
+        },
+        RecordedWindowEvent::KeyboardInput {
+            scancode: 0,
+            state: ElementState::Pressed,
+            key: None,
+        },
+        RecordedWindowEvent::Ime(Ime::Enabled),
+        RecordedWindowEvent::Ime(Ime::Preedit("かな".to_string(), Some((0, 3)))),
+        RecordedWindowEvent::Ime(Ime::Preedit(String::new(), None)),
+        RecordedWindowEvent::Ime(Ime::Commit("line\nbreak \\".to_string())),
+        RecordedWindowEvent::Ime(Ime::Disabled),
+        RecordedWindowEvent::ModifiersChanged(ModifiersState::SHIFT | ModifiersState::LOGO),
+        RecordedWindowEvent::ModifiersChanged(ModifiersState::empty()),
+        RecordedWindowEvent::CursorMoved(10.5, -3.25),
+        RecordedWindowEvent::CursorEntered,
+        RecordedWindowEvent::CursorLeft,
+        RecordedWindowEvent::MouseScroll(MouseScrollDelta::LineDelta(0.0, -1.5), TouchPhase::Moved),
+        RecordedWindowEvent::MouseScroll(
+            MouseScrollDelta::PixelDelta(PhysicalPosition::new(2.0, 8.5)),
+            TouchPhase::Ended,
+        ),
+        RecordedWindowEvent::MouseClick(MouseButton::Left, ElementState::Pressed),
+        RecordedWindowEvent::MouseClick(MouseButton::Other(4), ElementState::Released),
+        RecordedWindowEvent::TouchpadPressure(0.75, 2),
+        RecordedWindowEvent::AxisMotion(1, 0.125),
+        RecordedWindowEvent::Touch {
+            id: 7,
+            phase: TouchPhase::Cancelled,
+            x: 1.0,
+            y: 2.5,
+        },
+        RecordedWindowEvent::DPIChanged(2.0, 1600, 1200),
+        RecordedWindowEvent::ThemeChanged(Theme::Dark),
+        RecordedWindowEvent::ThemeChanged(Theme::Light),
+        RecordedWindowEvent::Occluded(false),
+    ];
+    assert_round_trip(&recording(
+        events.into_iter().map(RecordedEvent::Window).collect(),
+    ));
+}
+
+#[test]
+fn device_events_round_trip() {
+    let events = vec![
+        RecordedDeviceEvent::DeviceConnected,
+        RecordedDeviceEvent::DeviceDisconnected,
+        RecordedDeviceEvent::MouseMotion(-1.5, 3.0),
+        RecordedDeviceEvent::MouseWheel(MouseScrollDelta::LineDelta(1.0, 0.0)),
+        RecordedDeviceEvent::MouseWheel(MouseScrollDelta::PixelDelta(PhysicalPosition::new(
+            -4.0, 0.5,
+        ))),
+        RecordedDeviceEvent::Motion(3, -0.5),
+        RecordedDeviceEvent::Button(2, ElementState::Pressed),
+        RecordedDeviceEvent::Key {
+            scancode: 36,
+            state: ElementState::Released,
+            key: Some(VirtualKeyCode::Return),
+        },
+        RecordedDeviceEvent::Text('"'),
+    ];
+    assert_round_trip(&recording(
+        events.into_iter().map(RecordedEvent::Device).collect(),
+    ));
+}
+
+#[test]
+fn comments_and_blank_lines_are_skipped() {
+    let parsed = Recording::parse(
+        "# Recorded by hand\n\ncgraph-recording 1\n  # a comment\n16000 window cursor-moved 1 2\n\n",
+    )
+    .unwrap();
+    let mut expected = Recording::new();
+    expected.push(
+        Duration::from_millis(16),
+        RecordedEvent::Window(RecordedWindowEvent::CursorMoved(1.0, 2.0)),
+    );
+    assert_eq!(parsed, expected);
+}
+
+#[test]
+fn invalid_recordings() {
+    assert_eq!(Recording::parse(""), Err(RecordingError::MissingHeader));
+    assert_eq!(
+        Recording::parse("16000 window closing"),
+        Err(RecordingError::MissingHeader)
+    );
+    assert_eq!(
+        Recording::parse("cgraph-recording one"),
+        Err(RecordingError::MissingHeader)
+    );
+    assert_eq!(
+        Recording::parse("cgraph-recording 2\n0 window closing"),
+        Err(RecordingError::UnsupportedVersion(2))
+    );
+
+    let line_error = |text: &str| match Recording::parse(text) {
+        Err(RecordingError::Line(line, message)) => (line, message),
+        other => panic!("Expected a line error, found {other:?}"),
+    };
+    // Line numbers count the comments and blank lines that were skipped.
+    assert_eq!(
+        line_error("cgraph-recording 1\n# comment\n\n0 window bogus"),
+        (4, "Unknown window event `bogus`".to_string())
+    );
+    assert_eq!(
+        line_error("cgraph-recording 1\n0 window closing\nsoon window closing"),
+        (3, "Invalid time `soon`".to_string())
+    );
+    assert_eq!(
+        line_error("cgraph-recording 1\n0 mouse closing"),
+        (2, "Unknown event source `mouse`".to_string())
+    );
+    assert_eq!(
+        line_error("cgraph-recording 1\n0 window resized 800"),
+        (2, "Missing height".to_string())
+    );
+    assert_eq!(
+        line_error("cgraph-recording 1\n0 window closing now"),
+        (2, "Unexpected `now`".to_string())
+    );
+    assert_eq!(
+        line_error("cgraph-recording 1\n0 window key 30 pressed Hyper"),
+        (2, "Unknown key `Hyper`".to_string())
+    );
+    assert_eq!(
+        line_error("cgraph-recording 1\n0 window char \"ab\""),
+        (2, "Expected one character, found `ab`".to_string())
+    );
+    assert_eq!(
+        line_error("cgraph-recording 1\n0 window char \"a"),
+        (2, "Unterminated string".to_string())
+    );
+}
+
+#[derive(Default)]
+struct Seen {
+    events: Vec<String>,
+}
+
+fn describe(event: &CoreEvent) -> String {
+    match event {
+        CoreEvent::WindowEvent(CoreWindowEvent::Resized(width, height)) => {
+            format!("resized {width} {height}")
+        }
+        CoreEvent::WindowEvent(CoreWindowEvent::CursorMoved(_, x, y)) => {
+            format!("cursor-moved {x} {y}")
+        }
+        CoreEvent::WindowEvent(CoreWindowEvent::MouseClick(_, button, state)) => {
+            format!("mouse-click {button:?} {state:?}")
+        }
+        CoreEvent::WindowEvent(CoreWindowEvent::KeyboardInput(input)) => {
+            format!("key {:?} {:?}", input.virtual_keycode, input.state)
+        }
+        CoreEvent::WindowEvent(CoreWindowEvent::RecievedChar(character)) => {
+            format!("char {character}")
+        }
+        CoreEvent::WindowEvent(CoreWindowEvent::Closing) => "closing".to_string(),
+        CoreEvent::DeviceEvent(CoreDeviceEvent::MouseMotion(dx, dy)) => {
+            format!("mouse-motion {dx} {dy}")
+        }
+        CoreEvent::DeviceEvent(CoreDeviceEvent::Text(character)) => format!("text {character}"),
+        _ => "other".to_string(),
+    }
+}
+
+#[test]
+fn replay_dispatches_events_in_order() {
+    let recording = Recording::parse(
+        "cgraph-recording 1\n\
+         0 window resized 640 480\n\
+         1000 window cursor-moved 10 20\n\
+         2000 device mouse-motion 1 -2\n\
+         3000 window mouse-click left pressed\n\
+         4000 window mouse-click left released\n\
+         5000 window key 30 pressed A\n\
+         5100 window char \"a\"\n\
+         6000 device text \"b\"\n\
+         7000 window closing\n\
+         8000 window cursor-moved 0 0\n",
+    )
+    .unwrap();
+
+    let mut window = Window::headless("Replay", 320, 240, Seen::default());
+    for reference in [
+        CoreEventReference::WindowEvent,
+        CoreEventReference::DeviceEvent,
+    ] {
+        window
+            .subscribe(reference, |seen: &mut Seen, _, event| {
+                seen.events.push(describe(event));
+                Propagation::Continue
+            })
+            .detach();
+    }
+
+    // Closing the window ends the replay, so the last event is never dispatched.
+    assert_eq!(window.replay(&recording), ReplayEnd::Closed);
+    assert_eq!(
+        window.state().events,
+        vec![
+            "resized 640 480",
+            "cursor-moved 10 20",
+            "mouse-motion 1 -2",
+            "mouse-click Left Pressed",
+            "mouse-click Left Released",
+            "key Some(A) Pressed",
+            "char a",
+            "text b",
+            "closing",
+        ]
+    );
+}
@@ -0,0 +1,197 @@
+use cgraph::object::curve::{Curve, CurveEval, Path, Point, SvgPathError};
+
+fn assert_point(actual: Point, x: f32, y: f32) {
+    assert!(
+        (actual.x - x).abs() < 1e-3 && (actual.y - y).abs() < 1e-3,
+        "expected ({x}, {y}), got ({}, {})",
+        actual.x,
+        actual.y
+    );
+}
+
+fn end_points(path: &Path) -> Vec<(f32, f32)> {
+    path.curves
+        .iter()
+        .map(|curve| {
+            let end = curve.end_point();
+            (end.x, end.y)
+        })
+        .collect()
+}
+
+fn assert_same_shape(a: &Path, b: &Path) {
+    assert_eq!(a.curves.len(), b.curves.len());
+    for (a, b) in a.curves.iter().zip(&b.curves) {
+        for t in [0.0, 0.25, 0.5, 0.75, 1.0] {
+            let expected = a.evaluate(t);
+            assert_point(b.evaluate(t), expected.x, expected.y);
+        }
+    }
+}
+
+#[test]
+fn parse_lines() {
+    let path = Path::from_svg_d("M10 10 L20 10 H30 V20 Z").unwrap();
+    assert_eq!(
+        end_points(&path),
+        vec![(20.0, 10.0), (30.0, 10.0), (30.0, 20.0), (10.0, 10.0)]
+    );
+    assert!(matches!(path.curves[0], Curve::Quadratic { .. }));
+}
+
+#[test]
+fn parse_relative_commands() {
+    let path = Path::from_svg_d("m10 10 l10 0 h10 v10 z").unwrap();
+    assert_eq!(
+        end_points(&path),
+        vec![(20.0, 10.0), (30.0, 10.0), (30.0, 20.0), (10.0, 10.0)]
+    );
+
+    // A relative move after a close starts from the start of the closed subpath.
+    let path = Path::from_svg_d("M10 10 L20 10 Z m5 5 l1 0").unwrap();
+    assert_point(path.curves[2].start_point(), 15.0, 15.0);
+}
+
+#[test]
+fn parse_implicit_repeats() {
+    // Coordinates after a move are lines, and other commands repeat with more coordinates.
+    let path = Path::from_svg_d("M0 0 10 0 10 10 L20 10 20 20").unwrap();
+    assert_eq!(
+        end_points(&path),
+        vec![(10.0, 0.0), (10.0, 10.0), (20.0, 10.0), (20.0, 20.0)]
+    );
+
+    let path = Path::from_svg_d("m0 0 10 0 0 10").unwrap();
+    assert_eq!(end_points(&path), vec![(10.0, 0.0), (10.0, 10.0)]);
+}
+
+#[test]
+fn parse_packed_numbers() {
+    let path = Path::from_svg_d("M.5.5l-1e1,2-.5.5").unwrap();
+    assert_eq!(end_points(&path), vec![(-9.5, 2.5), (-10.0, 3.0)]);
+}
+
+#[test]
+fn parse_curves() {
+    let path = Path::from_svg_d("M0 0 C10 0 20 10 20 20 S30 40 40 40").unwrap();
+    let Curve::Cubic { p1, p2, p3, .. } = &path.curves[1] else {
+        panic!("expected a cubic");
+    };
+    // The first control point of `S` reflects the last one of the previous cubic.
+    assert_point(*p1, 20.0, 30.0);
+    assert_point(*p2, 30.0, 40.0);
+    assert_point(*p3, 40.0, 40.0);
+
+    let path = Path::from_svg_d("M0 0 Q10 10 20 0 T40 0").unwrap();
+    let Curve::Quadratic { p1, p2, .. } = &path.curves[1] else {
+        panic!("expected a quadratic");
+    };
+    assert_point(*p1, 30.0, -10.0);
+    assert_point(*p2, 40.0, 0.0);
+
+    // Without a previous curve, `S` and `T` use the current point as the first control point.
+    let path = Path::from_svg_d("M5 5 T15 5").unwrap();
+    let Curve::Quadratic { p1, .. } = &path.curves[0] else {
+        panic!("expected a quadratic");
+    };
+    assert_point(*p1, 5.0, 5.0);
+}
+
+#[test]
+fn parse_arcs() {
+    let path = Path::from_svg_d("M0 0 A50 50 0 0 1 100 0").unwrap();
+    assert!(matches!(path.curves[0], Curve::Arc { .. }));
+    assert_point(path.curves[0].end_point(), 100.0, 0.0);
+    assert_point(path.curves[0].evaluate(0.5), 50.0, -50.0);
+
+    // Elliptical arcs become cubics that still end at the arc's end point.
+    let path = Path::from_svg_d("M0 0 a50 25 30 1 0 100 0").unwrap();
+    assert!(
+        path.curves
+            .iter()
+            .all(|curve| matches!(curve, Curve::Cubic { .. }))
+    );
+    assert_point(path.curves.last().unwrap().end_point(), 100.0, 0.0);
+}
+
+#[test]
+fn parse_packed_arc_flags() {
+    let packed = Path::from_svg_d("M0 0 a50 50 0 0110 10").unwrap();
+    let spaced = Path::from_svg_d("M0 0 a50 50 0 0 1 10 10").unwrap();
+    assert_same_shape(&packed, &spaced);
+    assert_point(packed.curves[0].end_point(), 10.0, 10.0);
+}
+
+#[test]
+fn round_trip() {
+    let data = "M10 10 L20 20 h5 v-5 C30 30 40 40 50 50 S70 70 80 60 Q90 90 100 100 \
+                T120 120 A10 10 0 0 1 140 120 a20 10 30 1 0 20 20 z m1,1 l.5.5-1e1,2";
+    let path = Path::from_svg_d(data).unwrap();
+    let serialized = path.to_svg_d();
+    let parsed = Path::from_svg_d(&serialized).unwrap();
+    assert_same_shape(&path, &parsed);
+    assert_eq!(parsed.to_svg_d(), serialized);
+}
+
+#[test]
+fn serialize() {
+    let path = Path::from_svg_d("M0 0 L10 0 L10 10 Z M20 20 Q30 30 40 20").unwrap();
+    assert_eq!(
+        path.to_svg_d(),
+        "M0 0 L10 0 L10 10 L0 0 Z M20 20 Q30 30 40 20"
+    );
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        Path::from_svg_d("M10 x").err(),
+        Some(SvgPathError::ExpectedNumber {
+            offset: 4,
+            found: Some('x'),
+        })
+    );
+    assert_eq!(
+        Path::from_svg_d("M10").err(),
+        Some(SvgPathError::ExpectedNumber {
+            offset: 3,
+            found: None,
+        })
+    );
+    assert_eq!(
+        Path::from_svg_d("10 10").err(),
+        Some(SvgPathError::ExpectedCommand {
+            offset: 0,
+            found: Some('1'),
+        })
+    );
+    assert_eq!(
+        Path::from_svg_d("M0 0 Z 5").err(),
+        Some(SvgPathError::ExpectedCommand {
+            offset: 7,
+            found: Some('5'),
+        })
+    );
+    assert_eq!(
+        Path::from_svg_d("M0 0 a5 5 0 2 1 10 10").err(),
+        Some(SvgPathError::ExpectedFlag {
+            offset: 12,
+            found: Some('2'),
+        })
+    );
+    assert_eq!(
+        Path::from_svg_d("M0 0 X5").err(),
+        Some(SvgPathError::UnsupportedCommand {
+            offset: 5,
+            command: 'X',
+        })
+    );
+    assert_eq!(
+        SvgPathError::ExpectedNumber {
+            offset: 3,
+            found: None,
+        }
+        .to_string(),
+        "expected a number at 3, found the end of the data"
+    );
+}
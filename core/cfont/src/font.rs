@@ -1,13 +1,19 @@
+/// Module with the errors returned when loading fonts and shaping text.
+pub mod error;
+/// Module to pick other fonts for the characters a font doesn't have.
+pub mod fallback;
 /// Module to load and shape fonts.
 pub mod load;
+/// Module with the fonts registered by the application, which lookups consult before the system.
+pub mod registry;
 /// Module to produce text geometries from shaped text.
 pub mod shape;
 /// Module to handle font styles.
 pub mod style;
 
+use error::FontError;
 use load::get_system_font_with_style;
 use shape::{TextGeometry, TextTransform, produce_styled_text_with_family_name};
-use std::error::Error;
 use style::TextStyle;
 
 /// High-level function to render styled text with a system font.
@@ -23,14 +29,13 @@ pub fn render_text(
     family_name: &str,
     text: &str,
     transform: TextTransform,
-) -> Result<TextGeometry, Box<dyn Error>> {
+) -> Result<TextGeometry, FontError> {
     let font = get_system_font_with_style(family_name, &transform.style)?;
     let mut geometry =
         produce_styled_text_with_family_name(font, text, &transform.style, family_name)?;
 
-    // Apply the transform to position and scale the text
-    let font_units_per_em = 1000.0; // Default value, could be extracted from font metrics
-    geometry.transform_to_canvas(transform.clone(), font_units_per_em);
+    // Apply the transform to position and scale the text with the font's own metrics
+    geometry.transform_to_canvas(transform.clone());
     geometry.normalize_to_canvas(transform.canvas_size);
 
     Ok(geometry)
@@ -43,7 +48,7 @@ pub fn render_simple_text(
     font_size: f32,
     position: [f32; 2],
     canvas_size: [f32; 2],
-) -> Result<TextGeometry, Box<dyn Error>> {
+) -> Result<TextGeometry, FontError> {
     let transform = TextTransform {
         font_size,
         position,
@@ -0,0 +1,29 @@
+use font_kit::error::{FontLoadingError, GlyphLoadingError, SelectionError};
+use lyon::tessellation::TessellationError;
+use thiserror::Error;
+
+/// Errors returned when loading fonts or shaping text.
+#[derive(Debug, Error)]
+pub enum FontError {
+    /// No installed font matches the requested name or family.
+    #[error("font not found: {0}")]
+    NotFound(#[from] SelectionError),
+    /// The font file could not be read.
+    #[error("cannot read font file: {0}")]
+    Io(#[from] std::io::Error),
+    /// The font data could not be loaded.
+    #[error("cannot load font: {0}")]
+    Load(#[from] FontLoadingError),
+    /// The font data is not a font rustybuzz can shape with.
+    #[error("invalid font data")]
+    InvalidData,
+    /// The font was created without a loaded face.
+    #[error("font is not loaded")]
+    NotLoaded,
+    /// The outline of a glyph could not be read.
+    #[error("cannot load glyph: {0}")]
+    Glyph(#[from] GlyphLoadingError),
+    /// The outline of a glyph could not be tessellated.
+    #[error("cannot tessellate glyph: {0}")]
+    Tessellation(#[from] TessellationError),
+}
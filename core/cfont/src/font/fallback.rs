@@ -0,0 +1,266 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ops::Range,
+    rc::Rc,
+    sync::{
+        Mutex, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use font_kit::properties::Properties;
+use rustybuzz::{Face, ttf_parser::OutlineBuilder};
+
+use crate::font::load::{Font, find_font};
+
+static FALLBACK_FAMILIES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+// Bumped whenever the fonts a family resolves to may have changed, emptying every thread's cache.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+// A family with the bits of a weight and a style.
+type FontKey = (String, u32, u8);
+
+// Fallback fonts by family and style, from the generation they were loaded in. Families that
+// failed to load are kept as `None` so they aren't looked up again until the fonts change.
+#[derive(Default)]
+struct Cache {
+    generation: u64,
+    fonts: HashMap<FontKey, Option<Rc<Font>>>,
+}
+
+thread_local! {
+    static LOADED: RefCell<Cache> = RefCell::new(Cache::default());
+}
+
+/// Forgets the fallback fonts loaded by every thread, after the registered fonts changed.
+pub(crate) fn invalidate_cache() {
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Sets the families tried, in order, for the characters a font doesn't have, before the system
+/// fallbacks for the character's script.
+pub fn set_fallback_families(families: &[&str]) {
+    *FALLBACK_FAMILIES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) =
+        families.iter().map(ToString::to_string).collect();
+    invalidate_cache();
+}
+
+/// Returns the families tried for the characters a font doesn't have, before the system fallbacks.
+pub fn fallback_families() -> Vec<String> {
+    FALLBACK_FAMILIES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// A part of a text drawn with a single font, or with the requested font if `font` is `None`.
+pub(crate) struct Run {
+    pub(crate) range: Range<usize>,
+    pub(crate) font: Option<Rc<Font>>,
+}
+
+/// Splits a text into runs of characters that the same font can draw, starting with the
+/// requested font and falling back to fonts with the requested `properties`.
+pub(crate) fn segment(text: &str, face: &Face, properties: &Properties) -> Vec<Run> {
+    let mut runs: Vec<Run> = Vec::new();
+    let mut fallbacks: HashMap<char, Option<Rc<Font>>> = HashMap::new();
+
+    for (offset, character) in text.char_indices() {
+        let end = offset + character.len_utf8();
+        let font = match runs.last_mut() {
+            // Marks, joiners and variation selectors belong to the character before them.
+            Some(run) if extends_cluster(character) => {
+                run.range.end = end;
+                continue;
+            }
+            _ if has_outline(face, character) => None,
+            _ => fallbacks
+                .entry(character)
+                .or_insert_with(|| fallback_for(character, properties))
+                .clone(),
+        };
+        match runs.last_mut() {
+            Some(run) if same_font(&run.font, &font) => run.range.end = end,
+            _ => runs.push(Run {
+                range: offset..end,
+                font,
+            }),
+        }
+    }
+    runs
+}
+
+/// Returns whether the font can draw the character. Glyphs without an outline, like bitmap emoji,
+/// can't be tessellated, so they count as missing.
+fn has_outline(face: &Face, character: char) -> bool {
+    let Some(glyph) = face.glyph_index(character) else {
+        return false;
+    };
+    character.is_whitespace()
+        || character.is_control()
+        || face.outline_glyph(glyph, &mut NoOutline).is_some()
+}
+
+struct NoOutline;
+
+impl OutlineBuilder for NoOutline {
+    fn move_to(&mut self, _x: f32, _y: f32) {}
+    fn line_to(&mut self, _x: f32, _y: f32) {}
+    fn quad_to(&mut self, _x1: f32, _y1: f32, _x: f32, _y: f32) {}
+    fn curve_to(&mut self, _x1: f32, _y1: f32, _x2: f32, _y2: f32, _x: f32, _y: f32) {}
+    fn close(&mut self) {}
+}
+
+/// Picks the first fallback font with an outline for the character, skipping fonts that only have
+/// a bitmap of it.
+fn fallback_for(character: char, properties: &Properties) -> Option<Rc<Font>> {
+    let families = fallback_families().into_iter().chain(
+        system_fallbacks(Script::of(character))
+            .iter()
+            .map(ToString::to_string),
+    );
+    for family in families {
+        let Some(font) = load(&family, properties) else {
+            continue;
+        };
+        let Some(face) = Face::from_slice(&font.bytes, font.index) else {
+            continue;
+        };
+        if has_outline(&face, character) {
+            return Some(font);
+        }
+    }
+    None
+}
+
+fn load(family: &str, properties: &Properties) -> Option<Rc<Font>> {
+    let key = (
+        family.to_string(),
+        properties.weight.0.to_bits(),
+        properties.style as u8,
+    );
+    LOADED.with(|loaded| {
+        let mut cache = loaded.borrow_mut();
+        let generation = GENERATION.load(Ordering::Relaxed);
+        if cache.generation != generation {
+            *cache = Cache {
+                generation,
+                fonts: HashMap::new(),
+            };
+        }
+        cache
+            .fonts
+            .entry(key)
+            .or_insert_with(|| find_font(family, properties).ok().map(Rc::new))
+            .clone()
+    })
+}
+
+fn same_font(a: &Option<Rc<Font>>, b: &Option<Rc<Font>>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+        _ => false,
+    }
+}
+
+fn extends_cluster(character: char) -> bool {
+    matches!(
+        character as u32,
+        0x0300..=0x036F // Combining diacritical marks
+            | 0x200C..=0x200D // Zero-width joiners
+            | 0x20D0..=0x20FF // Combining marks for symbols, like the keycap
+            | 0xFE00..=0xFE0F // Variation selectors
+            | 0x1F3FB..=0x1F3FF // Emoji skin tones
+            | 0xE0020..=0xE007F // Emoji tags
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Cjk,
+    Emoji,
+    Symbol,
+    Arabic,
+    Hebrew,
+    Devanagari,
+    Thai,
+    Other,
+}
+
+impl Script {
+    fn of(character: char) -> Script {
+        match character as u32 {
+            0x1100..=0x11FF
+            | 0x2E80..=0x9FFF
+            | 0xAC00..=0xD7AF
+            | 0xF900..=0xFAFF
+            | 0xFF00..=0xFFEF
+            | 0x20000..=0x3FFFF => Script::Cjk,
+            0x1F000..=0x1FAFF => Script::Emoji,
+            0x2000..=0x2BFF | 0xE000..=0xF8FF | 0x1D400..=0x1D7FF => Script::Symbol,
+            0x0600..=0x06FF | 0x0750..=0x077F | 0xFB50..=0xFDFF | 0xFE70..=0xFEFF => Script::Arabic,
+            0x0590..=0x05FF => Script::Hebrew,
+            0x0900..=0x097F => Script::Devanagari,
+            0x0E00..=0x0E7F => Script::Thai,
+            _ => Script::Other,
+        }
+    }
+}
+
+/// The system families most likely to have the characters of a script.
+fn system_fallbacks(script: Script) -> &'static [&'static str] {
+    if cfg!(target_os = "macos") {
+        match script {
+            Script::Cjk => &["PingFang SC", "Hiragino Sans", "Apple SD Gothic Neo"],
+            Script::Emoji => &["Apple Color Emoji", "Apple Symbols"],
+            Script::Symbol => &[
+                "Apple Symbols",
+                "Menlo",
+                "STIX Two Math",
+                "Apple Color Emoji",
+            ],
+            Script::Arabic => &["Geeza Pro"],
+            Script::Hebrew => &["Arial Hebrew"],
+            Script::Devanagari => &["Kohinoor Devanagari"],
+            Script::Thai => &["Thonburi"],
+            Script::Other => &["Arial Unicode MS", "Lucida Grande"],
+        }
+    } else if cfg!(target_os = "windows") {
+        match script {
+            Script::Cjk => &["Microsoft YaHei", "Yu Gothic", "Malgun Gothic"],
+            Script::Emoji => &["Segoe UI Emoji", "Segoe UI Symbol"],
+            Script::Symbol => &["Segoe UI Symbol", "Cambria Math", "Segoe UI Emoji"],
+            Script::Arabic | Script::Hebrew => &["Segoe UI"],
+            Script::Devanagari => &["Nirmala UI"],
+            Script::Thai => &["Leelawadee UI"],
+            Script::Other => &["Segoe UI", "Arial Unicode MS"],
+        }
+    } else {
+        match script {
+            Script::Cjk => &[
+                "Noto Sans CJK SC",
+                "Noto Sans CJK JP",
+                "WenQuanYi Micro Hei",
+                "Droid Sans Fallback",
+            ],
+            Script::Emoji => &["Noto Emoji", "Symbola", "Noto Color Emoji"],
+            Script::Symbol => &[
+                "DejaVu Sans",
+                "Noto Sans Symbols",
+                "Noto Sans Symbols 2",
+                "Noto Sans Math",
+                "Symbola",
+            ],
+            Script::Arabic => &["Noto Sans Arabic", "DejaVu Sans"],
+            Script::Hebrew => &["Noto Sans Hebrew", "DejaVu Sans"],
+            Script::Devanagari => &["Noto Sans Devanagari"],
+            Script::Thai => &["Noto Sans Thai"],
+            Script::Other => &["DejaVu Sans", "Noto Sans", "FreeSans"],
+        }
+    }
+}
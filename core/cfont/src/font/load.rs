@@ -1,6 +1,13 @@
-use std::{error::Error, path::PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use crate::font::style::{FontWeight, TextStyle};
+use crate::font::{
+    error::FontError,
+    registry,
+    style::{FontWeight, TextStyle},
+};
 use font_kit::{
     handle::Handle,
     properties::{Properties, Style, Weight},
@@ -16,46 +23,173 @@ pub struct Font {
     pub core_font: Option<font_kit::font::Font>,
     /// Whether the font was loaded from memory.
     pub stored_in_memory: bool,
-    /// The raw bytes of the font file.
-    pub bytes: Vec<u8>,
+    /// The raw bytes of the font file, shared with font-kit and the font registry.
+    pub bytes: Arc<Vec<u8>>,
+    /// The index of the font in its file, which is only above 0 in collections such as `.ttc` files.
+    pub index: u32,
+    /// The vertical metrics of the font, in font units.
+    pub metrics: FontMetrics,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The metrics of a font, in font units. Heights are measured up from the baseline, so the
+/// descent and the underline position are usually negative.
+pub struct FontMetrics {
+    /// The number of font units in an em, usually 1000 for PostScript fonts and 2048 for TrueType fonts.
+    pub units_per_em: f32,
+    /// The height of the font above the baseline.
+    pub ascent: f32,
+    /// The depth of the font below the baseline, as a negative height.
+    pub descent: f32,
+    /// The extra space between the descent of a line and the ascent of the next one.
+    pub line_gap: f32,
+    /// The height of lowercase letters like `x`.
+    pub x_height: f32,
+    /// The height of capital letters like `H`.
+    pub cap_height: f32,
+    /// The height of the top of the underline.
+    pub underline_position: f32,
+    /// The thickness of the underline.
+    pub underline_thickness: f32,
+    /// The height of the top of the strikeout line.
+    pub strikeout_position: f32,
+    /// The thickness of the strikeout line.
+    pub strikeout_thickness: f32,
 }
 
-/// Retrieves a system font by its PostScript name.
-pub fn get_system_font(name: &str) -> Result<Font, Box<dyn Error>> {
-    let source = SystemSource::new();
-    let handle = source.select_by_postscript_name(name)?;
-    let loaded_in_mem: bool;
-    let mut outer_path: Option<PathBuf> = None;
-    let font_bytes: Vec<u8>;
-    match handle.clone() {
-        Handle::Path { path, .. } => {
-            loaded_in_mem = false;
-            outer_path = Some(path.clone());
-            font_bytes = std::fs::read(path).unwrap();
+impl FontMetrics {
+    /// Reads the metrics of a parsed font. Heights missing from older fonts are measured on the
+    /// glyphs of `x` and `H`, and the other missing metrics are estimated from the em size.
+    fn from_face(face: &rustybuzz::ttf_parser::Face) -> FontMetrics {
+        let units_per_em = face.units_per_em() as f32;
+        let glyph_height = |character: char| {
+            let glyph = face.glyph_index(character)?;
+            Some(face.glyph_bounding_box(glyph)?.y_max)
+        };
+        let x_height = face
+            .x_height()
+            .or_else(|| glyph_height('x'))
+            .map_or(units_per_em * 0.5, |height| height as f32);
+        let underline = face.underline_metrics();
+        let strikeout = face.strikeout_metrics();
+        FontMetrics {
+            units_per_em,
+            ascent: face.ascender() as f32,
+            descent: face.descender() as f32,
+            line_gap: face.line_gap() as f32,
+            x_height,
+            cap_height: face
+                .capital_height()
+                .or_else(|| glyph_height('H'))
+                .map_or(units_per_em * 0.7, |height| height as f32),
+            underline_position: underline
+                .map_or(units_per_em * -0.1, |metrics| metrics.position as f32),
+            underline_thickness: underline
+                .map_or(units_per_em * 0.05, |metrics| metrics.thickness as f32),
+            strikeout_position: strikeout.map_or(x_height * 0.5, |metrics| metrics.position as f32),
+            strikeout_thickness: strikeout
+                .map_or(units_per_em * 0.05, |metrics| metrics.thickness as f32),
         }
-        Handle::Memory { bytes, .. } => {
-            loaded_in_mem = true;
-            font_bytes = bytes.to_vec();
+    }
+
+    /// Returns the number of pixels in a font unit when drawing the font at `font_size` pixels.
+    pub fn scale(&self, font_size: f32) -> f32 {
+        font_size / self.units_per_em
+    }
+
+    /// Returns the distance between the baselines of two lines.
+    pub fn line_height(&self) -> f32 {
+        self.ascent - self.descent + self.line_gap
+    }
+}
+
+impl Default for FontMetrics {
+    /// Metrics in proportion to a 1000 units em, for fonts that weren't loaded.
+    fn default() -> Self {
+        FontMetrics {
+            units_per_em: 1000.0,
+            ascent: 800.0,
+            descent: -200.0,
+            line_gap: 0.0,
+            x_height: 500.0,
+            cap_height: 700.0,
+            underline_position: -100.0,
+            underline_thickness: 50.0,
+            strikeout_position: 250.0,
+            strikeout_thickness: 50.0,
         }
     }
-    let font = handle.load()?;
-    Ok(Font {
-        path: outer_path,
-        core_font: Some(font),
-        stored_in_memory: loaded_in_mem,
-        bytes: font_bytes,
-    })
 }
 
-/// Retrieves a system font by family name with specific style properties.
-pub fn get_system_font_with_style(
-    family_name: &str,
-    style: &TextStyle,
-) -> Result<Font, Box<dyn Error>> {
-    let source = SystemSource::new();
+impl Font {
+    /// Loads the first font of a font file.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Font, FontError> {
+        Font::from_path_with_index(path, 0)
+    }
+
+    /// Loads the font at `index` of a font file, such as one of the fonts of a `.ttc` collection.
+    pub fn from_path_with_index(path: impl AsRef<Path>, index: u32) -> Result<Font, FontError> {
+        let path = path.as_ref();
+        let mut font = Font::from_bytes(std::fs::read(path)?, index)?;
+        font.path = Some(path.to_path_buf());
+        font.stored_in_memory = false;
+        Ok(font)
+    }
+
+    /// Loads the font at `index` of the bytes of a font file, such as a font bundled with `include_bytes!`.
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>, index: u32) -> Result<Font, FontError> {
+        Font::from_shared_bytes(Arc::new(bytes.into()), index)
+    }
+
+    /// Loads the font at `index` of bytes shared with other fonts, without copying them.
+    pub fn from_shared_bytes(bytes: Arc<Vec<u8>>, index: u32) -> Result<Font, FontError> {
+        // Shaping reads the font with rustybuzz, so fail here rather than when drawing text.
+        let face = rustybuzz::Face::from_slice(&bytes, index).ok_or(FontError::InvalidData)?;
+        let metrics = FontMetrics::from_face(&face);
+        let core_font = font_kit::font::Font::from_bytes(Arc::clone(&bytes), index)?;
+        Ok(Font {
+            path: None,
+            core_font: Some(core_font),
+            stored_in_memory: true,
+            bytes,
+            index,
+            metrics,
+        })
+    }
+}
+
+/// Retrieves a font by its PostScript name, from the registered fonts first and then the system.
+pub fn get_system_font(name: &str) -> Result<Font, FontError> {
+    if let Some(font) = registry::find_by_postscript_name(name) {
+        return font;
+    }
+    let handle = SystemSource::new().select_by_postscript_name(name)?;
+    load_handle(handle)
+}
 
-    // Convert TextStyle to font-kit Properties
-    let properties = Properties {
+/// Retrieves a font by family name with specific style properties, from the registered fonts
+/// first and then the system.
+pub fn get_system_font_with_style(family_name: &str, style: &TextStyle) -> Result<Font, FontError> {
+    find_font(family_name, &font_properties(style))
+}
+
+/// Finds the font of a family closest to some properties, in the registered fonts and then the system.
+pub(crate) fn find_font(family_name: &str, properties: &Properties) -> Result<Font, FontError> {
+    if let Some(font) = registry::find_by_family(family_name, properties) {
+        return font;
+    }
+
+    let handle = SystemSource::new().select_best_match(
+        &[font_kit::family_name::FamilyName::Title(
+            family_name.to_string(),
+        )],
+        properties,
+    )?;
+    load_handle(handle)
+}
+
+fn font_properties(style: &TextStyle) -> Properties {
+    Properties {
         style: if style.italic {
             Style::Italic
         } else {
@@ -73,36 +207,12 @@ pub fn get_system_font_with_style(
             FontWeight::Black => Weight::BLACK,
         },
         stretch: font_kit::properties::Stretch::NORMAL,
-    };
-
-    let handle = source.select_best_match(
-        &[font_kit::family_name::FamilyName::Title(
-            family_name.to_string(),
-        )],
-        &properties,
-    )?;
-
-    let loaded_in_mem: bool;
-    let mut outer_path: Option<PathBuf> = None;
-    let font_bytes: Vec<u8>;
-
-    match handle.clone() {
-        Handle::Path { path, .. } => {
-            loaded_in_mem = false;
-            outer_path = Some(path.clone());
-            font_bytes = std::fs::read(path)?;
-        }
-        Handle::Memory { bytes, .. } => {
-            loaded_in_mem = true;
-            font_bytes = bytes.to_vec();
-        }
     }
+}
 
-    let font = handle.load()?;
-    Ok(Font {
-        path: outer_path,
-        core_font: Some(font),
-        stored_in_memory: loaded_in_mem,
-        bytes: font_bytes,
-    })
+fn load_handle(handle: Handle) -> Result<Font, FontError> {
+    match handle {
+        Handle::Path { path, font_index } => Font::from_path_with_index(path, font_index),
+        Handle::Memory { bytes, font_index } => Font::from_shared_bytes(bytes, font_index),
+    }
 }
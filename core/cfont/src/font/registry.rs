@@ -0,0 +1,134 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use font_kit::properties::Properties;
+
+use crate::font::{error::FontError, fallback, load::Font};
+
+/// A font registered by the application, kept as bytes so it can be loaded on any thread.
+struct RegisteredFont {
+    family: String,
+    postscript_name: Option<String>,
+    properties: Properties,
+    bytes: Arc<Vec<u8>>,
+    index: u32,
+    path: Option<PathBuf>,
+}
+
+impl RegisteredFont {
+    fn load(&self) -> Result<Font, FontError> {
+        let mut font = Font::from_shared_bytes(Arc::clone(&self.bytes), self.index)?;
+        if let Some(path) = &self.path {
+            font.path = Some(path.clone());
+            font.stored_in_memory = false;
+        }
+        Ok(font)
+    }
+}
+
+static REGISTRY: Mutex<Vec<RegisteredFont>> = Mutex::new(Vec::new());
+
+fn registry() -> MutexGuard<'static, Vec<RegisteredFont>> {
+    REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Registers a loaded font, replacing any registered font with the same PostScript name.
+pub fn register_font(font: &Font) -> Result<(), FontError> {
+    let core_font = font.core_font.as_ref().ok_or(FontError::NotLoaded)?;
+    insert(RegisteredFont {
+        family: core_font.family_name(),
+        postscript_name: core_font.postscript_name(),
+        properties: core_font.properties(),
+        bytes: Arc::clone(&font.bytes),
+        index: font.index,
+        path: font.path.clone(),
+    });
+    Ok(())
+}
+
+/// Registers every font of a font file, including all the fonts of a `.ttc` collection.
+pub fn register_font_file(path: impl AsRef<Path>) -> Result<(), FontError> {
+    let path = path.as_ref();
+    register_collection(std::fs::read(path)?, Some(path))
+}
+
+/// Registers every font of the bytes of a font file, such as a font bundled with `include_bytes!`.
+pub fn register_font_bytes(bytes: impl Into<Vec<u8>>) -> Result<(), FontError> {
+    register_collection(bytes.into(), None)
+}
+
+/// Returns the family names of the registered fonts, without duplicates.
+pub fn registered_families() -> Vec<String> {
+    let mut families: Vec<String> = registry().iter().map(|font| font.family.clone()).collect();
+    families.sort();
+    families.dedup();
+    families
+}
+
+/// Removes every registered font, so lookups only find system fonts.
+pub fn clear_registered_fonts() {
+    registry().clear();
+    fallback::invalidate_cache();
+}
+
+fn register_collection(bytes: Vec<u8>, path: Option<&Path>) -> Result<(), FontError> {
+    let count = ttf_parser::fonts_in_collection(&bytes).unwrap_or(1);
+    let bytes = Arc::new(bytes);
+    // Load every font before registering any, so a broken collection registers nothing.
+    let mut fonts = Vec::new();
+    for index in 0..count {
+        let font = Font::from_shared_bytes(Arc::clone(&bytes), index)?;
+        let core_font = font.core_font.as_ref().ok_or(FontError::NotLoaded)?;
+        fonts.push(RegisteredFont {
+            family: core_font.family_name(),
+            postscript_name: core_font.postscript_name(),
+            properties: core_font.properties(),
+            bytes: bytes.clone(),
+            index,
+            path: path.map(Path::to_path_buf),
+        });
+    }
+    for font in fonts {
+        insert(font);
+    }
+    Ok(())
+}
+
+fn insert(font: RegisteredFont) {
+    let mut registry = registry();
+    if font.postscript_name.is_some() {
+        registry.retain(|registered| registered.postscript_name != font.postscript_name);
+    }
+    registry.push(font);
+    fallback::invalidate_cache();
+}
+
+pub(crate) fn find_by_postscript_name(name: &str) -> Option<Result<Font, FontError>> {
+    registry()
+        .iter()
+        .find(|font| font.postscript_name.as_deref() == Some(name))
+        .map(RegisteredFont::load)
+}
+
+pub(crate) fn find_by_family(
+    family: &str,
+    properties: &Properties,
+) -> Option<Result<Font, FontError>> {
+    // The closest style wins: the right slant first, then the nearest weight.
+    registry()
+        .iter()
+        .filter(|font| font.family.eq_ignore_ascii_case(family))
+        .min_by(|a, b| {
+            let distance = |font: &RegisteredFont| {
+                (
+                    font.properties.style != properties.style,
+                    (font.properties.weight.0 - properties.weight.0).abs(),
+                )
+            };
+            let (a, b) = (distance(a), distance(b));
+            a.0.cmp(&b.0).then(a.1.total_cmp(&b.1))
+        })
+        .map(RegisteredFont::load)
+}
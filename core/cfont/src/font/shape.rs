@@ -1,6 +1,6 @@
-use std::error::Error;
-
-use crate::font::load::Font;
+use crate::font::error::FontError;
+use crate::font::fallback;
+use crate::font::load::{Font, FontMetrics};
 use crate::font::style::TextStyle;
 use font_kit::outline::OutlineSink;
 use lyon::{
@@ -25,6 +25,10 @@ pub struct TextGeometry {
     pub vertices: Vec<TextVertex>,
     /// The indices of the vertices for indexed rendering.
     pub indices: Vec<u16>,
+    /// The metrics of the font the text was shaped with.
+    pub metrics: FontMetrics,
+    /// The distance the text advances the pen, in font units.
+    pub advance: f32,
 }
 
 #[derive(Debug, Clone)]
@@ -63,25 +67,28 @@ impl TextGeometry {
         (min_x, min_y, max_x, max_y)
     }
 
-    /// Transforms the text geometry to fit within the specified canvas size.
-    pub fn transform_to_canvas(&mut self, transform: TextTransform, font_units_per_em: f32) {
-        let (min_x, _min_y, _max_x, max_y) = self.bounding_box();
-
-        let font_scale = transform.font_size / font_units_per_em;
+    /// Transforms the text geometry to pixels, with the pen origin on the left and the top of the
+    /// line at the top, so the baseline sits the ascent of the font below it.
+    pub fn transform_to_canvas(&mut self, transform: TextTransform) {
+        let font_scale = self.metrics.scale(transform.font_size);
+        let ascent = self.metrics.ascent;
 
         for vertex in &mut self.vertices {
             let [x, y] = vertex.position;
-
-            let pixel_x = x * font_scale;
-            let pixel_y = y * font_scale;
-
-            let local_x = pixel_x - (min_x * font_scale);
-            let local_y = (max_y * font_scale) - pixel_y;
-
-            vertex.position = [local_x, local_y];
+            vertex.position = [x * font_scale, (ascent - y) * font_scale];
         }
     }
 
+    /// Returns the size in pixels of the line of text at `font_size`, from its advance and the
+    /// ascent and descent of the font, rather than from the glyph outlines.
+    pub fn line_size(&self, font_size: f32) -> (f32, f32) {
+        let font_scale = self.metrics.scale(font_size);
+        (
+            self.advance * font_scale,
+            (self.metrics.ascent - self.metrics.descent) * font_scale,
+        )
+    }
+
     pub fn normalize_to_canvas(&mut self, canvas_size: [f32; 2]) {
         for vertex in &mut self.vertices {
             vertex.position[0] /= canvas_size[0];
@@ -140,16 +147,6 @@ impl OutlineSink for PathBuilderSink {
     }
 }
 
-/// Extracts font metrics for better character spacing.
-fn get_font_metrics(font: &font_kit::font::Font) -> (f32, f32, f32) {
-    let metrics = font.metrics();
-    let units_per_em = metrics.units_per_em as f32;
-    let line_height = metrics.ascent + metrics.descent + metrics.line_gap;
-    let ascent = metrics.ascent;
-
-    (units_per_em, line_height, ascent)
-}
-
 /// Calculates font-specific spacing multiplier based on font characteristics.
 fn calculate_spacing_multiplier(font_family_name: &str) -> f32 {
     // Font-specific spacing adjustments for fonts known to have spacing issues
@@ -179,7 +176,7 @@ fn calculate_spacing_multiplier(font_family_name: &str) -> f32 {
 }
 
 /// Produces a `TextGeometry` from the given font and text string with font-aware spacing.
-pub fn produce_text(font: Font, text: &str) -> Result<TextGeometry, Box<dyn Error>> {
+pub fn produce_text(font: Font, text: &str) -> Result<TextGeometry, FontError> {
     produce_text_with_family_name(font, text, "")
 }
 
@@ -188,66 +185,77 @@ pub fn produce_text_with_family_name(
     font: Font,
     text: &str,
     font_family_name: &str,
-) -> Result<TextGeometry, Box<dyn Error>> {
-    let cfont_font = font.clone();
-    let face: Result<Face<'_>, Box<dyn Error>> =
-        Face::from_slice(&font.bytes, 0).ok_or_else(|| "Value was none".into());
-
-    let core_font = cfont_font.core_font.unwrap();
-
-    let mut buffer = UnicodeBuffer::new();
-    buffer.push_str(text);
-    let glyph_buffer = rustybuzz::shape(&face?, &[], buffer);
+) -> Result<TextGeometry, FontError> {
+    let face = Face::from_slice(&font.bytes, font.index).ok_or(FontError::InvalidData)?;
+    let core_font = font.core_font.as_ref().ok_or(FontError::NotLoaded)?;
 
     let mut global_geometry: VertexBuffers<[f32; 2], u16> = VertexBuffers::new();
     let mut tess = FillTessellator::new();
 
-    // Extract font metrics for better spacing calculations
-    let (units_per_em, _line_height, _ascent) = get_font_metrics(&core_font);
+    let units_per_em = font.metrics.units_per_em;
 
     // Calculate spacing multiplier based on font characteristics
     let spacing_multiplier = calculate_spacing_multiplier(font_family_name);
 
     let mut cursor_x = 0.0f32;
 
-    for glyph in glyph_buffer
-        .glyph_infos()
-        .iter()
-        .zip(glyph_buffer.glyph_positions())
-    {
-        let (info, pos) = glyph;
-        let gid = info.glyph_id;
-        let x_offset = pos.x_offset as f32;
-        let y_offset = pos.y_offset as f32;
-        let mut x_advance = pos.x_advance as f32;
-
-        // Apply font-specific spacing adjustments
-        if spacing_multiplier != 1.0 {
-            x_advance *= spacing_multiplier;
-
-            // For decorative fonts, also add a small minimum spacing
-            if spacing_multiplier > 1.2 {
-                let min_spacing = units_per_em * 0.05; // 5% of em size as minimum spacing
-                x_advance = x_advance.max(min_spacing);
+    // Characters the font doesn't have are shaped with a fallback font, one run at a time
+    for run in fallback::segment(text, &face, &core_font.properties()) {
+        let (run_face, run_core_font, run_units_per_em, run_spacing) = match &run.font {
+            Some(fallback) => (
+                Face::from_slice(&fallback.bytes, fallback.index).ok_or(FontError::InvalidData)?,
+                fallback.core_font.as_ref().ok_or(FontError::NotLoaded)?,
+                fallback.metrics.units_per_em,
+                1.0,
+            ),
+            None => (face.clone(), core_font, units_per_em, spacing_multiplier),
+        };
+        // Fallback glyphs are scaled to the em size of the requested font
+        let scale = units_per_em / run_units_per_em;
+
+        let mut buffer = UnicodeBuffer::new();
+        buffer.push_str(&text[run.range]);
+        let glyph_buffer = rustybuzz::shape(&run_face, &[], buffer);
+
+        for glyph in glyph_buffer
+            .glyph_infos()
+            .iter()
+            .zip(glyph_buffer.glyph_positions())
+        {
+            let (info, pos) = glyph;
+            let gid = info.glyph_id;
+            let x_offset = pos.x_offset as f32 * scale;
+            let y_offset = pos.y_offset as f32 * scale;
+            let mut x_advance = pos.x_advance as f32 * scale;
+
+            // Apply font-specific spacing adjustments
+            if run_spacing != 1.0 {
+                x_advance *= run_spacing;
+
+                // For decorative fonts, also add a small minimum spacing
+                if run_spacing > 1.2 {
+                    let min_spacing = units_per_em * 0.05; // 5% of em size as minimum spacing
+                    x_advance = x_advance.max(min_spacing);
+                }
             }
-        }
 
-        let mut sink = PathBuilderSink::new();
-        core_font.outline(gid, font_kit::hinting::HintingOptions::None, &mut sink)?;
-        let path = sink.build();
-
-        tess.tessellate_path(
-            &path,
-            &FillOptions::default(),
-            &mut BuffersBuilder::new(&mut global_geometry, |v: FillVertex| {
-                [
-                    v.position().x + cursor_x + x_offset,
-                    v.position().y + y_offset,
-                ]
-            }),
-        )?;
-
-        cursor_x += x_advance;
+            let mut sink = PathBuilderSink::new();
+            run_core_font.outline(gid, font_kit::hinting::HintingOptions::None, &mut sink)?;
+            let path = sink.build();
+
+            tess.tessellate_path(
+                &path,
+                &FillOptions::default(),
+                &mut BuffersBuilder::new(&mut global_geometry, |v: FillVertex| {
+                    [
+                        v.position().x * scale + cursor_x + x_offset,
+                        v.position().y * scale + y_offset,
+                    ]
+                }),
+            )?;
+
+            cursor_x += x_advance;
+        }
     }
 
     let vertices: Vec<TextVertex> = global_geometry
@@ -259,6 +267,8 @@ pub fn produce_text_with_family_name(
     Ok(TextGeometry {
         vertices,
         indices: global_geometry.indices,
+        metrics: font.metrics,
+        advance: cursor_x,
     })
 }
 
@@ -268,7 +278,7 @@ pub fn produce_styled_text(
     font: Font,
     text: &str,
     style: &TextStyle,
-) -> Result<TextGeometry, Box<dyn Error>> {
+) -> Result<TextGeometry, FontError> {
     produce_styled_text_with_family_name(font, text, style, "")
 }
 
@@ -278,7 +288,7 @@ pub fn produce_styled_text_with_family_name(
     text: &str,
     style: &TextStyle,
     font_family_name: &str,
-) -> Result<TextGeometry, Box<dyn Error>> {
+) -> Result<TextGeometry, FontError> {
     let mut geometry = produce_text_with_family_name(font, text, font_family_name)?;
 
     // Apply style-specific modifications
@@ -302,31 +312,28 @@ pub fn produce_styled_text_with_family_name(
     Ok(geometry)
 }
 
-/// Adds underline geometry to existing text geometry.
+/// Adds underline geometry to existing text geometry, where the font places its underline.
 fn add_underline_geometry(geometry: &mut TextGeometry) {
-    let (min_x, min_y, max_x, _) = geometry.bounding_box();
-
-    if geometry.vertices.is_empty() {
+    if geometry.advance <= 0.0 {
         return;
     }
 
-    // Calculate underline position and thickness
-    let underline_y = min_y - 20.0; // Position below text baseline
-    let underline_thickness = 2.0;
+    let underline_y = geometry.metrics.underline_position;
+    let underline_thickness = geometry.metrics.underline_thickness;
 
     // Create underline rectangle vertices
     let underline_vertices = vec![
         TextVertex {
-            position: [min_x, underline_y],
+            position: [0.0, underline_y],
         },
         TextVertex {
-            position: [max_x, underline_y],
+            position: [geometry.advance, underline_y],
         },
         TextVertex {
-            position: [max_x, underline_y - underline_thickness],
+            position: [geometry.advance, underline_y - underline_thickness],
         },
         TextVertex {
-            position: [min_x, underline_y - underline_thickness],
+            position: [0.0, underline_y - underline_thickness],
         },
     ];
 
@@ -10,3 +10,13 @@ fn has_core_font() {
     let font = get_system_font("Arial").unwrap();
     assert!(font.core_font.is_some());
 }
+
+#[test]
+fn has_metrics() {
+    let font = get_system_font("Arial").unwrap();
+    let core_metrics = font.core_font.as_ref().unwrap().metrics();
+    assert_eq!(font.metrics.units_per_em, core_metrics.units_per_em as f32);
+    assert!(font.metrics.ascent > 0.0);
+    assert!(font.metrics.descent < 0.0);
+    assert!(font.metrics.cap_height > font.metrics.x_height);
+}
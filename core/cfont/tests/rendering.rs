@@ -18,6 +18,8 @@ fn render_text() {
         max_y - min_y
     );
     println!("Total vertices: {}", result.vertices.len());
+    assert_eq!(result.metrics, font.metrics);
+    assert!(result.advance > max_x - min_x);
 
     println!("First 3 vertices (font units):");
     for (i, vertex) in result.vertices.iter().take(3).enumerate() {
@@ -27,8 +29,6 @@ fn render_text() {
         );
     }
 
-    let font_units_per_em = 1000.0;
-
     let transform = TextTransform {
         font_size: 48.0,             // 48px font
         position: [100.0, 200.0],    // Position at 100px from left, 200px from top
@@ -36,7 +36,7 @@ fn render_text() {
         style: TextStyle::bold(),
     };
 
-    result.transform_to_canvas(transform.clone(), font_units_per_em);
+    result.transform_to_canvas(transform.clone());
 
     println!("\n=== AFTER TRANSFORMATION ===");
     let (min_x, min_y, max_x, max_y) = result.bounding_box();
@@ -0,0 +1,58 @@
+use outbox::{
+    app::Application,
+    component::{Column, Text},
+    stack,
+    theme::{BUILTIN_THEME_DIR, ColorToken, SpacingToken, TextScale, Theme},
+    window::Window,
+};
+
+// Pass the path of a VS Code `.json` or TextMate `.tmTheme` theme to try your own.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| format!("{BUILTIN_THEME_DIR}/delta-dark.json"));
+    let import = Theme::import(&path)?;
+    for key in &import.unmapped {
+        println!("Unmapped key: {key}");
+    }
+
+    let comment = import
+        .theme
+        .token_style("source.rust comment.line.double-slash");
+    let keyword = import.theme.token_style("source.rust keyword.control");
+
+    let mut app = Application::new("Import Theme", "0.1.0");
+    let mut win = Window::new(&import.theme.name, 800, 600).with_theme(import.theme.clone());
+
+    let mut comment_text = Text::new("// Colors from the theme's token rules").color(
+        comment
+            .foreground
+            .unwrap_or(import.theme.color(ColorToken::MutedForeground)),
+    );
+    if comment.font_style.italic {
+        comment_text = comment_text.italic();
+    }
+    let mut keyword_text = Text::new("fn main() {}").color(
+        keyword
+            .foreground
+            .unwrap_or(import.theme.color(ColorToken::Accent)),
+    );
+    if keyword.font_style.bold {
+        keyword_text = keyword_text.bold();
+    }
+
+    let col: Column = stack!(
+        Text::new(&import.theme.name).scale(TextScale::Title),
+        comment_text,
+        keyword_text,
+    )
+    .with_spacing(SpacingToken::Small)
+    .padding([24.0, 24.0, 24.0, 24.0]);
+
+    win.set_main_view(col);
+
+    app.set_main_window(win);
+    app.run();
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use cgraph::app::{BindingSource, Keymap};
+use outbox::{app::Application, focus::Focus, window::Window};
+
+const DEFAULT_KEYMAP: &str = "
+cmdorctrl+s = editor.save when editorFocus
+cmdorctrl+k cmdorctrl+s = keymap.open
+cmdorctrl+shift+e = focus.explorer
+cmdorctrl+shift+d = focus.editor
+";
+
+fn main() {
+    let mut app = Application::new("Key Bindings", "0.1.0");
+    let mut win = Window::new("Key Bindings", 800, 600);
+
+    let mut keymap = Keymap::parse(DEFAULT_KEYMAP, BindingSource::Default).unwrap();
+    if let Ok(user) = Keymap::load("keymap.txt") {
+        keymap.extend(user);
+    }
+    win.set_keymap(keymap);
+
+    win.add_command("editor.save", |context| context.set_title("Saved"));
+    win.add_command("keymap.open", |context| {
+        context.set_title("Keyboard Shortcuts")
+    });
+    win.add_command("focus.explorer", |context| {
+        Focus::move_to(context, "explorer")
+    });
+    win.add_command("focus.editor", |context| Focus::move_to(context, "editor"));
+    win.focus().focus("editor");
+
+    app.set_main_window(win);
+    app.run();
+}
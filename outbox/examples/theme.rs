@@ -0,0 +1,34 @@
+use outbox::{
+    app::Application,
+    component::{Column, Row, Shape, Text},
+    row, stack,
+    theme::{ColorToken, SpacingToken, TextScale},
+    window::Window,
+};
+
+fn main() {
+    let mut app = Application::new("Theme", "0.1.0");
+    let mut win = Window::new("Theme", 800, 600);
+
+    // Switch the system between light and dark mode while the example runs.
+    let col: Column = stack!(
+        Text::new("Themes").scale(TextScale::Title).bold(),
+        Text::new("Colors, sizes and spacing come from the window's theme."),
+        Text::new("Secondary text").color(ColorToken::MutedForeground),
+        row!(
+            Shape::new_rounded_rectangle(40.0, 40.0, ColorToken::Accent, 8.0),
+            Shape::new_rounded_rectangle(40.0, 40.0, ColorToken::Success, 8.0),
+            Shape::new_rounded_rectangle(40.0, 40.0, ColorToken::Warning, 8.0),
+            Shape::new_rounded_rectangle(40.0, 40.0, ColorToken::Error, 8.0),
+        )
+        .add_spacing(SpacingToken::Small),
+        Text::new("Something went wrong").color(ColorToken::Error),
+    )
+    .with_spacing(SpacingToken::Medium)
+    .padding([24.0, 24.0, 24.0, 24.0]);
+
+    win.set_main_view(col);
+
+    app.set_main_window(win);
+    app.run();
+}
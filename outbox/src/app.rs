@@ -37,10 +37,30 @@ impl Application {
     }
 
     pub fn run(&mut self) {
-        if let Some(main_window) = &mut self.main_window.take() {
-            main_window.launch();
-        } else {
+        if self.main_window.is_none() && self.windows.is_empty() {
             eprintln!("No main window set for the application.");
+            return;
         }
+        let mut app = match cgraph::app::Application::new() {
+            Ok(app) => app,
+            Err(error) => {
+                eprintln!("Failed to start the application: {error}");
+                return;
+            }
+        };
+        for mut window in self
+            .main_window
+            .take()
+            .into_iter()
+            .chain(self.windows.drain(..))
+        {
+            match window.create(&app) {
+                Ok(window) => {
+                    app.add_window(window);
+                }
+                Err(error) => eprintln!("Failed to create window: {error}"),
+            }
+        }
+        app.run();
     }
 }
@@ -1,4 +1,4 @@
-use crate::renderable::Renderable;
+use crate::{renderable::Renderable, theme::Theme};
 
 #[derive(Default)]
 pub struct Empty {}
@@ -8,11 +8,12 @@ impl Renderable for Empty {
         &self,
         _canvas_size: [f32; 2],
         _assigned_position: [f32; 2],
+        _theme: &Theme,
     ) -> Vec<cgraph::object::Object> {
         Vec::new()
     }
 
-    fn get_size(&self) -> [f32; 2] {
+    fn get_size(&self, _theme: &Theme) -> [f32; 2] {
         [0.0, 0.0]
     }
 
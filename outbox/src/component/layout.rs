@@ -1,9 +1,12 @@
-use crate::renderable::Renderable;
+use crate::{
+    renderable::Renderable,
+    theme::{Space, Theme},
+};
 
 #[derive(Default)]
 pub struct Column {
     pub elements: Vec<Box<dyn Renderable>>,
-    spacing: f32,
+    spacing: Space,
     padding: [f32; 4],
 }
 
@@ -12,14 +15,16 @@ impl Renderable for Column {
         &self,
         canvas_size: [f32; 2],
         assigned_position: [f32; 2],
+        theme: &Theme,
     ) -> Vec<cgraph::object::Object> {
         let mut objects = Vec::new();
         let mut position = assigned_position;
+        let spacing = self.spacing.resolve(theme);
 
         for (index, element) in self.elements.iter().enumerate() {
             // Add spacing before each element except the first one
             if index > 0 {
-                position[1] += self.spacing;
+                position[1] += spacing;
             }
 
             let element_objects = element.render(
@@ -28,21 +33,23 @@ impl Renderable for Column {
                     position[0] + element.get_padding()[0],
                     position[1] + element.get_padding()[1],
                 ],
+                theme,
             );
             objects.extend(element_objects);
 
-            let size = element.get_size();
+            let size = element.get_size(theme);
             position[1] += size[1] + element.get_padding()[1] + element.get_padding()[3]; // Move down for the next element
         }
         objects
     }
 
-    fn get_size(&self) -> [f32; 2] {
+    fn get_size(&self, theme: &Theme) -> [f32; 2] {
+        let spacing = self.spacing.resolve(theme);
         let mut y_size = 0.0;
         let mut x_sizes = Vec::new();
 
         for (index, element) in self.elements.iter().enumerate() {
-            let element_size = element.get_size();
+            let element_size = element.get_size(theme);
             let element_padding = element.get_padding();
 
             x_sizes.push(element_size[0] + element_padding[0] + element_padding[2]); // width + left + right padding
@@ -50,7 +57,7 @@ impl Renderable for Column {
 
             // Add spacing between elements (not after the last one)
             if index > 0 {
-                y_size += self.spacing;
+                y_size += spacing;
             }
         }
 
@@ -87,14 +94,14 @@ impl Column {
         self
     }
 
-    pub fn add_spacing(&mut self, spacing: f32) -> &mut dyn Renderable {
-        self.spacing = spacing;
+    pub fn add_spacing(&mut self, spacing: impl Into<Space>) -> &mut dyn Renderable {
+        self.spacing = spacing.into();
         self
     }
 
     /// Chainable version of add_spacing for use in builder pattern
-    pub fn with_spacing(mut self, spacing: f32) -> Self {
-        self.spacing = spacing;
+    pub fn with_spacing(mut self, spacing: impl Into<Space>) -> Self {
+        self.spacing = spacing.into();
         self
     }
 
@@ -174,7 +181,7 @@ macro_rules! stack {
 #[derive(Default)]
 pub struct Row {
     pub elements: Vec<Box<dyn Renderable>>,
-    spacing: f32,
+    spacing: Space,
     padding: [f32; 4],
 }
 
@@ -183,14 +190,16 @@ impl Renderable for Row {
         &self,
         canvas_size: [f32; 2],
         assigned_position: [f32; 2],
+        theme: &Theme,
     ) -> Vec<cgraph::object::Object> {
         let mut objects = Vec::new();
         let mut position = assigned_position;
+        let spacing = self.spacing.resolve(theme);
 
         for (index, element) in self.elements.iter().enumerate() {
             // Add spacing before each element except the first one
             if index > 0 {
-                position[0] += self.spacing;
+                position[0] += spacing;
             }
 
             let element_objects = element.render(
@@ -199,21 +208,23 @@ impl Renderable for Row {
                     position[0] + element.get_padding()[0],
                     position[1] + element.get_padding()[1],
                 ],
+                theme,
             );
             objects.extend(element_objects);
 
-            let size = element.get_size();
+            let size = element.get_size(theme);
             position[0] += size[0] + element.get_padding()[0] + element.get_padding()[2]; // Move right for the next element
         }
         objects
     }
 
-    fn get_size(&self) -> [f32; 2] {
+    fn get_size(&self, theme: &Theme) -> [f32; 2] {
+        let spacing = self.spacing.resolve(theme);
         let mut y_sizes = Vec::new();
         let mut x_size = 0.0;
 
         for (index, element) in self.elements.iter().enumerate() {
-            let element_size = element.get_size();
+            let element_size = element.get_size(theme);
             let element_padding = element.get_padding();
 
             y_sizes.push(element_size[1] + element_padding[1] + element_padding[3]); // height + top + bottom padding
@@ -221,7 +232,7 @@ impl Renderable for Row {
 
             // Add spacing between elements (not after the last one)
             if index > 0 {
-                x_size += self.spacing;
+                x_size += spacing;
             }
         }
 
@@ -258,14 +269,14 @@ impl Row {
         self
     }
 
-    pub fn add_spacing(mut self, spacing: f32) -> Self {
-        self.spacing = spacing;
+    pub fn add_spacing(mut self, spacing: impl Into<Space>) -> Self {
+        self.spacing = spacing.into();
         self
     }
 
     /// Mutable version of add_spacing for when you have a mutable reference
-    pub fn set_spacing(&mut self, spacing: f32) -> &mut Self {
-        self.spacing = spacing;
+    pub fn set_spacing(&mut self, spacing: impl Into<Space>) -> &mut Self {
+        self.spacing = spacing.into();
         self
     }
 
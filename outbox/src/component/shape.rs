@@ -1,18 +1,21 @@
-use cgraph::object::primitives::{Color, Position, Size, create_circle, create_rounded_quad};
+use cgraph::object::primitives::{Position, Size, create_circle, create_rounded_quad};
 
-use crate::renderable::Renderable;
+use crate::{
+    renderable::Renderable,
+    theme::{Fill, Theme},
+};
 
 pub enum Shape {
     Circle {
         radius: f32,
-        color: Color,
+        color: Fill,
         padding: [f32; 4],
     },
     Rectangle {
         width: f32,
         height: f32,
         corner_radius: f32,
-        color: Color,
+        color: Fill,
         padding: [f32; 4],
     },
 }
@@ -52,7 +55,7 @@ impl Renderable for Shape {
         }
     }
 
-    fn get_size(&self) -> [f32; 2] {
+    fn get_size(&self, _theme: &Theme) -> [f32; 2] {
         match self {
             Shape::Circle { radius, .. } => [*radius * 2.0, *radius * 2.0],
             Shape::Rectangle { width, height, .. } => [*width, *height],
@@ -63,13 +66,14 @@ impl Renderable for Shape {
         &self,
         _canvas_size: [f32; 2],
         assigned_position: [f32; 2],
+        theme: &Theme,
     ) -> Vec<cgraph::object::Object> {
         match self {
             Shape::Circle { radius, color, .. } => {
                 let position = [assigned_position[0], assigned_position[1]];
                 vec![create_circle(
                     Size::new(*radius * 2.0, *radius * 2.0),
-                    *color,
+                    color.resolve(theme),
                     2.0,
                     Position::new(position[0], position[1]),
                 )]
@@ -84,7 +88,7 @@ impl Renderable for Shape {
                 let position = [assigned_position[0], assigned_position[1]];
                 vec![create_rounded_quad(
                     Size::new(*width, *height),
-                    *color,
+                    color.resolve(theme),
                     2.0,
                     Position::new(position[0], position[1]),
                     *corner_radius,
@@ -95,12 +99,12 @@ impl Renderable for Shape {
 }
 
 impl Shape {
-    pub fn new_rectangle(width: f32, height: f32, color: Color) -> Self {
+    pub fn new_rectangle(width: f32, height: f32, color: impl Into<Fill>) -> Self {
         Shape::Rectangle {
             width,
             height,
             corner_radius: 0.0,
-            color,
+            color: color.into(),
             padding: [0.0, 0.0, 0.0, 0.0],
         }
     }
@@ -108,27 +112,28 @@ impl Shape {
     pub fn new_rounded_rectangle(
         width: f32,
         height: f32,
-        color: Color,
+        color: impl Into<Fill>,
         corner_radius: f32,
     ) -> Self {
         Shape::Rectangle {
             width,
             height,
             corner_radius,
-            color,
+            color: color.into(),
             padding: [0.0, 0.0, 0.0, 0.0],
         }
     }
 
-    pub fn new_circle(radius: f32, color: Color) -> Self {
+    pub fn new_circle(radius: f32, color: impl Into<Fill>) -> Self {
         Shape::Circle {
             radius,
-            color,
+            color: color.into(),
             padding: [0.0, 0.0, 0.0, 0.0],
         }
     }
 
-    pub fn set_color(mut self, color: Color) -> Self {
+    pub fn set_color(mut self, color: impl Into<Fill>) -> Self {
+        let color = color.into();
         match &mut self {
             Shape::Circle { color: c, .. } => *c = color,
             Shape::Rectangle { color: c, .. } => *c = color,
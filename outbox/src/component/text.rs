@@ -1,16 +1,16 @@
+use std::cell::RefCell;
+
 use cfont::font::{
     load::{Font, get_system_font, get_system_font_with_style},
     shape::{TextTransform, produce_styled_text_with_family_name},
     style::TextStyle,
 };
-use cgraph::{
-    object::primitives::Color,
-    text::{StyledFont, make_styled_text},
-};
+use cgraph::text::{StyledFont, make_styled_text};
 use glam::Vec2;
 
 use crate::{
     renderable::{PaddingDirection, Renderable},
+    theme::{ColorToken, Fill, TextScale, Theme, Typography},
     window::Window,
 };
 
@@ -19,28 +19,43 @@ pub struct Text {
     pub content: String,
     pub font: Font,
     font_family: String,
+    follows_theme_font: bool,
+    // Loading a system font is slow, so the theme's font is only looked up when it changes.
+    theme_font: RefCell<Option<ThemeFont>>,
     font_transform: cfont::font::shape::TextTransform,
+    font_size: Option<f32>,
+    scale: TextScale,
+    color: Fill,
     padding: [f32; 4],
     overrides_position: bool,
     overrided_position: [f32; 2],
 }
 
+/// The font loaded for a theme's font family, or `None` if the family isn't installed.
+#[derive(Clone)]
+struct ThemeFont {
+    family: String,
+    style: TextStyle,
+    font: Option<Font>,
+}
+
 impl Default for Text {
     fn default() -> Self {
         Text {
             content: String::new(),
             font: Font::default(),
-            font_family: if cfg!(target_os = "macos") {
-                "SF Pro".to_string()
-            } else {
-                "Arial".to_string()
-            },
+            font_family: Typography::default().font_family,
+            follows_theme_font: true,
+            theme_font: RefCell::new(None),
             font_transform: TextTransform {
                 canvas_size: [0.0, 0.0],
-                font_size: 16.0,
+                font_size: Typography::default().body,
                 position: [0.0, 0.0],
                 style: TextStyle::new(),
             },
+            font_size: None,
+            scale: TextScale::Body,
+            color: Fill::Token(ColorToken::Foreground),
             padding: [0.0, 0.0, 0.0, 0.0], // [left, top, right, bottom]
             overrides_position: false,
             overrided_position: [0.0, 0.0],
@@ -50,35 +65,14 @@ impl Default for Text {
 
 impl Text {
     pub fn new(content: &str) -> Self {
-        let font_family = if cfg!(target_os = "macos") {
-            "SF Pro"
-        } else {
-            "Arial"
-        };
-
-        let font = get_system_font(font_family).unwrap_or_else(|_| {
-            eprintln!("Failed to load {font_family} font, using default font.");
-            Font::default()
-        });
-
-        Text {
-            content: content.to_string(),
-            font,
-            font_family: font_family.to_string(),
-            font_transform: TextTransform {
-                canvas_size: [0.0, 0.0],
-                font_size: 16.0,
-                position: [0.0, 0.0],
-                style: TextStyle::new(),
-            },
-            padding: [0.0, 0.0, 0.0, 0.0], // [left, top, right, bottom]
-            overrides_position: false,
-            overrided_position: [0.0, 0.0],
-        }
+        Text::with_family(content, &Typography::default().font_family)
     }
 
     pub fn new_default(content: &str, window: &Window) -> Self {
-        let font_family = window.app_font.as_str();
+        Text::with_family(content, &window.theme().typography.font_family)
+    }
+
+    fn with_family(content: &str, font_family: &str) -> Self {
         let font = get_system_font(font_family).unwrap_or_else(|_| {
             eprintln!("Failed to load {font_family} font, using default font.");
             Font::default()
@@ -88,25 +82,19 @@ impl Text {
             content: content.to_string(),
             font,
             font_family: font_family.to_string(),
-            font_transform: TextTransform {
-                canvas_size: [0.0, 0.0],
-                font_size: 16.0,
-                position: [0.0, 0.0],
-                style: TextStyle::new(),
-            },
-            padding: [0.0, 0.0, 0.0, 0.0], // [left, top, right, bottom]
-            overrides_position: false,
-            overrided_position: [0.0, 0.0],
+            ..Text::default()
         }
     }
 
     pub fn set_font(&mut self, font: Font) -> &mut Self {
         self.font = font;
+        self.follows_theme_font = false;
         self
     }
 
     pub fn set_font_by_name(&mut self, name: &str) -> Result<(), Box<dyn std::error::Error>> {
         self.font_family = name.to_string();
+        self.follows_theme_font = false;
         self.font = get_system_font_with_style(&self.font_family, &self.font_transform.style)?;
         Ok(())
     }
@@ -220,10 +208,53 @@ impl Text {
     }
 
     pub fn set_size(mut self, size: f32) -> Self {
-        self.font_transform.font_size = size;
+        self.font_size = Some(size);
         self
     }
 
+    /// Use a size from the theme's typography scale
+    pub fn scale(mut self, scale: TextScale) -> Self {
+        self.scale = scale;
+        self.font_size = None;
+        self
+    }
+
+    pub fn color(mut self, color: impl Into<Fill>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    fn resolve_font(&self, theme: &Theme) -> (Font, String) {
+        let family = &theme.typography.font_family;
+        if !self.follows_theme_font || *family == self.font_family {
+            return (self.font.clone(), self.font_family.clone());
+        }
+        let style = &self.font_transform.style;
+        let mut theme_font = self.theme_font.borrow_mut();
+        let stale = theme_font
+            .as_ref()
+            .is_none_or(|loaded| loaded.family != *family || loaded.style != *style);
+        if stale {
+            *theme_font = Some(ThemeFont {
+                family: family.clone(),
+                style: style.clone(),
+                font: get_system_font_with_style(family, style).ok(),
+            });
+        }
+        match theme_font.as_ref().and_then(|loaded| loaded.font.clone()) {
+            Some(font) => (font, family.clone()),
+            None => (self.font.clone(), self.font_family.clone()),
+        }
+    }
+
+    fn resolve_transform(&self, theme: &Theme) -> TextTransform {
+        let mut transform = self.font_transform.clone();
+        transform.font_size = self
+            .font_size
+            .unwrap_or_else(|| theme.font_size(self.scale));
+        transform
+    }
+
     /// Add padding to text [left, top, right, bottom]
     pub fn padding(mut self, padding: [f32; 4]) -> Self {
         self.padding = padding;
@@ -273,21 +304,23 @@ impl Renderable for Text {
         &self,
         canvas_size: [f32; 2],
         assigned_position: [f32; 2],
+        theme: &Theme,
     ) -> Vec<cgraph::object::Object> {
-        let mut transform = self.font_transform.clone();
+        let mut transform = self.resolve_transform(theme);
         transform.canvas_size = canvas_size;
         transform.position = [0.0, 0.0];
 
+        let (core_font, font_family) = self.resolve_font(theme);
         let styled_font = StyledFont {
-            core_font: self.font.clone(),
+            core_font,
             transform,
-            font_family: self.font_family.clone(),
+            font_family,
         };
 
         let object = make_styled_text(
             styled_font,
             &self.content,
-            Color::new(1.0, 1.0, 1.0, 1.0),
+            self.color.resolve(theme),
             1.0,
             Vec2::new(assigned_position[0], assigned_position[1]),
         );
@@ -300,22 +333,21 @@ impl Renderable for Text {
         vec![object.unwrap()]
     }
 
-    fn get_size(&self) -> [f32; 2] {
+    fn get_size(&self, theme: &Theme) -> [f32; 2] {
+        let transform = self.resolve_transform(theme);
+        let (font, font_family) = self.resolve_font(theme);
         match produce_styled_text_with_family_name(
-            self.font.clone(),
+            font,
             &self.content,
             &self.font_transform.style,
-            &self.font_family,
+            &font_family,
         ) {
-            Ok(mut geometry) => {
-                let font_units_per_em = 1000.0;
-                geometry.transform_to_canvas(self.font_transform.clone(), font_units_per_em);
-
-                let (width, height) = geometry.pixel_dimensions();
-                [width / 2.0, height / 2.0]
+            Ok(geometry) => {
+                let (width, height) = geometry.line_size(transform.font_size);
+                [width, height]
             }
             Err(_) => {
-                let font_size = self.font_transform.font_size;
+                let font_size = transform.font_size;
                 let width = font_size * self.content.len() as f32 * 0.6;
                 let height = font_size;
                 [width, height]
@@ -0,0 +1,50 @@
+use cgraph::app::{KeyContext, WindowContext};
+
+#[derive(Clone, Default)]
+pub struct Focus {
+    focused: Option<String>,
+    keys: KeyContext,
+}
+
+impl Focus {
+    pub fn new() -> Self {
+        Focus::default()
+    }
+
+    pub fn focus(&mut self, name: &str) {
+        if let Some(previous) = self.focused.take() {
+            self.keys.set(&format!("{previous}Focus"), false);
+        }
+        self.keys.set(&format!("{name}Focus"), true);
+        self.focused = Some(name.to_string());
+    }
+
+    pub fn blur(&mut self) {
+        if let Some(previous) = self.focused.take() {
+            self.keys.set(&format!("{previous}Focus"), false);
+        }
+    }
+
+    pub fn focused(&self) -> Option<&str> {
+        self.focused.as_deref()
+    }
+
+    pub fn set_context(&mut self, key: &str, value: bool) {
+        self.keys.set(key, value);
+    }
+
+    pub fn key_context(&self) -> &KeyContext {
+        &self.keys
+    }
+
+    // Moves the focus of a running window, updating the context its key bindings see.
+    pub fn move_to(context: &mut WindowContext, name: &str) {
+        let Some(mut focus) = context.objects.borrow_mut::<Focus>() else {
+            return;
+        };
+        focus.focus(name);
+        if let Some(mut keys) = context.objects.borrow_mut::<KeyContext>() {
+            *keys = focus.key_context().clone();
+        }
+    }
+}
@@ -11,9 +11,13 @@
 pub mod app;
 /// This module contains all the components that can be rendered in Outbox.
 pub mod component;
+/// This module tracks which part of a window has the keyboard focus, for key bindings.
+pub mod focus;
 /// This module contains the `Interactable` trait for handling user interactions with UI components.
 pub mod interactable;
 /// This module defines the `Renderable` trait and related types for rendering UI components.
 pub mod renderable;
+/// This module defines the `Theme` that components resolve their colors, typography and spacing from.
+pub mod theme;
 /// This module contains the `Window` struct and related functionality for creating and managing windows.
 pub mod window;
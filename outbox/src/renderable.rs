@@ -1,3 +1,5 @@
+use crate::theme::Theme;
+
 #[derive(Debug, Clone)]
 pub enum PaddingDirection {
     Top,
@@ -14,11 +16,13 @@ pub trait Renderable {
         &self,
         canvas_size: [f32; 2],
         assigned_position: [f32; 2],
+        theme: &Theme,
     ) -> Vec<cgraph::object::Object> {
         vec![]
     }
 
-    fn get_size(&self) -> [f32; 2] {
+    #[allow(unused_variables)]
+    fn get_size(&self, theme: &Theme) -> [f32; 2] {
         [0.0, 0.0]
     }
     fn get_padding(&self) -> [f32; 4] {
@@ -0,0 +1,337 @@
+use cgraph::object::primitives::Color;
+
+mod import;
+mod json;
+mod plist;
+mod syntax;
+
+pub use import::*;
+pub use syntax::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorToken {
+    Background,
+    Surface,
+    Foreground,
+    MutedForeground,
+    Accent,
+    Border,
+    Selection,
+    Error,
+    Warning,
+    Success,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+    pub background: Color,
+    pub surface: Color,
+    pub foreground: Color,
+    pub muted_foreground: Color,
+    pub accent: Color,
+    pub border: Color,
+    pub selection: Color,
+    pub error: Color,
+    pub warning: Color,
+    pub success: Color,
+}
+
+impl Palette {
+    pub fn light() -> Self {
+        Palette {
+            background: Color::new(0.96, 0.96, 0.96, 1.0),
+            surface: Color::new(1.0, 1.0, 1.0, 1.0),
+            foreground: Color::new(0.1, 0.1, 0.1, 1.0),
+            muted_foreground: Color::new(0.45, 0.45, 0.45, 1.0),
+            accent: Color::new(0.0, 0.48, 1.0, 1.0),
+            border: Color::new(0.82, 0.82, 0.82, 1.0),
+            selection: Color::new(0.0, 0.48, 1.0, 0.25),
+            error: Color::new(0.85, 0.18, 0.18, 1.0),
+            warning: Color::new(0.9, 0.6, 0.0, 1.0),
+            success: Color::new(0.15, 0.65, 0.3, 1.0),
+        }
+    }
+
+    pub fn dark() -> Self {
+        Palette {
+            background: Color::new(0.05, 0.05, 0.05, 1.0),
+            surface: Color::new(0.12, 0.12, 0.12, 1.0),
+            foreground: Color::new(1.0, 1.0, 1.0, 1.0),
+            muted_foreground: Color::new(0.6, 0.6, 0.6, 1.0),
+            accent: Color::new(0.04, 0.52, 1.0, 1.0),
+            border: Color::new(0.25, 0.25, 0.25, 1.0),
+            selection: Color::new(0.04, 0.52, 1.0, 0.35),
+            error: Color::new(1.0, 0.27, 0.23, 1.0),
+            warning: Color::new(1.0, 0.72, 0.0, 1.0),
+            success: Color::new(0.2, 0.78, 0.35, 1.0),
+        }
+    }
+
+    pub fn get(&self, token: ColorToken) -> Color {
+        match token {
+            ColorToken::Background => self.background,
+            ColorToken::Surface => self.surface,
+            ColorToken::Foreground => self.foreground,
+            ColorToken::MutedForeground => self.muted_foreground,
+            ColorToken::Accent => self.accent,
+            ColorToken::Border => self.border,
+            ColorToken::Selection => self.selection,
+            ColorToken::Error => self.error,
+            ColorToken::Warning => self.warning,
+            ColorToken::Success => self.success,
+        }
+    }
+
+    pub fn set(&mut self, token: ColorToken, color: Color) {
+        let slot = match token {
+            ColorToken::Background => &mut self.background,
+            ColorToken::Surface => &mut self.surface,
+            ColorToken::Foreground => &mut self.foreground,
+            ColorToken::MutedForeground => &mut self.muted_foreground,
+            ColorToken::Accent => &mut self.accent,
+            ColorToken::Border => &mut self.border,
+            ColorToken::Selection => &mut self.selection,
+            ColorToken::Error => &mut self.error,
+            ColorToken::Warning => &mut self.warning,
+            ColorToken::Success => &mut self.success,
+        };
+        *slot = color;
+    }
+}
+
+/// A color that is either picked from the theme or fixed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fill {
+    Token(ColorToken),
+    Color(Color),
+}
+
+impl Fill {
+    pub fn resolve(&self, theme: &Theme) -> Color {
+        match self {
+            Fill::Token(token) => theme.palette.get(*token),
+            Fill::Color(color) => *color,
+        }
+    }
+}
+
+impl From<ColorToken> for Fill {
+    fn from(token: ColorToken) -> Self {
+        Fill::Token(token)
+    }
+}
+
+impl From<Color> for Fill {
+    fn from(color: Color) -> Self {
+        Fill::Color(color)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextScale {
+    Caption,
+    Body,
+    Subtitle,
+    Title,
+    Display,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Typography {
+    pub font_family: String,
+    pub caption: f32,
+    pub body: f32,
+    pub subtitle: f32,
+    pub title: f32,
+    pub display: f32,
+}
+
+impl Default for Typography {
+    fn default() -> Self {
+        Typography {
+            font_family: if cfg!(target_os = "macos") {
+                "SF Pro".to_string()
+            } else {
+                "Arial".to_string()
+            },
+            caption: 12.0,
+            body: 16.0,
+            subtitle: 20.0,
+            title: 28.0,
+            display: 40.0,
+        }
+    }
+}
+
+impl Typography {
+    pub fn size(&self, scale: TextScale) -> f32 {
+        match scale {
+            TextScale::Caption => self.caption,
+            TextScale::Body => self.body,
+            TextScale::Subtitle => self.subtitle,
+            TextScale::Title => self.title,
+            TextScale::Display => self.display,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpacingToken {
+    None,
+    ExtraSmall,
+    Small,
+    Medium,
+    Large,
+    ExtraLarge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spacing {
+    pub extra_small: f32,
+    pub small: f32,
+    pub medium: f32,
+    pub large: f32,
+    pub extra_large: f32,
+}
+
+impl Default for Spacing {
+    fn default() -> Self {
+        Spacing {
+            extra_small: 4.0,
+            small: 8.0,
+            medium: 16.0,
+            large: 24.0,
+            extra_large: 32.0,
+        }
+    }
+}
+
+impl Spacing {
+    pub fn get(&self, token: SpacingToken) -> f32 {
+        match token {
+            SpacingToken::None => 0.0,
+            SpacingToken::ExtraSmall => self.extra_small,
+            SpacingToken::Small => self.small,
+            SpacingToken::Medium => self.medium,
+            SpacingToken::Large => self.large,
+            SpacingToken::ExtraLarge => self.extra_large,
+        }
+    }
+}
+
+/// A distance that is either picked from the theme or fixed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Space {
+    Token(SpacingToken),
+    Points(f32),
+}
+
+impl Default for Space {
+    fn default() -> Self {
+        Space::Points(0.0)
+    }
+}
+
+impl Space {
+    pub fn resolve(&self, theme: &Theme) -> f32 {
+        match self {
+            Space::Token(token) => theme.spacing.get(*token),
+            Space::Points(points) => *points,
+        }
+    }
+}
+
+impl From<SpacingToken> for Space {
+    fn from(token: SpacingToken) -> Self {
+        Space::Token(token)
+    }
+}
+
+impl From<f32> for Space {
+    fn from(points: f32) -> Self {
+        Space::Points(points)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    pub palette: Palette,
+    pub typography: Typography,
+    pub spacing: Spacing,
+    /// Token color rules for syntax highlighting, usually imported from an editor theme
+    pub syntax: Vec<TokenRule>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    pub fn light() -> Self {
+        Theme {
+            name: "Light".to_string(),
+            palette: Palette::light(),
+            typography: Typography::default(),
+            spacing: Spacing::default(),
+            syntax: Vec::new(),
+        }
+    }
+
+    pub fn dark() -> Self {
+        Theme {
+            name: "Dark".to_string(),
+            palette: Palette::dark(),
+            typography: Typography::default(),
+            spacing: Spacing::default(),
+            syntax: Vec::new(),
+        }
+    }
+
+    pub fn color(&self, token: ColorToken) -> Color {
+        self.palette.get(token)
+    }
+
+    pub fn font_size(&self, scale: TextScale) -> f32 {
+        self.typography.size(scale)
+    }
+
+    pub fn space(&self, token: SpacingToken) -> f32 {
+        self.spacing.get(token)
+    }
+}
+
+/// The light and dark variants of a theme, switched when the system appearance changes
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeSet {
+    pub light: Theme,
+    pub dark: Theme,
+}
+
+impl Default for ThemeSet {
+    fn default() -> Self {
+        ThemeSet {
+            light: Theme::light(),
+            dark: Theme::dark(),
+        }
+    }
+}
+
+impl ThemeSet {
+    /// Use the same theme in light and dark mode
+    pub fn fixed(theme: Theme) -> Self {
+        ThemeSet {
+            light: theme.clone(),
+            dark: theme,
+        }
+    }
+
+    pub fn get(&self, appearance: winit::window::Theme) -> &Theme {
+        match appearance {
+            winit::window::Theme::Light => &self.light,
+            winit::window::Theme::Dark => &self.dark,
+        }
+    }
+}
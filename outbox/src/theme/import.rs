@@ -0,0 +1,331 @@
+use std::{collections::HashMap, io, path::Path};
+
+use cgraph::object::primitives::Color;
+use thiserror::Error;
+
+use crate::theme::{ColorToken, FontStyle, Theme, TokenRule, json::Json, plist::Plist};
+
+/// The directory holding the themes that ship with Outbox
+pub const BUILTIN_THEME_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/themes");
+
+// VS Code workbench colors, in order of precedence when several map to the same token.
+const WORKBENCH_COLORS: &[(&str, ColorToken)] = &[
+    ("editor.background", ColorToken::Background),
+    ("editor.foreground", ColorToken::Foreground),
+    ("foreground", ColorToken::Foreground),
+    ("editorWidget.background", ColorToken::Surface),
+    ("sideBar.background", ColorToken::Surface),
+    ("descriptionForeground", ColorToken::MutedForeground),
+    ("editorLineNumber.foreground", ColorToken::MutedForeground),
+    ("focusBorder", ColorToken::Accent),
+    ("button.background", ColorToken::Accent),
+    ("contrastBorder", ColorToken::Border),
+    ("editorGroup.border", ColorToken::Border),
+    ("editor.selectionBackground", ColorToken::Selection),
+    ("selection.background", ColorToken::Selection),
+    ("errorForeground", ColorToken::Error),
+    ("editorError.foreground", ColorToken::Error),
+    ("editorWarning.foreground", ColorToken::Warning),
+    ("testing.iconPassed", ColorToken::Success),
+];
+
+// Settings of a rule without a scope, which TextMate uses for the editor's colors.
+const GLOBAL_SETTINGS: &[(&str, ColorToken)] = &[
+    ("background", ColorToken::Background),
+    ("foreground", ColorToken::Foreground),
+    ("lineHighlight", ColorToken::Surface),
+    ("invisibles", ColorToken::MutedForeground),
+    ("caret", ColorToken::Accent),
+    ("guide", ColorToken::Border),
+    ("selection", ColorToken::Selection),
+];
+
+// Keys that describe a theme without changing how it looks, so they aren't reported.
+const METADATA: &[&str] = &[
+    "$schema",
+    "author",
+    "colorSpaceName",
+    "comment",
+    "semanticClass",
+    "type",
+    "uuid",
+];
+
+/// A theme read from an editor theme file, with the keys that have no place in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeImport {
+    /// The imported theme, based on the built-in light or dark theme for the colors it lacks
+    pub theme: Theme,
+    /// Paths of the ignored keys, like `colors.activityBar.background`
+    pub unmapped: Vec<String>,
+}
+
+/// Why a theme file could not be imported
+#[derive(Debug, Error)]
+pub enum ThemeImportError {
+    /// The file could not be read
+    #[error("Cannot read the theme: {0}")]
+    Io(#[from] io::Error),
+    /// The file is not valid JSON or property list, with the line of the error
+    #[error("Invalid theme file: {0}")]
+    Syntax(String),
+    /// The file parsed, but doesn't have the shape of a theme
+    #[error("Unsupported theme: {0}")]
+    Format(String),
+}
+
+impl Theme {
+    /// Import a `.tmTheme` file, or a VS Code JSON theme for any other extension
+    pub fn import(path: impl AsRef<Path>) -> Result<ThemeImport, ThemeImportError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        let is_tmtheme = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("tmTheme"));
+        if is_tmtheme {
+            Theme::from_tmtheme(&text)
+        } else {
+            Theme::from_vscode(&text)
+        }
+    }
+
+    /// Import a VS Code color theme, which may have comments and trailing commas
+    ///
+    /// `colors` fill the palette and `tokenColors` become syntax rules; the other keys are
+    /// listed in `unmapped`.
+    pub fn from_vscode(text: &str) -> Result<ThemeImport, ThemeImportError> {
+        let json = Json::parse(text).map_err(ThemeImportError::Syntax)?;
+        let root = json
+            .as_object()
+            .ok_or_else(|| ThemeImportError::Format("expected an object".to_string()))?;
+
+        let mut importer = Importer::default();
+        for (key, value) in root {
+            match key.as_str() {
+                "name" => importer.name = value.as_str().map(str::to_string),
+                "type" => {
+                    importer.light = value
+                        .as_str()
+                        .map(|kind| kind == "light" || kind == "hcLight");
+                }
+                "colors" => importer.workbench_colors(value),
+                "tokenColors" => importer.token_colors(key, value),
+                key if METADATA.contains(&key) => {}
+                key => importer.unmapped.push(key.to_string()),
+            }
+        }
+        Ok(importer.finish())
+    }
+
+    /// Import a TextMate `.tmTheme` property list
+    ///
+    /// The rule without a scope fills the palette and the others become syntax rules; the other
+    /// keys are listed in `unmapped`.
+    pub fn from_tmtheme(text: &str) -> Result<ThemeImport, ThemeImportError> {
+        let plist = Plist::parse(text)
+            .map_err(ThemeImportError::Syntax)?
+            .to_json();
+        let root = plist
+            .as_object()
+            .ok_or_else(|| ThemeImportError::Format("expected a dictionary".to_string()))?;
+
+        let mut importer = Importer::default();
+        for (key, value) in root {
+            match key.as_str() {
+                "name" => importer.name = value.as_str().map(str::to_string),
+                "settings" => importer.token_colors(key, value),
+                key if METADATA.contains(&key) => {}
+                key => importer.unmapped.push(key.to_string()),
+            }
+        }
+        Ok(importer.finish())
+    }
+}
+
+#[derive(Default)]
+struct Importer {
+    name: Option<String>,
+    light: Option<bool>,
+    // The color of each token, with the precedence of the key it came from.
+    colors: HashMap<ColorToken, (usize, Color)>,
+    syntax: Vec<TokenRule>,
+    unmapped: Vec<String>,
+}
+
+impl Importer {
+    fn set_color(&mut self, token: ColorToken, precedence: usize, color: Color) {
+        if self
+            .colors
+            .get(&token)
+            .is_none_or(|(current, _)| precedence < *current)
+        {
+            self.colors.insert(token, (precedence, color));
+        }
+    }
+
+    fn workbench_colors(&mut self, colors: &Json) {
+        let Some(colors) = colors.as_object() else {
+            self.unmapped.push("colors".to_string());
+            return;
+        };
+        for (key, value) in colors {
+            let mapping = WORKBENCH_COLORS
+                .iter()
+                .position(|(name, _)| name == key)
+                .zip(value.as_str().and_then(parse_color));
+            match mapping {
+                Some((precedence, color)) => {
+                    self.set_color(WORKBENCH_COLORS[precedence].1, precedence, color)
+                }
+                None => self.unmapped.push(format!("colors.{key}")),
+            }
+        }
+    }
+
+    fn token_colors(&mut self, path: &str, rules: &Json) {
+        // VS Code themes may point to a TextMate theme instead of listing the rules.
+        let Json::Array(rules) = rules else {
+            self.unmapped.push(path.to_string());
+            return;
+        };
+        for (index, rule) in rules.iter().enumerate() {
+            self.token_rule(&format!("{path}[{index}]"), rule);
+        }
+    }
+
+    fn token_rule(&mut self, path: &str, rule: &Json) {
+        let Some(entries) = rule.as_object() else {
+            self.unmapped.push(path.to_string());
+            return;
+        };
+        let mut token_rule = TokenRule::default();
+        let mut settings = None;
+        for (key, value) in entries {
+            match (key.as_str(), value) {
+                ("name", Json::String(name)) => token_rule.name = Some(name.clone()),
+                ("scope", Json::String(scopes)) => {
+                    token_rule.scopes = scopes
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|scope| !scope.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                }
+                ("scope", Json::Array(scopes)) => {
+                    token_rule.scopes = scopes
+                        .iter()
+                        .filter_map(Json::as_str)
+                        .map(str::to_string)
+                        .collect();
+                }
+                ("settings", Json::Object(entries)) => settings = Some(entries),
+                _ => self.unmapped.push(format!("{path}.{key}")),
+            }
+        }
+        let Some(settings) = settings else {
+            return;
+        };
+
+        let path = format!("{path}.settings");
+        if token_rule.scopes.is_empty() {
+            self.global_settings(&path, settings);
+            return;
+        }
+        for (key, value) in settings {
+            match (key.as_str(), value.as_str()) {
+                ("foreground", Some(color)) if parse_color(color).is_some() => {
+                    token_rule.foreground = parse_color(color);
+                }
+                ("background", Some(color)) if parse_color(color).is_some() => {
+                    token_rule.background = parse_color(color);
+                }
+                ("fontStyle", Some(style)) => {
+                    token_rule.font_style = Some(self.font_style(&path, style));
+                }
+                _ => self.unmapped.push(format!("{path}.{key}")),
+            }
+        }
+        self.syntax.push(token_rule);
+    }
+
+    fn global_settings(&mut self, path: &str, settings: &[(String, Json)]) {
+        for (key, value) in settings {
+            let mapping = GLOBAL_SETTINGS
+                .iter()
+                .position(|(name, _)| name == key)
+                .zip(value.as_str().and_then(parse_color));
+            match mapping {
+                Some((index, color)) => self.set_color(
+                    GLOBAL_SETTINGS[index].1,
+                    WORKBENCH_COLORS.len() + index,
+                    color,
+                ),
+                None => self.unmapped.push(format!("{path}.{key}")),
+            }
+        }
+    }
+
+    fn font_style(&mut self, path: &str, style: &str) -> FontStyle {
+        let mut font_style = FontStyle::default();
+        for word in style.split_whitespace() {
+            match word {
+                "bold" => font_style.bold = true,
+                "italic" => font_style.italic = true,
+                "underline" => font_style.underline = true,
+                "strikethrough" => font_style.strikethrough = true,
+                _ => self.unmapped.push(format!("{path}.fontStyle.{word}")),
+            }
+        }
+        font_style
+    }
+
+    fn finish(self) -> ThemeImport {
+        let light = self.light.unwrap_or_else(|| {
+            self.colors
+                .get(&ColorToken::Background)
+                .is_some_and(|(_, color)| luminance(*color) > 0.5)
+        });
+        let mut theme = if light { Theme::light() } else { Theme::dark() };
+        if let Some(name) = self.name {
+            theme.name = name;
+        }
+        for (token, (_, color)) in self.colors {
+            theme.palette.set(token, color);
+        }
+        theme.syntax = self.syntax;
+        ThemeImport {
+            theme,
+            unmapped: self.unmapped,
+        }
+    }
+}
+
+/// Parse a `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa` color
+fn parse_color(text: &str) -> Option<Color> {
+    let hex = text.strip_prefix('#')?;
+    let channel = |digits: &str| {
+        u8::from_str_radix(digits, 16)
+            .ok()
+            .map(|value| value as f32 / 255.0)
+    };
+    let channels: Option<Vec<f32>> = match hex.len() {
+        3 | 4 => hex
+            .chars()
+            .map(|digit| channel(&digit.to_string().repeat(2)))
+            .collect(),
+        6 | 8 => (0..hex.len())
+            .step_by(2)
+            .map(|index| hex.get(index..index + 2).and_then(channel))
+            .collect(),
+        _ => None,
+    };
+    match channels?.as_slice() {
+        [red, green, blue] => Some(Color::new(*red, *green, *blue, 1.0)),
+        [red, green, blue, alpha] => Some(Color::new(*red, *green, *blue, *alpha)),
+        _ => None,
+    }
+}
+
+fn luminance(color: Color) -> f32 {
+    0.2126 * color.x + 0.7152 * color.y + 0.0722 * color.z
+}
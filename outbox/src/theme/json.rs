@@ -0,0 +1,222 @@
+// A JSON reader for theme files, which may have comments and trailing commas like VS Code's.
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    // Keys keep the order of the file, so reports list them in that order.
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub(crate) fn parse(text: &str) -> Result<Json, String> {
+        let mut parser = Parser {
+            chars: text.chars().collect(),
+            position: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace()?;
+        if parser.position < parser.chars.len() {
+            return Err(parser.error("expected the end of the file"));
+        }
+        Ok(value)
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_object(&self) -> Option<&[(String, Json)]> {
+        match self {
+            Json::Object(entries) => Some(entries),
+            _ => None,
+        }
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> String {
+        let line = self.chars[..self.position.min(self.chars.len())]
+            .iter()
+            .filter(|&&character| character == '\n')
+            .count()
+            + 1;
+        format!("line {line}: {message}")
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        if self.peek() != Some(expected) {
+            return Err(self.error(&format!("expected `{expected}`")));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) -> Result<(), String> {
+        loop {
+            match (self.peek(), self.chars.get(self.position + 1)) {
+                (Some(character), _) if character.is_whitespace() => self.position += 1,
+                (Some('/'), Some('/')) => {
+                    while self.peek().is_some_and(|character| character != '\n') {
+                        self.position += 1;
+                    }
+                }
+                (Some('/'), Some('*')) => {
+                    self.position += 2;
+                    while !(self.peek() == Some('*')
+                        && self.chars.get(self.position + 1) == Some(&'/'))
+                    {
+                        if self.peek().is_none() {
+                            return Err(self.error("unterminated comment"));
+                        }
+                        self.position += 1;
+                    }
+                    self.position += 2;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace()?;
+        match self.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => self.string().map(Json::String),
+            Some(character) if character == '-' || character.is_ascii_digit() => self.number(),
+            Some(_) => self.literal(),
+            None => Err(self.error("unexpected end of file")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, String> {
+        self.expect('{')?;
+        let mut entries = Vec::new();
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some('}') {
+                self.position += 1;
+                return Ok(Json::Object(entries));
+            }
+            let key = self.string()?;
+            self.skip_whitespace()?;
+            self.expect(':')?;
+            entries.push((key, self.value()?));
+            self.skip_whitespace()?;
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some('}') => {}
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, String> {
+        self.expect('[')?;
+        let mut values = Vec::new();
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some(']') {
+                self.position += 1;
+                return Ok(Json::Array(values));
+            }
+            values.push(self.value()?);
+            self.skip_whitespace()?;
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some(']') => {}
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut string = String::new();
+        loop {
+            let Some(character) = self.peek() else {
+                return Err(self.error("unterminated string"));
+            };
+            self.position += 1;
+            match character {
+                '"' => return Ok(string),
+                '\\' => string.push(self.escape()?),
+                _ => string.push(character),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, String> {
+        let Some(character) = self.peek() else {
+            return Err(self.error("unterminated string"));
+        };
+        self.position += 1;
+        Ok(match character {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'u' => {
+                let hex: String = self.chars.iter().skip(self.position).take(4).collect();
+                self.position += 4;
+                u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .unwrap_or(char::REPLACEMENT_CHARACTER)
+            }
+            _ => character,
+        })
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|character| character.is_ascii_digit() || "+-.eE".contains(character))
+        {
+            self.position += 1;
+        }
+        let number: String = self.chars[start..self.position].iter().collect();
+        number
+            .parse()
+            .map(Json::Number)
+            .map_err(|_| self.error(&format!("invalid number `{number}`")))
+    }
+
+    fn literal(&mut self) -> Result<Json, String> {
+        for (word, value) in [
+            ("true", Json::Bool(true)),
+            ("false", Json::Bool(false)),
+            ("null", Json::Null),
+        ] {
+            let end = self.position + word.len();
+            if end <= self.chars.len()
+                && self.chars[self.position..end]
+                    .iter()
+                    .copied()
+                    .eq(word.chars())
+            {
+                self.position = end;
+                return Ok(value);
+            }
+        }
+        Err(self.error("expected a value"))
+    }
+}
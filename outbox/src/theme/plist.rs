@@ -0,0 +1,124 @@
+// A reader for the XML property lists that TextMate themes are written in.
+
+use xml::reader::{EventReader, XmlEvent};
+
+use crate::theme::json::Json;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Plist {
+    String(String),
+    Integer(i64),
+    Real(f64),
+    Bool(bool),
+    Array(Vec<Plist>),
+    // Keys keep the order of the file, so reports list them in that order.
+    Dict(Vec<(String, Plist)>),
+}
+
+impl Plist {
+    pub(crate) fn parse(text: &str) -> Result<Plist, String> {
+        let root = read_element(text)?;
+        if root.name != "plist" {
+            return Err(format!("expected a `plist` element, found `{}`", root.name));
+        }
+        match root.children.as_slice() {
+            [value] => Plist::from_element(value),
+            _ => Err("expected a single value in the `plist` element".to_string()),
+        }
+    }
+
+    pub(crate) fn to_json(&self) -> Json {
+        match self {
+            Plist::String(string) => Json::String(string.clone()),
+            Plist::Integer(integer) => Json::Number(*integer as f64),
+            Plist::Real(real) => Json::Number(*real),
+            Plist::Bool(value) => Json::Bool(*value),
+            Plist::Array(values) => Json::Array(values.iter().map(Plist::to_json).collect()),
+            Plist::Dict(entries) => Json::Object(
+                entries
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.to_json()))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn from_element(element: &Element) -> Result<Plist, String> {
+        Ok(match element.name.as_str() {
+            "string" | "date" | "data" => Plist::String(element.text.clone()),
+            "integer" => Plist::Integer(
+                element
+                    .text
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("invalid integer `{}`", element.text))?,
+            ),
+            "real" => Plist::Real(
+                element
+                    .text
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("invalid real `{}`", element.text))?,
+            ),
+            "true" => Plist::Bool(true),
+            "false" => Plist::Bool(false),
+            "array" => Plist::Array(
+                element
+                    .children
+                    .iter()
+                    .map(Plist::from_element)
+                    .collect::<Result<_, _>>()?,
+            ),
+            "dict" => {
+                let mut entries = Vec::new();
+                let mut children = element.children.iter();
+                while let Some(key) = children.next() {
+                    if key.name != "key" {
+                        return Err(format!("expected a `key` element, found `{}`", key.name));
+                    }
+                    let value = children
+                        .next()
+                        .ok_or_else(|| format!("missing the value of `{}`", key.text))?;
+                    entries.push((key.text.clone(), Plist::from_element(value)?));
+                }
+                Plist::Dict(entries)
+            }
+            name => return Err(format!("unknown element `{name}`")),
+        })
+    }
+}
+
+struct Element {
+    name: String,
+    children: Vec<Element>,
+    text: String,
+}
+
+fn read_element(text: &str) -> Result<Element, String> {
+    let mut stack: Vec<Element> = Vec::new();
+    for event in EventReader::from_str(text) {
+        match event.map_err(|error| error.to_string())? {
+            XmlEvent::StartElement { name, .. } => stack.push(Element {
+                name: name.local_name,
+                children: Vec::new(),
+                text: String::new(),
+            }),
+            XmlEvent::EndElement { .. } => {
+                let element = stack.pop().ok_or("unexpected closing element")?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Ok(element),
+                }
+            }
+            XmlEvent::Characters(characters)
+            | XmlEvent::CData(characters)
+            | XmlEvent::Whitespace(characters) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&characters);
+                }
+            }
+            _ => {}
+        }
+    }
+    Err("unexpected end of file".to_string())
+}
@@ -0,0 +1,102 @@
+use cgraph::object::primitives::Color;
+
+use crate::theme::Theme;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FontStyle {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+}
+
+/// A token color rule, such as an entry of a VS Code theme's `tokenColors`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TokenRule {
+    pub name: Option<String>,
+    /// Scope selectors like `comment` or `source.rust string.quoted`
+    pub scopes: Vec<String>,
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    /// `None` keeps the style of less specific rules, while an empty style clears it
+    pub font_style: Option<FontStyle>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TokenStyle {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub font_style: FontStyle,
+}
+
+impl Theme {
+    /// Style a scope stack such as `source.rust comment.line.double-slash`.
+    /// Each property comes from the most specific rule that sets it, and later rules win ties.
+    pub fn token_style(&self, scope: &str) -> TokenStyle {
+        let scopes: Vec<&str> = scope.split_whitespace().collect();
+        let mut foreground = None;
+        let mut background = None;
+        let mut font_style = None;
+
+        for rule in &self.syntax {
+            let Some(specificity) = rule
+                .scopes
+                .iter()
+                .filter_map(|selector| specificity(selector, &scopes))
+                .max()
+            else {
+                continue;
+            };
+            pick(&mut foreground, rule.foreground, specificity);
+            pick(&mut background, rule.background, specificity);
+            pick(&mut font_style, rule.font_style, specificity);
+        }
+
+        TokenStyle {
+            foreground: foreground.map(|(color, _)| color),
+            background: background.map(|(color, _)| color),
+            font_style: font_style.map(|(style, _)| style).unwrap_or_default(),
+        }
+    }
+}
+
+fn pick<T>(
+    current: &mut Option<(T, (usize, usize))>,
+    value: Option<T>,
+    specificity: (usize, usize),
+) {
+    let Some(value) = value else {
+        return;
+    };
+    if current
+        .as_ref()
+        .is_none_or(|(_, current)| specificity >= *current)
+    {
+        *current = Some((value, specificity));
+    }
+}
+
+/// How closely a selector matches a scope stack, or `None` if it doesn't match.
+/// The last part of the selector has to match the innermost scope and the other parts its
+/// ancestors, in order.
+fn specificity(selector: &str, scopes: &[&str]) -> Option<(usize, usize)> {
+    let parts: Vec<&str> = selector.split_whitespace().collect();
+    let (last, ancestors) = parts.split_last()?;
+    let (scope, outer) = scopes.split_last()?;
+    if !matches_scope(last, scope) {
+        return None;
+    }
+    let mut remaining = outer.iter();
+    for part in ancestors {
+        if !remaining.any(|scope| matches_scope(part, scope)) {
+            return None;
+        }
+    }
+    Some((last.split('.').count(), ancestors.len()))
+}
+
+fn matches_scope(selector: &str, scope: &str) -> bool {
+    scope
+        .strip_prefix(selector)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
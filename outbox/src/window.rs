@@ -1,15 +1,34 @@
-use cgraph::app::WindowOptions;
+use std::{
+    cell::Cell,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
-use crate::renderable::Renderable;
+use cgraph::app::{
+    Commands, CoreEvent, CoreEventReference, CoreWindowEvent, Keymap, Propagation, WindowContext,
+    WindowError, WindowOptions,
+};
+
+use crate::{
+    focus::Focus,
+    renderable::Renderable,
+    theme::{Theme, ThemeSet},
+};
 
 pub struct Window {
     pub title: String,
     pub width: u32,
     pub height: u32,
-    pub app_font: String,
     window: Option<cgraph::app::Window>,
     options: cgraph::app::WindowOptions,
     main_view: Box<dyn Renderable>,
+    focus: Focus,
+    keymap: Keymap,
+    commands: Commands,
+    geometry_path: Option<PathBuf>,
+    themes: ThemeSet,
+    // Shared with the window's theme handler, which updates it when the system appearance changes.
+    appearance: Rc<Cell<winit::window::Theme>>,
 }
 
 impl Window {
@@ -21,7 +40,12 @@ impl Window {
             window: None,
             options: cgraph::app::WindowOptions::default(),
             main_view: Box::new(crate::component::Empty::default()),
-            app_font: "Arial".to_string(),
+            focus: Focus::new(),
+            keymap: Keymap::new(),
+            commands: Commands::new(),
+            geometry_path: None,
+            themes: ThemeSet::default(),
+            appearance: Rc::new(Cell::new(winit::window::Theme::Dark)),
         }
     }
 
@@ -35,25 +59,125 @@ impl Window {
         self
     }
 
+    pub fn with_options(mut self, options: WindowOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Use the same theme in light and dark mode
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.themes = ThemeSet::fixed(theme);
+        self
+    }
+
+    pub fn with_themes(mut self, themes: ThemeSet) -> Self {
+        self.themes = themes;
+        self
+    }
+
+    pub fn theme(&self) -> &Theme {
+        self.themes.get(self.appearance.get())
+    }
+
+    pub fn remember_geometry(mut self, path: impl AsRef<Path>) -> Self {
+        self.geometry_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
     pub fn launch(&mut self) {
-        self.window = Some(cgraph::app::Window::new(
+        let mut window = match cgraph::app::Window::new(
             &self.title,
             self.width,
             self.height,
             Some(self.options.clone()),
-        ));
-        if let Some(mut window) = self.window.take() {
-            let first_padding = self.main_view.get_padding();
-            for view in self.main_view.render(
-                [window.width as f32, window.height as f32],
-                [0.0 + first_padding[0], 0.0 + first_padding[1]],
-            ) {
-                window.add_object(view);
+        ) {
+            Ok(window) => window,
+            Err(error) => {
+                eprintln!("Failed to create window: {error}");
+                return;
             }
-            window.launch();
+        };
+        self.setup(&mut window);
+        if let Err(error) = window.launch() {
+            eprintln!("Failed to launch window: {error}");
         }
     }
 
+    pub(crate) fn create(
+        &mut self,
+        app: &cgraph::app::Application,
+    ) -> Result<cgraph::app::Window, WindowError> {
+        let mut window = app.create_window(
+            &self.title,
+            self.width,
+            self.height,
+            Some(self.options.clone()),
+        )?;
+        self.setup(&mut window);
+        Ok(window)
+    }
+
+    fn setup(&mut self, window: &mut cgraph::app::Window) {
+        if let Some(path) = &self.geometry_path {
+            window.remember_geometry(path);
+        }
+        for conflict in self.keymap.conflicts() {
+            eprintln!("Key binding conflict: {conflict}");
+        }
+        window.share_object(self.focus.key_context().clone());
+        window.share_object(self.focus.clone());
+        window
+            .bind_keymap(
+                std::mem::take(&mut self.keymap),
+                std::mem::take(&mut self.commands),
+            )
+            .detach();
+
+        if let Some(appearance) = window.theme() {
+            self.appearance.set(appearance);
+        }
+        let mut canvas_size = [window.width as f32, window.height as f32];
+        let theme = self.theme();
+        window.set_background_color(theme.palette.background);
+        for view in render_main_view(self.main_view.as_ref(), canvas_size, theme) {
+            window.add_object(view);
+        }
+
+        // Components resolve their styles when rendered, so render them again in the new theme.
+        let themes = self.themes.clone();
+        let main_view = self.main_view.copy();
+        let current = Rc::clone(&self.appearance);
+        window
+            .subscribe(CoreEventReference::WindowEvent, move |_, context, event| {
+                let CoreEvent::WindowEvent(CoreWindowEvent::ThemeChanged(appearance)) = event
+                else {
+                    return Propagation::Continue;
+                };
+                current.set(*appearance);
+                let theme = themes.get(*appearance);
+                context
+                    .renderer
+                    .set_background_color(theme.palette.background);
+                if let Some(window) = &context.window {
+                    let size = window.inner_size().to_logical::<f32>(window.scale_factor());
+                    canvas_size = [size.width, size.height];
+                }
+
+                // Objects other handlers added are kept, and the view keeps its place among them.
+                let views = render_main_view(main_view.as_ref(), canvas_size, theme);
+                let objects = context.renderer.objects_mut();
+                let start = objects
+                    .iter()
+                    .position(|object| object.tag == Some(MAIN_VIEW_TAG))
+                    .unwrap_or(objects.len());
+                objects.retain(|object| object.tag != Some(MAIN_VIEW_TAG));
+                objects.splice(start..start, views);
+                context.request_redraw();
+                Propagation::Continue
+            })
+            .detach();
+    }
+
     pub fn set_title(&mut self, title: &str) {
         self.title = title.to_string();
         if let Some(window) = &mut self.window {
@@ -69,7 +193,38 @@ impl Window {
         }
     }
 
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+    }
+
+    pub fn add_command<F>(&mut self, name: &str, mut handler: F)
+    where
+        F: FnMut(&mut WindowContext) + 'static,
+    {
+        self.commands
+            .register(name, move |_, context| handler(context));
+    }
+
+    pub fn focus(&mut self) -> &mut Focus {
+        &mut self.focus
+    }
+
     pub fn set_main_view<T: Renderable + 'static>(&mut self, view: T) {
         self.main_view = Box::new(view);
     }
 }
+
+/// Tags the objects of the main view, so a theme change replaces only them.
+const MAIN_VIEW_TAG: &str = "outbox.main-view";
+
+fn render_main_view(
+    view: &dyn Renderable,
+    canvas_size: [f32; 2],
+    theme: &Theme,
+) -> Vec<cgraph::object::Object> {
+    let padding = view.get_padding();
+    view.render(canvas_size, [padding[0], padding[1]], theme)
+        .into_iter()
+        .map(|object| object.with_tag(MAIN_VIEW_TAG))
+        .collect()
+}
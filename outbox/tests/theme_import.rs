@@ -0,0 +1,149 @@
+use std::path::Path;
+
+use cgraph::object::primitives::Color;
+use outbox::theme::{BUILTIN_THEME_DIR, FontStyle, Palette, Theme, ThemeImportError};
+
+fn hex(rgba: u32) -> Color {
+    let channel = |shift: u32| ((rgba >> shift) & 0xff) as f32 / 255.0;
+    Color::new(channel(24), channel(16), channel(8), channel(0))
+}
+
+fn builtin(file: &str) -> String {
+    Path::new(BUILTIN_THEME_DIR)
+        .join(file)
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[test]
+fn import_vscode_theme() {
+    let import = Theme::import(builtin("delta-dark.json")).unwrap();
+    let theme = import.theme;
+    assert_eq!(theme.name, "Delta Dark");
+    assert_eq!(
+        theme.palette,
+        Palette {
+            background: hex(0x0d0d0dff),
+            surface: hex(0x1f1f1fff),
+            foreground: hex(0xe6e6e6ff),
+            muted_foreground: hex(0x999999ff),
+            accent: hex(0x0a84ffff),
+            border: hex(0x404040ff),
+            selection: hex(0x0a84ff59),
+            error: hex(0xff453aff),
+            warning: hex(0xffb800ff),
+            success: hex(0x32c759ff),
+        }
+    );
+
+    assert_eq!(theme.syntax.len(), 7);
+    let comments = &theme.syntax[0];
+    assert_eq!(comments.name.as_deref(), Some("Comments"));
+    assert_eq!(
+        comments.scopes,
+        vec!["comment", "punctuation.definition.comment"]
+    );
+    assert_eq!(comments.foreground, Some(hex(0x6a737dff)));
+    assert_eq!(
+        comments.font_style,
+        Some(FontStyle {
+            italic: true,
+            ..FontStyle::default()
+        })
+    );
+    // Scopes listed in one string are split on commas.
+    assert_eq!(theme.syntax[1].scopes, vec!["string", "string.quoted"]);
+    assert_eq!(theme.syntax[1].font_style, None);
+    assert_eq!(
+        theme.syntax[6].font_style,
+        Some(FontStyle {
+            underline: true,
+            strikethrough: true,
+            ..FontStyle::default()
+        })
+    );
+
+    assert_eq!(
+        import.unmapped,
+        vec!["colors.activityBar.background", "semanticHighlighting"]
+    );
+}
+
+#[test]
+fn import_tmtheme() {
+    let import = Theme::import(builtin("delta-light.tmTheme")).unwrap();
+    let theme = import.theme;
+    assert_eq!(theme.name, "Delta Light");
+    // The theme doesn't set the status colors, so they come from the built-in light theme.
+    assert_eq!(
+        theme.palette,
+        Palette {
+            background: hex(0xf5f5f5ff),
+            surface: hex(0xffffffff),
+            foreground: hex(0x1a1a1aff),
+            muted_foreground: hex(0x737373ff),
+            accent: hex(0x007affff),
+            border: hex(0xd1d1d1ff),
+            selection: hex(0x007aff40),
+            ..Palette::light()
+        }
+    );
+
+    // The rule without a scope holds the palette, so it isn't a syntax rule.
+    assert_eq!(theme.syntax.len(), 5);
+    assert_eq!(
+        theme.syntax[0].scopes,
+        vec!["comment", "punctuation.definition.comment"]
+    );
+    assert_eq!(theme.syntax[4].scopes, vec!["source.rust string"]);
+    assert_eq!(theme.syntax[4].foreground, Some(hex(0xd12f1bff)));
+
+    assert_eq!(import.unmapped, vec!["settings[0].settings.findHighlight"]);
+}
+
+#[test]
+fn parse_comments_and_trailing_commas() {
+    let import = Theme::from_vscode(
+        r##"// A theme
+        {
+            /* The name,
+               on two lines */
+            "name": "Commented", // after a value
+            "colors": { "editor.background": "#ffffff", },
+            "tokenColors": [
+                { "scope": "comment", "settings": { "foreground": "#000000" }, },
+            ],
+        }"##,
+    )
+    .unwrap();
+    assert_eq!(import.theme.name, "Commented");
+    assert_eq!(import.theme.palette.background, hex(0xffffffff));
+    assert_eq!(import.theme.syntax.len(), 1);
+    assert!(import.unmapped.is_empty());
+}
+
+#[test]
+fn parse_escapes() {
+    let import =
+        Theme::from_vscode(r#"{ "name": "\"Quoted\" \\ tab\t caf\u00e9 \/ line\n" }"#).unwrap();
+    assert_eq!(import.theme.name, "\"Quoted\" \\ tab\t café / line\n");
+}
+
+#[test]
+fn syntax_errors() {
+    let error = Theme::from_vscode("{\n  \"name\": \"Missing comma\"\n  \"type\": \"dark\"\n}")
+        .unwrap_err();
+    assert!(
+        matches!(&error, ThemeImportError::Syntax(message) if message == "line 3: expected `,` or `}`"),
+        "{error}"
+    );
+
+    let error = Theme::from_vscode("{ /* never closed }").unwrap_err();
+    assert!(
+        matches!(&error, ThemeImportError::Syntax(message) if message.ends_with("unterminated comment")),
+        "{error}"
+    );
+
+    let error = Theme::from_vscode("[]").unwrap_err();
+    assert!(matches!(error, ThemeImportError::Format(_)), "{error}");
+}